@@ -0,0 +1,332 @@
+//! Helpers building common MIP formulations out of plain constraints.
+//!
+//! Every helper returns the auxiliary variables and constraints it introduces,
+//! the caller is responsible for adding the constraints to the problem.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dsl::LpExprNode::ConsCont;
+use dsl::LpExprOp::{Addition, Subtraction};
+use dsl::*;
+
+static AUXILIARY_VARIABLES: AtomicUsize = AtomicUsize::new(0);
+
+/// Unique name for an auxiliary variable, like `prefix_3`
+pub(crate) fn auxiliary_name(prefix: &str) -> String {
+    format!("{}_{}", prefix, AUXILIARY_VARIABLES.fetch_add(1, Ordering::SeqCst))
+}
+
+/// Relaxation of `constraint` allowing it to be violated by up to `slack`
+fn relax(constraint: &LpConstraint, slack: &LpExpression) -> Vec<LpConstraint> {
+    let LpConstraint(lhs, op, rhs) = constraint.generalize();
+    let upper = || lhs.le(&rhs + slack);
+    let lower = || lhs.ge(&rhs - slack);
+    match op {
+        Constraint::LessOrEqual => vec![upper()],
+        Constraint::GreaterOrEqual => vec![lower()],
+        Constraint::Equal => vec![upper(), lower()],
+    }
+}
+
+/// Big-M relaxation of `constraint`: it only holds when `indicator` is 1.
+fn big_m_relaxation(constraint: &LpConstraint, indicator: &LpBinary, big_m: f64) -> Vec<LpConstraint> {
+    relax(constraint, &(big_m * (1 - indicator)))
+}
+
+/// Logical OR of `constraints`, using the big-M formulation.
+///
+/// One binary selector is created per constraint, which is enforced when its selector is 1.
+/// Exactly one selector is 1. `big_m` must be at least the largest violation of any
+/// constraint over the feasible region, and as small as possible to keep the relaxation tight.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref x = LpContinuous::new("x");
+/// let mut problem = LpProblem::new("Disjunction", LpObjective::Maximize);
+/// problem += x;
+/// problem += x.le(9);
+///
+/// // x <= 2 or x >= 12
+/// let (selectors, constraints) = disjunction(vec![x.le(2), x.ge(12)], 100.0);
+/// for constraint in constraints {
+///     problem += constraint;
+/// }
+/// assert_eq!(selectors.len(), 2);
+/// ```
+pub fn disjunction(constraints: Vec<LpConstraint>, big_m: f64) -> (Vec<LpBinary>, Vec<LpConstraint>) {
+    let selectors: Vec<LpBinary> = constraints
+        .iter()
+        .map(|_| LpBinary::new(&auxiliary_name("disjunction")))
+        .collect();
+    let mut relaxed: Vec<LpConstraint> = constraints
+        .iter()
+        .zip(&selectors)
+        .flat_map(|(constraint, selector)| big_m_relaxation(constraint, selector, big_m))
+        .collect();
+    relaxed.push(lp_sum(&selectors).equal(1));
+    (selectors, relaxed)
+}
+
+/// Either `first` or `second` holds, using the big-M formulation with a single binary selector.
+///
+/// `first` is enforced when the selector is 1, being relaxed by `M (1 - z)`,
+/// and `second` when it is 0, being relaxed by `M z`.
+///
+/// `big_m` must be at least the largest violation of both constraints over the feasible region,
+/// otherwise feasible solutions are cut off. Choosing it much larger than needed weakens the
+/// relaxation and makes the solver sensitive to its integrality tolerance: with `M = 1e9`,
+/// a selector of `1e-9` is considered integral while already relaxing the constraint by 1.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref start_a = LpContinuous::new("start_a");
+/// let ref start_b = LpContinuous::new("start_b");
+/// let mut problem = LpProblem::new("Scheduling", LpObjective::Minimize);
+///
+/// // task a (duration 3) runs before task b, or b (duration 5) runs before a
+/// let (a_first, constraints) = either_or((start_a + 3).le(start_b), (start_b + 5).le(start_a), 100.0);
+/// for constraint in constraints {
+///     problem += constraint;
+/// }
+/// ```
+pub fn either_or(first: LpConstraint, second: LpConstraint, big_m: f64) -> (LpBinary, Vec<LpConstraint>) {
+    let selector = LpBinary::new(&auxiliary_name("either"));
+    let mut constraints = big_m_relaxation(&first, &selector, big_m);
+    constraints.extend(relax(&second, &(big_m * &selector)));
+    (selector, constraints)
+}
+
+/// Piecewise-linear function of `x` going through the `breakpoints` `(x_i, y_i)`,
+/// given by increasing `x_i`.
+///
+/// Returns the variable standing for the function value, the constraints tying `x` and the value
+/// to a convex combination of the breakpoints, and the SOS2 set of the weights of this combination.
+/// `x` is restricted to `[x_0, x_n]`.
+/// The SOS2 set makes the formulation exact; it can be left out of the problem when a convex
+/// function is minimized (or a concave one maximized), the constraints alone being exact then.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref x = LpContinuous::new("x");
+/// let mut problem = LpProblem::new("Cost curve", LpObjective::Minimize);
+///
+/// let (cost, constraints, sos) = piecewise_linear(x, &[(0.0, 0.0), (10.0, 5.0), (20.0, 20.0)]);
+/// problem += &cost;
+/// for constraint in constraints {
+///     problem += constraint;
+/// }
+/// problem += sos;
+/// ```
+pub fn piecewise_linear<T>(x: &T, breakpoints: &[(f64, f64)]) -> (LpContinuous, Vec<LpConstraint>, LpSos2)
+where
+    T: Into<LpExpression> + Clone,
+{
+    let value = LpContinuous::new(&auxiliary_name("pwl"));
+    let weights: Vec<LpContinuous> = (0..breakpoints.len())
+        .map(|i| LpContinuous::new(&format!("{}_lambda_{}", value.name, i)).lower_bound(0.0))
+        .collect();
+    let combination = |coordinate: &dyn Fn(&(f64, f64)) -> f64| {
+        let terms: Vec<LpExpression> = weights
+            .iter()
+            .zip(breakpoints)
+            .map(|(weight, point)| coordinate(point) * weight)
+            .collect();
+        lp_sum(&terms)
+    };
+    let x: LpExpression = x.clone().into();
+    let constraints = vec![
+        lp_sum(&weights).equal(1),
+        x.equal(combination(&|&(x, _)| x)),
+        value.equal(combination(&|&(_, y)| y)),
+    ];
+    let sos = LpSos2(
+        weights
+            .iter()
+            .cloned()
+            .zip(breakpoints.iter().map(|&(x, _)| x))
+            .collect(),
+    );
+    (value, constraints, sos)
+}
+
+/// Step function of `x`, worth `values[k]` when `x` lies in `[breakpoints[k], breakpoints[k + 1]]`.
+///
+/// One binary selects each step, exactly one step is selected and `x` is bound to its interval.
+/// Returns the expression of the function value, the step selectors and the constraints.
+/// At a breakpoint both adjacent steps are allowed, and the solver picks the best one.
+///
+/// # Panics
+///
+/// If there is not exactly one more breakpoint than values.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref x = LpContinuous::new("x");
+/// let mut problem = LpProblem::new("Tariff", LpObjective::Minimize);
+///
+/// let (cost, steps, constraints) = step_function(x, &[0.0, 10.0, 20.0], &[5.0, 3.0]);
+/// problem += cost;
+/// for constraint in constraints {
+///     problem += constraint;
+/// }
+/// assert_eq!(steps.len(), 2);
+/// ```
+pub fn step_function<T>(
+    x: &T,
+    breakpoints: &[f64],
+    values: &[f64],
+) -> (LpExpression, Vec<LpBinary>, Vec<LpConstraint>)
+where
+    T: Into<LpExpression> + Clone,
+{
+    assert_eq!(
+        breakpoints.len(),
+        values.len() + 1,
+        "A step function needs one more breakpoint than values"
+    );
+    let steps: Vec<LpBinary> = values
+        .iter()
+        .map(|_| LpBinary::new(&auxiliary_name("step")))
+        .collect();
+    let weighted = |weights: &[f64]| {
+        let terms: Vec<LpExpression> = steps.iter().zip(weights).map(|(step, &w)| w * step).collect();
+        lp_sum(&terms)
+    };
+    let x: LpExpression = x.clone().into();
+    let constraints = vec![
+        lp_sum(&steps).equal(1),
+        x.ge(weighted(&breakpoints[..values.len()])),
+        x.le(weighted(&breakpoints[1..])),
+    ];
+    (weighted(values), steps, constraints)
+}
+
+/// Assignment problem: assign each worker `i` to exactly one task `j` and each task to exactly
+/// one worker, at the minimal total cost `costs[i][j]`.
+///
+/// Returns the problem and the binary variables `assign_i_j`, set to 1 when worker `i` is
+/// assigned to task `j`. The constraint matrix being totally unimodular, the LP relaxation
+/// of the problem already has an integral optimal vertex.
+///
+/// # Panics
+///
+/// If the cost matrix is not square.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let (problem, assign) = assignment(&[vec![4.0, 1.0], vec![2.0, 3.0]]);
+/// assert_eq!(problem.constraints.len(), 4);
+/// assert_eq!(assign[0][1].name, "assign_0_1");
+/// ```
+pub fn assignment(costs: &[Vec<f64>]) -> (LpProblem, Vec<Vec<LpBinary>>) {
+    let n = costs.len();
+    assert!(
+        costs.iter().all(|row| row.len() == n),
+        "The cost matrix of an assignment problem must be square"
+    );
+    let assign: Vec<Vec<LpBinary>> = (0..n)
+        .map(|i| (0..n).map(|j| LpBinary::new(&format!("assign_{}_{}", i, j))).collect())
+        .collect();
+
+    let mut problem = LpProblem::with_capacity("Assignment", LpObjective::Minimize, 2 * n);
+    if n > 0 {
+        problem += lp_sum_iter(
+            costs
+                .iter()
+                .zip(&assign)
+                .flat_map(|(row, vars)| row.iter().zip(vars).map(|(&cost, var)| cost * var)),
+        );
+    }
+    for vars in &assign {
+        problem += lp_sum(vars).equal(1);
+    }
+    for j in 0..n {
+        let column: Vec<&LpBinary> = assign.iter().map(|vars| &vars[j]).collect();
+        problem += lp_sum(&column).equal(1);
+    }
+    (problem, assign)
+}
+
+impl LpExpression {
+    /// Auxiliary variable `t` standing for the maximum of `exprs`, with the constraints `t >= e`
+    /// for every expression `e`.
+    ///
+    /// The constraints only bound `t` from below: the linearization is exact when the
+    /// objective pushes `t` down, e.g. when minimizing `t` or a positive multiple of it.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref a = LpContinuous::new("a");
+    /// let ref b = LpContinuous::new("b");
+    /// let mut problem = LpProblem::new("Minimax", LpObjective::Minimize);
+    ///
+    /// let (t, constraints) = LpExpression::max_of(&[a.into(), 2 * b]);
+    /// problem += &t;
+    /// for constraint in constraints {
+    ///     problem += constraint;
+    /// }
+    /// ```
+    pub fn max_of(exprs: &[LpExpression]) -> (LpContinuous, Vec<LpConstraint>) {
+        let t = LpContinuous::new(&auxiliary_name("max"));
+        let constraints = exprs.iter().map(|e| t.ge(e)).collect();
+        (t, constraints)
+    }
+
+    /// Auxiliary variable `t` standing for the minimum of `exprs`, with the constraints `t <= e`
+    /// for every expression `e`.
+    ///
+    /// The constraints only bound `t` from above: the linearization is exact when the
+    /// objective pushes `t` up, e.g. when maximizing `t` or a positive multiple of it.
+    pub fn min_of(exprs: &[LpExpression]) -> (LpContinuous, Vec<LpConstraint>) {
+        let t = LpContinuous::new(&auxiliary_name("min"));
+        let constraints = exprs.iter().map(|e| t.le(e)).collect();
+        (t, constraints)
+    }
+
+    /// Nonnegative auxiliary variable `t` standing for the absolute value of the expression,
+    /// with the constraints `t >= expr` and `t >= -expr`.
+    ///
+    /// As for `max_of`, the linearization is only exact when the objective pushes `t` down.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let mut problem = LpProblem::new("Distance", LpObjective::Minimize);
+    ///
+    /// let (t, constraints) = (x - 5).abs();
+    /// problem += &t;
+    /// for constraint in constraints {
+    ///     problem += constraint;
+    /// }
+    /// ```
+    pub fn abs(&self) -> (LpContinuous, Vec<LpConstraint>) {
+        let t = LpContinuous::new(&auxiliary_name("abs")).lower_bound(0.0);
+        let var: LpExpression = ConsCont(t.clone()).into();
+        let constraints = vec![
+            var.merge_cloned_arenas(self, Subtraction).ge(0),
+            var.merge_cloned_arenas(self, Addition).ge(0),
+        ];
+        (t, constraints)
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::{Add, Mul, Neg, Sub, AddAssign, SubAssign, MulAssign};
 use dsl::LpExprNode::*;
-use dsl::{Constraint, LpBinary, LpConstraint, LpContinuous, LpExprNode, LpInteger, LpExpression};
+use dsl::{Constraint, LpBinary, LpConstraint, LpContinuous, LpExprNode, LpInteger, LpExpression, LpSemiContinuous};
 use dsl::LpExprOp::{Addition, Subtraction, Multiplication};
 
 /// Operations trait for any type implementing Into<LpExpression> trait
@@ -22,8 +22,7 @@ macro_rules! operations_for_expr {
         {
             type Output = LpExpression;
             fn $f_name(self, not_yet_lp_expr_arena: T) -> LpExpression {
-                let new_lp_expr_arena = self.clone();
-                new_lp_expr_arena.merge_cloned_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
+                self.merge_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
             }
         }
         impl<'a, T> $trait_name<T> for &'a LpExpression
@@ -50,7 +49,8 @@ macro_rules! assign_operations_for_expr {
             T: Into<LpExpression> + Clone,
         {
             fn $f_name(&mut self, rhs: T) {
-                *self = self.merge_cloned_arenas(&rhs.into(), $expr_type)
+                let lhs = std::mem::replace(self, LpExpression::literal(0.0));
+                *self = lhs.merge_arenas(&rhs.into(), $expr_type)
             }
         }
     };
@@ -69,8 +69,8 @@ macro_rules! lpvars_operation_for_intoexpr {
         {
             type Output = LpExpression;
             fn $f_name(self, not_yet_lp_expr_arena: T) -> LpExpression {
-                let new_lp_expr_arena: LpExpression = self.clone().into();
-                new_lp_expr_arena.merge_cloned_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
+                let new_lp_expr_arena: LpExpression = self.into();
+                new_lp_expr_arena.merge_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
             }
         }
         impl<'a, T> $trait_name<T> for &'a $lp_type
@@ -95,6 +95,9 @@ lpvars_operation_for_intoexpr!(Sub, sub, LpInteger, Subtraction);
 lpvars_operation_for_intoexpr!(Mul, mul, LpContinuous, Multiplication);
 lpvars_operation_for_intoexpr!(Add, add, LpContinuous, Addition);
 lpvars_operation_for_intoexpr!(Sub, sub, LpContinuous, Subtraction);
+lpvars_operation_for_intoexpr!(Mul, mul, LpSemiContinuous, Multiplication);
+lpvars_operation_for_intoexpr!(Add, add, LpSemiContinuous, Addition);
+lpvars_operation_for_intoexpr!(Sub, sub, LpSemiContinuous, Subtraction);
 
 /// Macro implementing binary operations for a numeric type
 macro_rules! numeric_operation_for_expr {
@@ -103,7 +106,7 @@ macro_rules! numeric_operation_for_expr {
             type Output = LpExpression;
             fn $f_name(self, lp_expr_arena: LpExpression) -> LpExpression {
                 let new_lp_expr_arena: LpExpression = (self as f64).into();
-                new_lp_expr_arena.merge_cloned_arenas(&lp_expr_arena.clone(), $type_expr)
+                new_lp_expr_arena.merge_arenas(&lp_expr_arena, $type_expr)
             }
         }
         impl<'a> $trait_name<&'a LpExpression> for $num_type {
@@ -183,6 +186,7 @@ macro_rules! neg_operation_for_lpvars {
 neg_operation_for_lpvars!(LpInteger);
 neg_operation_for_lpvars!(LpContinuous);
 neg_operation_for_lpvars!(LpBinary);
+neg_operation_for_lpvars!(LpSemiContinuous);
 
 /// Macro implementing binary operations for a numeric type
 macro_rules! numeric_operation_for_lpvars {
@@ -218,6 +222,9 @@ macro_rules! numeric_all_ops_for_lpvars {
         numeric_operation_for_lpvars!($num_type, Sub, sub, Subtraction, LpInteger);
         numeric_operation_for_lpvars!($num_type, Sub, sub, Subtraction, LpBinary);
         numeric_operation_for_lpvars!($num_type, Sub, sub, Subtraction, LpContinuous);
+        numeric_operation_for_lpvars!($num_type, Add, add, Addition, LpSemiContinuous);
+        numeric_operation_for_lpvars!($num_type, Mul, mul, Multiplication, LpSemiContinuous);
+        numeric_operation_for_lpvars!($num_type, Sub, sub, Subtraction, LpSemiContinuous);
     };
 }
 numeric_all_ops_for_lpvars!(i32);
@@ -1,11 +1,19 @@
+#[cfg(all(feature = "uuid", not(target_arch = "wasm32")))]
 extern crate uuid;
 
-use std::collections::HashMap;
-use std::ops::AddAssign;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::ops::{AddAssign, Deref};
+#[cfg(not(all(feature = "uuid", not(target_arch = "wasm32"))))]
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 
+#[cfg(all(feature = "uuid", not(target_arch = "wasm32")))]
 use self::uuid::Uuid;
 use dsl::*;
+use error::LpError;
+use format::lp_format::LpFileFormat;
+use solvers::{Solution, SolverTrait, Status};
 
 /// Enum helping to specify the objective function of the linear problem.
 ///
@@ -16,7 +24,7 @@ use dsl::*;
 ///
 /// let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum LpObjective {
     Minimize,
     Maximize,
@@ -27,11 +35,104 @@ pub trait Problem {
     fn add_constraints(&mut self, contraint_expr: &LpConstraint);
 }
 
+/// Likely modeling mistake reported by `LpProblem::validate`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelWarning {
+    /// Variable without bound in the direction improving the objective: the problem is likely unbounded
+    UnboundedVariable(String),
+    /// Constraint without any variable, named as in the LP file, always satisfied by its constants
+    EmptyConstraint(String),
+    /// Constraint without any variable, named as in the LP file, violated by its constants:
+    /// the problem is infeasible
+    ViolatedConstraint(String),
+    /// Variable appearing in the problem, but only with zero coefficients
+    UnusedVariable(String),
+    /// Problem without any constraint nor variable in its objective, see `LpProblem::check_not_empty`
+    EmptyProblem,
+    /// Variable given to `LpProblem::declare`, but found neither in the objective nor in a constraint
+    UnusedDeclaredVariable(String),
+}
+
+impl fmt::Display for ModelWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModelWarning::UnboundedVariable(name) => {
+                write!(f, "Variable {} is not bounded in the direction improving the objective", name)
+            }
+            ModelWarning::EmptyConstraint(name) => {
+                write!(f, "Constraint {} has no variable and is always satisfied", name)
+            }
+            ModelWarning::ViolatedConstraint(name) => {
+                write!(f, "Constraint {} has no variable and can never be satisfied", name)
+            }
+            ModelWarning::UnusedVariable(name) => write!(f, "Variable {} only has zero coefficients", name),
+            ModelWarning::EmptyProblem => write!(f, "The problem has no constraint and no variable in its objective"),
+            ModelWarning::UnusedDeclaredVariable(name) => {
+                write!(f, "Variable {} is declared but used neither in the objective nor in a constraint", name)
+            }
+        }
+    }
+}
+
+/// Batch of updates applied in one pass by `LpProblem::apply_updates`
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::ProblemUpdate;
+///
+/// let mut update = ProblemUpdate::default();
+/// update.objective_coefficients.insert("x".to_string(), 2.0);
+/// update.bounds.insert("y".to_string(), (Some(0.0), Some(10.0)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProblemUpdate {
+    /// New objective coefficients, by variable name
+    pub objective_coefficients: HashMap<String, f64>,
+    /// New lower and upper bounds of integer or continuous variables, by variable name
+    pub bounds: HashMap<String, (Option<f64>, Option<f64>)>,
+}
+
+/// Dense matrix form of a problem, as returned by `LpProblem::to_standard_form`:
+/// optimize `c x + objective_constant` subject to `a[i] x senses[i] b[i]` for every row `i`,
+/// and `bounds[j].0 <= x[j] <= bounds[j].1` for every column `j`.
+///
+/// Columns are the variables sorted by name, rows are the constraints in insertion order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandardForm {
+    pub c: Vec<f64>,
+    pub objective_constant: f64,
+    pub a: Vec<Vec<f64>>,
+    pub b: Vec<f64>,
+    pub senses: Vec<Constraint>,
+    pub var_names: Vec<String>,
+    /// Lower and upper bound of every column, `None` if unbounded
+    pub bounds: Vec<(Option<f64>, Option<f64>)>,
+}
+
+/// Constraint matrix of a problem in compressed sparse column format,
+/// as returned by `LpProblem::to_sparse`.
+///
+/// The nonzero coefficients of column `j` are `values[col_ptr[j]..col_ptr[j + 1]]`,
+/// in the rows `row_indices[col_ptr[j]..col_ptr[j + 1]]`.
+/// Columns are the variables sorted by name, rows are the constraints in insertion order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix {
+    pub row_indices: Vec<usize>,
+    pub col_ptr: Vec<usize>,
+    pub values: Vec<f64>,
+    pub var_names: Vec<String>,
+    /// Constraint names, as in the LP file
+    pub row_names: Vec<String>,
+}
+
 /// Structure used for creating the model and solving a linear problem.
 ///
 /// # Examples:
 ///
 /// ```
+/// # #[cfg(feature = "cbc")]
+/// # fn main() {
 /// use lp_modeler::dsl::*;
 /// use lp_modeler::solvers::{SolverTrait, CbcSolver, Solution};
 ///
@@ -57,29 +158,1526 @@ pub trait Problem {
 ///     },
 ///     Err(msg) => println!("{}", msg),
 /// }
+/// # }
+/// # #[cfg(not(feature = "cbc"))]
+/// # fn main() {}
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LpProblem {
     pub name: &'static str,
     pub unique_name: String,
     pub objective_type: LpObjective,
     pub obj_expr_arena: Option<LpExpression>,
+    /// Constant term of the objective, kept apart from `obj_expr_arena`
+    pub objective_constant: f64,
+    /// Name of the objective row in the LP file, `obj` if not set
+    pub objective_name: Option<String>,
+    /// Divide the objective by its largest coefficient magnitude when writing the problem
+    /// for a solver, to avoid numerical warnings with badly-scaled objectives.
+    /// Objective values reported by the solvers are scaled back.
+    pub auto_scale_objective: bool,
+    /// Number of decimals of the coefficients and bounds written in the LP format, as set by
+    /// `with_precision`. The values are written exactly if not set.
+    pub lp_precision: Option<usize>,
     pub constraints: Vec<LpConstraint>,
+    /// Tags given to `add_tagged`, stored with a copy of their constraint so that they follow it
+    /// whatever the changes to `constraints`: identical constraints share their tags.
+    /// They are only kept in memory and never written in the LP file.
+    pub constraint_tags: Vec<(LpConstraint, Vec<String>)>,
+    pub sos2: Vec<LpSos2>,
+    pub metadata: BTreeMap<String, String>,
+    /// Objectives optimized one after the other by `solve_lexicographic`, with their priority
+    pub objectives: Vec<(u32, LpExpression)>,
+    /// Variables given to `declare`, expected in the solutions even if the model does not use them
+    pub declared: Vec<LpExpression>,
+}
+
+/// Index in `LpProblem::constraints` of the constraint named `name` in the LP file, e.g. 1 for `c2`
+fn constraint_index(name: &str) -> Option<usize> {
+    match name.strip_prefix('c').and_then(|number| number.parse::<usize>().ok()) {
+        Some(number) if number >= 1 => Some(number - 1),
+        _ => None,
+    }
+}
+
+pub(crate) fn non_finite_coefficient(name: &str, coefficient: f64) -> LpError {
+    LpError::NonFiniteCoefficient { name: name.to_string(), coefficient }
+}
+
+/// Validated problem with simplified expressions, as returned by `LpProblem::freeze`.
+///
+/// A frozen problem cannot be modified anymore. It dereferences to the underlying `LpProblem`,
+/// so it can be given to the solvers and the writers like any problem.
+#[derive(Debug)]
+pub struct FrozenProblem {
+    problem: LpProblem,
+    warnings: Vec<ModelWarning>,
+}
+
+impl FrozenProblem {
+    pub fn problem(&self) -> &LpProblem {
+        &self.problem
+    }
+
+    /// Variables reported as unbounded by `validate`, which constraints may still bound
+    pub fn warnings(&self) -> &[ModelWarning] {
+        &self.warnings
+    }
+}
+
+impl Deref for FrozenProblem {
+    type Target = LpProblem;
+    fn deref(&self) -> &LpProblem {
+        &self.problem
+    }
+}
+
+/// Suffix making the name of a problem unique, as used for the files exchanged with the solvers
+#[cfg(all(feature = "uuid", not(target_arch = "wasm32")))]
+fn unique_suffix() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Without the process solvers, no file is exchanged and a counter is enough.
+/// No random source is available on wasm32-unknown-unknown anyway.
+#[cfg(not(all(feature = "uuid", not(target_arch = "wasm32"))))]
+fn unique_suffix() -> String {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    NEXT.fetch_add(1, Ordering::Relaxed).to_string()
 }
 
 impl LpProblem {
     /// Create a new problem
     pub fn new(name: &'static str, objective: LpObjective) -> LpProblem {
-        let unique_name = format!("{}_{}", name, Uuid::new_v4());
+        let unique_name = format!("{}_{}", name, unique_suffix());
         LpProblem {
             name,
             unique_name,
             objective_type: objective,
             obj_expr_arena: None,
+            objective_constant: 0.0,
+            objective_name: None,
+            auto_scale_objective: false,
+            lp_precision: None,
             constraints: Vec::new(),
+            constraint_tags: Vec::new(),
+            sos2: Vec::new(),
+            metadata: BTreeMap::new(),
+            objectives: Vec::new(),
+            declared: Vec::new(),
+        }
+    }
+
+    /// Create a new problem with room for `num_constraints` constraints
+    pub fn with_capacity(name: &'static str, objective: LpObjective, num_constraints: usize) -> LpProblem {
+        let mut problem = LpProblem::new(name, objective);
+        problem.constraints.reserve_exact(num_constraints);
+        problem
+    }
+
+    /// Create a problem with a zero objective, to find any point satisfying its constraints.
+    /// The solvers report it `Optimal` with a feasible assignment, or `Infeasible`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # #[cfg(feature = "minilp")]
+    /// # fn main() {
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::solvers::{MiniLpSolver, SolverTrait, Status};
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let ref y = LpContinuous::new("y");
+    /// let mut problem = LpProblem::feasibility("Satisfiable");
+    /// problem += (x + y).ge(2);
+    /// problem += (x - y).equal(1);
+    ///
+    /// let solution = MiniLpSolver::new().run(&problem).unwrap();
+    /// assert_eq!(solution.status, Status::Optimal);
+    /// assert!(solution.results["x"] + solution.results["y"] >= 2.0 - 1e-9);
+    /// # }
+    /// # #[cfg(not(feature = "minilp"))]
+    /// # fn main() {}
+    /// ```
+    pub fn feasibility(name: &'static str) -> LpProblem {
+        let mut problem = LpProblem::new(name, LpObjective::Minimize);
+        problem += 0.0;
+        problem
+    }
+
+    pub fn set_objective_constant(&mut self, constant: f64) {
+        self.objective_constant = constant;
+    }
+
+    pub fn set_objective_name(&mut self, name: &str) {
+        self.objective_name = Some(name.to_string());
+    }
+
+    /// Write the coefficients and bounds rounded to `decimals` decimals in the LP format,
+    /// e.g. to keep the files of successive runs comparable
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::format::lp_format::LpFileFormat;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let mut problem = LpProblem::new("Rounded", LpObjective::Maximize).with_precision(2);
+    /// problem += (1.0 / 3.0) * x;
+    /// assert!(problem.to_lp_file_format().contains("obj: 0.33 x"));
+    /// ```
+    pub fn with_precision(self, decimals: usize) -> LpProblem {
+        LpProblem {
+            lp_precision: Some(decimals),
+            ..self
+        }
+    }
+
+    /// Add an objective expression, as `+=` does, so that a problem can be built in one expression
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let ref y = LpContinuous::new("y");
+    /// let problem = LpProblem::new("Fluent", LpObjective::Maximize)
+    ///     .with_objective(x + 2 * y)
+    ///     .with_constraint((x + y).le(10))
+    ///     .with_constraint(x.ge(y));
+    /// assert_eq!(problem.constraints.len(), 2);
+    /// ```
+    pub fn with_objective<T: Into<LpExpression>>(mut self, objective: T) -> LpProblem {
+        self += objective;
+        self
+    }
+
+    /// Add a constraint, as `+=` does, see `with_objective`
+    pub fn with_constraint(mut self, constraint: LpConstraint) -> LpProblem {
+        self += constraint;
+        self
+    }
+
+    /// Factor the objective is divided by when written for a solver: the largest magnitude
+    /// of its coefficients if `auto_scale_objective` is set, 1 otherwise.
+    /// A nonlinear objective, which the solvers reject, is not scaled.
+    pub fn objective_scale(&self) -> f64 {
+        if !self.auto_scale_objective {
+            return 1.0;
+        }
+        let largest = self
+            .obj_expr_arena
+            .as_ref()
+            .and_then(|o| o.linear_terms().ok())
+            .map(|(terms, _)| terms.values().fold(0.0, |m: f64, c| m.max(c.abs())))
+            .unwrap_or(0.0);
+        if largest > 0.0 {
+            largest
+        } else {
+            1.0
+        }
+    }
+
+    /// Attach a key-value pair to the problem, e.g. a scenario id.
+    /// Metadata are written as comments in the LP file, their line breaks escaped as `\n`.
+    pub fn set_metadata(&mut self, key: &str, value: &str) {
+        self.metadata.insert(key.to_string(), value.to_string());
+    }
+
+    pub fn get_metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(|v| v.as_str())
+    }
+
+
+    /// Replace every continuous variable without lower bound by the difference
+    /// `x_pos - x_neg` of two nonnegative variables, in the objective and the constraints.
+    /// The upper bound of a split variable becomes a constraint.
+    ///
+    /// Returns the pair of variables replacing each split variable, by name:
+    /// the value of `x` is recovered from a solution as `x_pos - x_neg`.
+    pub fn split_free_variables(&mut self) -> HashMap<String, (LpContinuous, LpContinuous)> {
+        let mut variables = HashMap::new();
+        for expr in self.obj_expr_arena.iter().chain(
+            self.constraints
+                .iter()
+                .flat_map(|LpConstraint(lhs, _, rhs)| vec![lhs, rhs]),
+        ) {
+            for (name, var) in expr.continuous_variables() {
+                variables.entry(name).or_insert(var);
+            }
+        }
+
+        let mut substitutes = HashMap::new();
+        let mut upper_bounds = Vec::new();
+        for (name, var) in variables {
+            if var.lower_bound.is_some() {
+                continue;
+            }
+            let positive = LpContinuous::new(&format!("{}_pos", name)).lower_bound(0.0);
+            let negative = LpContinuous::new(&format!("{}_neg", name)).lower_bound(0.0);
+            if let Some(upper) = var.upper_bound {
+                upper_bounds.push((&positive - &negative).le(upper));
+            }
+            substitutes.insert(name, (positive, negative));
+        }
+
+        if let Some(objective) = self.obj_expr_arena.as_mut() {
+            objective.substitute_differences(&substitutes);
+        }
+        for LpConstraint(lhs, _, rhs) in self.all_constraints_mut() {
+            lhs.substitute_differences(&substitutes);
+            rhs.substitute_differences(&substitutes);
+        }
+        self.constraints.extend(upper_bounds);
+        substitutes
+    }
+
+    /// LP relaxation of the problem: a copy where every integer, binary and semi-continuous
+    /// variable is continuous, a binary variable being bounded by 0 and 1.
+    ///
+    /// The optimum of the relaxation bounds the one of the problem, e.g. to estimate the
+    /// integrality gap of a MIP solution.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::format::lp_format::LpFileFormat;
+    ///
+    /// let ref x = LpBinary::new("x");
+    /// let mut problem = LpProblem::new("Knapsack", LpObjective::Maximize);
+    /// problem += x;
+    /// problem += (2 * x).le(1);
+    ///
+    /// let relaxed = problem.relax().to_lp_file_format();
+    /// assert!(relaxed.contains("0 <= x <= 1"));
+    /// assert!(!relaxed.contains("Binary"));
+    /// ```
+    pub fn relax(&self) -> LpProblem {
+        let mut relaxed = self.clone();
+        relaxed.unique_name = format!("{}_{}", self.name, unique_suffix());
+        for expr in relaxed
+            .obj_expr_arena
+            .iter_mut()
+            .chain(relaxed.constraints.iter_mut().flat_map(|LpConstraint(lhs, _, rhs)| vec![lhs, rhs]))
+            .chain(relaxed.constraint_tags.iter_mut().flat_map(|(LpConstraint(lhs, _, rhs), _)| vec![lhs, rhs]))
+            .chain(relaxed.objectives.iter_mut().map(|(_, objective)| objective))
+        {
+            expr.relax_variables();
+        }
+        relaxed
+    }
+
+    /// Copy of the problem for the solvers without semi-continuous variables: every
+    /// semi-continuous variable `x` becomes continuous between 0 and its upper bound, switched
+    /// on by a new binary variable `x_on` with the constraints `x >= lower x_on` and
+    /// `x <= upper x_on`. The indicator is named `x_on1`, `x_on2`... when the problem already has
+    /// a variable `x_on`. The bounds have to be finite.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::format::lp_format::LpFileFormat;
+    ///
+    /// let ref size = LpSemiContinuous::new("size", 20.0, 100.0);
+    /// let mut problem = LpProblem::new("Facility", LpObjective::Minimize);
+    /// problem += 3 * size;
+    /// problem += size.ge(10);
+    ///
+    /// let lowered = problem.lower_semi_continuous().unwrap();
+    /// assert_eq!(lowered.constraints.len(), 3);
+    /// assert!(lowered.to_lp_file_format().contains("Binary\n  size_on"));
+    /// ```
+    pub fn lower_semi_continuous(&self) -> Result<LpProblem, LpError> {
+        let indicators = self.semi_continuous_indicators();
+        if let Some((var, _)) = indicators.iter().find(|(var, _)| !var.lower.is_finite() || !var.upper.is_finite()) {
+            return Err(LpError::Unsupported(format!(
+                "Semi-continuous variable {} with bounds [{}, {}] cannot be lowered: the bounds have to be finite",
+                var.name, var.lower, var.upper
+            )));
+        }
+        let mut lowered = self.clone();
+        lowered.unique_name = format!("{}_{}", self.name, unique_suffix());
+        for expr in lowered
+            .obj_expr_arena
+            .iter_mut()
+            .chain(lowered.constraints.iter_mut().flat_map(|LpConstraint(lhs, _, rhs)| vec![lhs, rhs]))
+            .chain(lowered.constraint_tags.iter_mut().flat_map(|(LpConstraint(lhs, _, rhs), _)| vec![lhs, rhs]))
+            .chain(lowered.objectives.iter_mut().map(|(_, objective)| objective))
+            .chain(lowered.declared.iter_mut())
+        {
+            expr.replace_semi_continuous();
+        }
+        for (var, on) in &indicators {
+            let x = &var.continuous_hull();
+            lowered += x.ge(var.lower * on);
+            lowered += x.le(var.upper * on);
+        }
+        Ok(lowered)
+    }
+
+    /// Semi-continuous variables with the binary indicator added by `lower_semi_continuous`,
+    /// named after the variable without clashing with the other variables of the problem
+    pub(crate) fn semi_continuous_indicators(&self) -> Vec<(LpSemiContinuous, LpBinary)> {
+        let mut taken: BTreeSet<String> = self.variable_nodes().into_keys().collect();
+        self.semi_continuous_variables()
+            .into_iter()
+            .map(|var| {
+                let mut name = format!("{}_on", var.name);
+                let mut suffix = 1;
+                while taken.contains(&name) {
+                    name = format!("{}_on{}", var.name, suffix);
+                    suffix += 1;
+                }
+                let on = LpBinary::new(&name);
+                taken.insert(name);
+                (var, on)
+            })
+            .collect()
+    }
+
+    /// Semi-continuous variables of the objective and the constraints, sorted by name
+    pub(crate) fn semi_continuous_variables(&self) -> Vec<LpSemiContinuous> {
+        self.variable_nodes()
+            .into_values()
+            .filter_map(|node| match node {
+                LpExprNode::ConsSemiCont(var) => Some(var.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Copy of the problem where every constraint is a `<=` constraint, with the variables on the
+    /// left and the constant on the right, for adapters expecting a single sense: `a >= b + 1` is
+    /// written `-a + b <= -1`, and `a = b + 1` is split into `a - b <= 1` and `-a + b <= -1`.
+    /// The tags of a split constraint are given to both halves.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::format::lp_format::LpFileFormat;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let ref y = LpContinuous::new("y");
+    /// let mut problem = LpProblem::new("Senses", LpObjective::Maximize);
+    /// problem += x + y;
+    /// problem += x.ge(1);
+    /// problem += (x + y).equal(4);
+    ///
+    /// let normalized = problem.to_le_form();
+    /// assert_eq!(normalized.constraints.len(), 3);
+    /// assert!(normalized.constraints.iter().all(|LpConstraint(_, op, _)| *op == Constraint::LessOrEqual));
+    /// ```
+    pub fn to_le_form(&self) -> LpProblem {
+        let mut normalized = self.clone();
+        normalized.unique_name = format!("{}_{}", self.name, unique_suffix());
+        let halves = |constraint: &LpConstraint| {
+            let LpConstraint(lhs, op, rhs) = constraint.generalize();
+            let at_most = LpConstraint(lhs.clone(), Constraint::LessOrEqual, rhs.clone());
+            let at_least = || LpConstraint(lhs.clone(), Constraint::GreaterOrEqual, rhs.clone()).flipped();
+            match op {
+                Constraint::LessOrEqual => vec![at_most],
+                Constraint::GreaterOrEqual => vec![at_least()],
+                Constraint::Equal => vec![at_most, at_least()],
+            }
+        };
+        normalized.constraints = self.constraints.iter().flat_map(halves).collect();
+        normalized.constraint_tags = self
+            .constraint_tags
+            .iter()
+            .flat_map(|(constraint, tags)| halves(constraint).into_iter().map(move |half| (half, tags.clone())))
+            .collect();
+        normalized
+    }
+
+    /// Append the constraints, special ordered sets and objective of `other` to the problem.
+    ///
+    /// Variables of the same name are unified: all their occurrences get the intersection of
+    /// their bounds in both problems. Fails, leaving the problem unchanged, if both problems
+    /// have an objective with different senses, or if the bounds of a variable conflict.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let ref y = LpContinuous::new("y");
+    /// let mut problem = LpProblem::new("Production", LpObjective::Maximize);
+    /// problem += x;
+    /// problem += x.le(4);
+    ///
+    /// let mut transport = LpProblem::new("Transport", LpObjective::Maximize);
+    /// transport += 2 * y;
+    /// transport += (x + y).le(6);
+    ///
+    /// problem.merge(&transport).unwrap();
+    /// assert_eq!(problem.constraints.len(), 2);
+    /// ```
+    pub fn merge(&mut self, other: &LpProblem) -> Result<(), LpError> {
+        let has_objective = |p: &LpProblem| p.obj_expr_arena.is_some() || p.objective_constant != 0.0;
+        let mut merged = self.clone();
+        if has_objective(other) {
+            if !has_objective(self) {
+                merged.objective_type = other.objective_type;
+            } else if self.objective_type != other.objective_type {
+                return Err(LpError::Unsupported(format!(
+                    "Cannot merge the objective of {} into {}: their senses differ",
+                    other.name, self.name
+                )));
+            }
+            if let Some(objective) = &other.obj_expr_arena {
+                merged.add_objective_expression(&mut objective.clone());
+            }
+            merged.objective_constant += other.objective_constant;
+        }
+        merged.constraints.extend(other.constraints.iter().cloned());
+        merged.constraint_tags.extend(other.constraint_tags.iter().cloned());
+        merged.sos2.extend(other.sos2.iter().cloned());
+        merged.objectives.extend(other.objectives.iter().cloned());
+        merged.declared.extend(other.declared.iter().cloned());
+
+        let bounds: HashMap<String, (Option<f64>, Option<f64>)> = merged
+            .intersected_bounds()?
+            .into_iter()
+            .map(|(name, (lower, upper))| {
+                let finite = |bound: f64| if bound.is_infinite() { None } else { Some(bound) };
+                (name.to_string(), (finite(lower), finite(upper)))
+            })
+            .collect();
+        for expr in merged
+            .obj_expr_arena
+            .iter_mut()
+            .chain(merged.constraints.iter_mut().flat_map(|LpConstraint(lhs, _, rhs)| vec![lhs, rhs]))
+            .chain(merged.constraint_tags.iter_mut().flat_map(|(LpConstraint(lhs, _, rhs), _)| vec![lhs, rhs]))
+            .chain(merged.objectives.iter_mut().map(|(_, objective)| objective))
+        {
+            expr.set_bounds(&bounds);
+        }
+        *self = merged;
+        Ok(())
+    }
+
+    /// Set the objective coefficient of `var`, adding it to the objective if it is not part of it.
+    /// Fails, leaving the problem unchanged, if the objective is not linear.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::format::lp_format::LpFileFormat;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let ref y = LpContinuous::new("y");
+    /// let mut problem = LpProblem::new("Reoptimize", LpObjective::Maximize);
+    /// problem += 3 * x + y;
+    ///
+    /// problem.set_objective_coefficient(x, 0.5).unwrap();
+    /// problem.set_objective_coefficient(&LpInteger::new("z"), 2.0).unwrap();
+    /// assert_eq!(problem.obj_expr_arena.unwrap().to_lp_file_format(), "0.5 x + y + 2 z");
+    /// ```
+    pub fn set_objective_coefficient<T>(&mut self, var: &T, coefficient: f64) -> Result<(), LpError>
+    where
+        T: NamedVariable + Into<LpExpression> + Clone,
+    {
+        let name = var.name();
+        let mut terms: Vec<LpExpression> = Vec::new();
+        let mut found = false;
+        if let Some(objective) = &self.obj_expr_arena {
+            let (nodes, constant) = objective.linear_term_nodes().ok_or_else(|| {
+                LpError::Nonlinear("Expected a linear objective, found a product of variables".to_string())
+            })?;
+            // the terms are rebuilt, so that the coefficient is set exactly
+            for (term, node, current) in nodes {
+                let value = if term == name {
+                    found = true;
+                    coefficient
+                } else {
+                    current
+                };
+                terms.push(value * LpExpression::from(node));
+            }
+            terms.push(constant.into());
+        }
+        if !found {
+            terms.push(coefficient * var.clone().into());
+        }
+        self.obj_expr_arena = None;
+        self.add_objective_expression(&mut lp_sum(&terms));
+        Ok(())
+    }
+
+    /// Apply the objective coefficient and bound updates of `update` together.
+    /// Fails, leaving the problem unchanged, if a variable is not part of the problem.
+    /// A built `IncrementalMiniLpSolver` keeps its own copy of the problem, changed by
+    /// `IncrementalMiniLpSolver::apply_updates`.
+    pub fn apply_updates(&mut self, update: &ProblemUpdate) -> Result<(), LpError> {
+        let mut objective_delta = Vec::new();
+        for (name, &coefficient) in &update.objective_coefficients {
+            let (current, node) = match self.obj_expr_arena.as_ref().map(|o| (o, o.find_variable(name))) {
+                Some((objective, Some(node))) => (objective.coefficient_of(name)?, node.clone()),
+                _ => (0.0, self.find_variable(name)?),
+            };
+            let node: LpExpression = node.into();
+            objective_delta.push((coefficient - current) * node);
+        }
+        for name in update.bounds.keys() {
+            self.find_variable(name)?;
+        }
+
+        if !objective_delta.is_empty() {
+            self.add_objective_expression(&mut lp_sum(&objective_delta));
+        }
+        if let Some(objective) = self.obj_expr_arena.as_mut() {
+            objective.set_bounds(&update.bounds);
+        }
+        for LpConstraint(lhs, _, rhs) in self.all_constraints_mut() {
+            lhs.set_bounds(&update.bounds);
+            rhs.set_bounds(&update.bounds);
+        }
+        Ok(())
+    }
+
+    /// Remove the constraint at `index` and return it, `None` if there is no such constraint.
+    /// The constraints are named after their position in the LP file (`c1`, `c2`, ...), so the
+    /// following constraints are renamed: `remove_constraints_by_name` removes several constraints
+    /// by their names before the removal. A built `IncrementalMiniLpSolver` keeps its own copy of
+    /// the problem, changed by `IncrementalMiniLpSolver::remove_constraint`.
+    pub fn remove_constraint(&mut self, index: usize) -> Option<LpConstraint> {
+        if index < self.constraints.len() {
+            let removed = self.constraints.remove(index);
+            if !self.constraints.contains(&removed) {
+                self.constraint_tags.retain(|(constraint, _)| *constraint != removed);
+            }
+            Some(removed)
+        } else {
+            None
+        }
+    }
+
+    /// Declare a variable of the model. The variables are only known to the problem by their
+    /// occurrences: a declared variable missing from the objective and the constraints is
+    /// reported by `validate`, and the solvers give it the value closest to zero within its bounds.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x").upper_bound(10.0);
+    /// let ref spare = LpInteger::new("spare").lower_bound(2.0);
+    /// let mut problem = LpProblem::new("Declared", LpObjective::Maximize);
+    /// problem += x;
+    /// problem += x.le(10);
+    /// problem.declare(spare);
+    ///
+    /// assert_eq!(problem.validate().unwrap(), vec![ModelWarning::UnusedDeclaredVariable("spare".to_string())]);
+    /// ```
+    pub fn declare<T>(&mut self, var: &T)
+    where
+        T: NamedVariable + Into<LpExpression> + Clone,
+    {
+        self.declared.push(var.clone().into());
+    }
+
+    /// Declared variables found neither in the objective nor in the constraints, sorted by name,
+    /// with their value closest to zero within their bounds
+    pub(crate) fn unused_declared_values(&self) -> Vec<(String, f64)> {
+        fn closest_to_zero(lower: Option<f64>, upper: Option<f64>) -> f64 {
+            match (lower, upper) {
+                (Some(l), _) if l > 0.0 => l,
+                (_, Some(u)) if u < 0.0 => u,
+                _ => 0.0,
+            }
+        }
+        if self.declared.is_empty() {
+            return Vec::new();
+        }
+        let used = self.variable_nodes();
+        let mut values = BTreeMap::new();
+        for node in self.declared.iter().flat_map(|expr| expr.variable_nodes()) {
+            let (name, value) = match node {
+                LpExprNode::ConsBin(LpBinary { name }) => (name, 0.0),
+                LpExprNode::ConsInt(LpInteger { name, lower_bound, upper_bound }) => {
+                    (name, closest_to_zero(lower_bound.map(f64::ceil), upper_bound.map(f64::floor)))
+                }
+                LpExprNode::ConsCont(LpContinuous { name, lower_bound, upper_bound }) => {
+                    (name, closest_to_zero(*lower_bound, *upper_bound))
+                }
+                LpExprNode::ConsSemiCont(LpSemiContinuous { name, .. }) => (name, 0.0),
+                _ => continue,
+            };
+            if !used.contains_key(name) {
+                values.entry(name.clone()).or_insert(value);
+            }
+        }
+        values.into_iter().collect()
+    }
+
+    /// Constraints with the copies kept in `constraint_tags`, changed together so that the tags
+    /// keep matching their constraints
+    fn all_constraints_mut(&mut self) -> impl Iterator<Item = &mut LpConstraint> + '_ {
+        self.constraints.iter_mut().chain(self.constraint_tags.iter_mut().map(|(constraint, _)| constraint))
+    }
+
+    /// Add a constraint with tags, e.g. its category in a large model, to find it back with
+    /// `constraints_with_tag`. The tags do not change the LP file.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let ref y = LpContinuous::new("y");
+    /// let mut problem = LpProblem::new("Tagged", LpObjective::Maximize);
+    /// problem += x + y;
+    /// problem.add_tagged(&["capacity"], x.le(10));
+    /// problem.add_tagged(&["capacity", "plant"], y.le(5));
+    /// problem += (x + y).le(12);
+    ///
+    /// let capacities: Vec<usize> = problem.constraints_with_tag("capacity").iter().map(|(i, _)| *i).collect();
+    /// assert_eq!(capacities, vec![0, 1]);
+    /// ```
+    pub fn add_tagged(&mut self, tags: &[&str], constraint: LpConstraint) {
+        self.constraint_tags.push((constraint.clone(), tags.iter().map(|tag| tag.to_string()).collect()));
+        self.constraints.push(constraint);
+    }
+
+    /// Constraints added with the tag `tag`, with their index in `constraints`: the constraint
+    /// at index `i` is named `c{i + 1}` in the LP file
+    pub fn constraints_with_tag(&self, tag: &str) -> Vec<(usize, &LpConstraint)> {
+        let tagged: Vec<&LpConstraint> = self
+            .constraint_tags
+            .iter()
+            .filter(|(_, tags)| tags.iter().any(|t| t == tag))
+            .map(|(constraint, _)| constraint)
+            .collect();
+        if tagged.is_empty() {
+            return Vec::new();
         }
+        self.constraints.iter().enumerate().filter(|(_, constraint)| tagged.contains(constraint)).collect()
     }
 
+    /// Remove the constraint named `name` in the LP file, e.g. `c2` as reported by
+    /// `compute_iis` or in the sensitivity analysis. Returns whether it was found.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let mut problem = LpProblem::new("Cuts", LpObjective::Maximize);
+    /// problem += x;
+    /// problem += x.le(10);
+    /// problem += x.le(5);
+    ///
+    /// assert!(problem.remove_constraint_by_name("c2"));
+    /// assert!(!problem.remove_constraint_by_name("c2"));
+    /// assert_eq!(problem.constraints.len(), 1);
+    /// ```
+    pub fn remove_constraint_by_name(&mut self, name: &str) -> bool {
+        match constraint_index(name) {
+            Some(index) => self.remove_constraint(index).is_some(),
+            None => false,
+        }
+    }
+
+    /// Remove the constraints named `names` in the LP file before any of them is removed, e.g. the
+    /// constraints of an IIS, whereas removing them one at a time renames the following ones.
+    /// Returns the number of constraints found.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let mut problem = LpProblem::new("Cuts", LpObjective::Maximize);
+    /// problem += x;
+    /// problem += x.le(10);
+    /// problem += x.le(5);
+    /// problem += x.le(7);
+    ///
+    /// assert_eq!(problem.remove_constraints_by_name(&["c3", "c1", "c4"]), 2);
+    /// assert_eq!(problem.constraints, vec![x.le(5)]);
+    /// ```
+    pub fn remove_constraints_by_name(&mut self, names: &[&str]) -> usize {
+        let mut indices: Vec<usize> = names
+            .iter()
+            .filter_map(|name| constraint_index(name))
+            .filter(|&index| index < self.constraints.len())
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        // from the last one, so that the indices of the others do not change
+        for &index in indices.iter().rev() {
+            self.remove_constraint(index);
+        }
+        indices.len()
+    }
+
+    /// Cheap checks for likely modeling mistakes, without solving the problem.
+    /// Fails if the objective or a constraint is not linear.
+    pub fn validate(&self) -> Result<Vec<ModelWarning>, LpError> {
+        let mut warnings = Vec::new();
+        if self.check_not_empty().is_err() {
+            warnings.push(ModelWarning::EmptyProblem);
+        }
+        let mut used: HashMap<String, bool> = HashMap::new();
+        let mut record = |terms: &HashMap<String, f64>| {
+            for (name, coefficient) in terms {
+                *used.entry(name.clone()).or_insert(false) |= *coefficient != 0.0;
+            }
+        };
+
+        let objective_terms = match &self.obj_expr_arena {
+            Some(objective) => objective.linear_terms()?.0,
+            None => HashMap::new(),
+        };
+        record(&objective_terms);
+        let mut unbounded = Vec::new();
+        for (name, &coefficient) in &objective_terms {
+            let improving = match self.objective_type {
+                LpObjective::Minimize => -coefficient,
+                LpObjective::Maximize => coefficient,
+            };
+            let (lower, upper) = match self.find_variable(name) {
+                Ok(LpExprNode::ConsCont(LpContinuous { lower_bound, upper_bound, .. }))
+                | Ok(LpExprNode::ConsInt(LpInteger { lower_bound, upper_bound, .. })) => (lower_bound, upper_bound),
+                _ => continue,
+            };
+            if (improving > 0.0 && upper.is_none()) || (improving < 0.0 && lower.is_none()) {
+                unbounded.push(name.clone());
+            }
+        }
+        unbounded.sort();
+        warnings.extend(unbounded.into_iter().map(ModelWarning::UnboundedVariable));
+
+        for (index, LpConstraint(lhs, op, rhs)) in self.constraints.iter().enumerate() {
+            let (lhs_terms, lhs_constant) = lhs.linear_terms()?;
+            let (rhs_terms, rhs_constant) = rhs.linear_terms()?;
+            record(&lhs_terms);
+            record(&rhs_terms);
+            let mut terms = lhs_terms;
+            for (name, coefficient) in rhs_terms {
+                *terms.entry(name).or_insert(0.0) -= coefficient;
+            }
+            if terms.values().all(|&coefficient| coefficient == 0.0) {
+                let satisfied = match op {
+                    Constraint::LessOrEqual => lhs_constant <= rhs_constant,
+                    Constraint::GreaterOrEqual => lhs_constant >= rhs_constant,
+                    Constraint::Equal => lhs_constant == rhs_constant,
+                };
+                let name = format!("c{}", index + 1);
+                warnings.push(if satisfied {
+                    ModelWarning::EmptyConstraint(name)
+                } else {
+                    ModelWarning::ViolatedConstraint(name)
+                });
+            }
+        }
+
+        let mut unused: Vec<String> = used.into_iter().filter(|(_, used)| !used).map(|(name, _)| name).collect();
+        unused.sort();
+        warnings.extend(unused.into_iter().map(ModelWarning::UnusedVariable));
+        warnings.extend(
+            self.unused_declared_values()
+                .into_iter()
+                .map(|(name, _)| ModelWarning::UnusedDeclaredVariable(name)),
+        );
+        Ok(warnings)
+    }
+
+    /// Add an objective for `solve_lexicographic`, objectives with a higher `priority` being
+    /// optimized first. All objectives are optimized in the direction of the problem.
+    pub fn add_objective<T: Into<LpExpression>>(&mut self, priority: u32, objective: T) {
+        self.objectives.push((priority, objective.into()));
+    }
+
+    /// Optimize the objectives added with `add_objective` in decreasing priority order,
+    /// each one subject to keeping the previous ones at their optimal value.
+    ///
+    /// The optimal value `v` of every objective `f` is enforced by a constraint `f >= v - tol`
+    /// (or `f <= v + tol` when minimizing), with `tol = 1e-6 * (1 + |v|)` to absorb the numerical
+    /// noise of the solvers. The objective set with `+=` is not used.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # #[cfg(feature = "cbc")]
+    /// # fn main() {
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::solvers::CbcSolver;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let ref y = LpContinuous::new("y");
+    /// let mut problem = LpProblem::new("Lexicographic", LpObjective::Maximize);
+    /// problem += (x + y).le(4);
+    /// problem.add_objective(2, x + y);
+    /// problem.add_objective(1, x);
+    ///
+    /// if let Ok(solution) = problem.solve_lexicographic(&CbcSolver::new()) {
+    ///     println!("x = {}, y = {}", solution.results["x"], solution.results["y"]);
+    /// }
+    /// # }
+    /// # #[cfg(not(feature = "cbc"))]
+    /// # fn main() {}
+    /// ```
+    pub fn solve_lexicographic<S: SolverTrait<P = LpProblem>>(&self, solver: &S) -> Result<Solution<'_>, LpError> {
+        if self.objectives.is_empty() {
+            return Err(LpError::MissingObjective("No objective added with add_objective".to_string()));
+        }
+        let mut objectives: Vec<&(u32, LpExpression)> = self.objectives.iter().collect();
+        // stable sort: objectives of equal priority are optimized in insertion order
+        objectives.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+
+        let mut fixed: Vec<LpConstraint> = Vec::new();
+        let mut last = None;
+        for (_, objective) in objectives {
+            let mut stage = LpProblem::new(self.name, self.objective_type);
+            stage.metadata = self.metadata.clone();
+            stage.constraints = self.constraints.iter().chain(&fixed).cloned().collect();
+            stage.sos2 = self.sos2.clone();
+            stage.declared = self.declared.clone();
+            stage += objective.clone();
+
+            let solution = solver.run(&stage)?;
+            let value = match solution.status {
+                Status::Optimal | Status::SubOptimal | Status::TimeLimit => solution.objective_value(&stage)?,
+                _ => {
+                    last = Some(solution.with_related_problem(self));
+                    break;
+                }
+            };
+            let tolerance = 1e-6 * (1.0 + value.abs());
+            fixed.push(match self.objective_type {
+                LpObjective::Maximize => objective.ge(value - tolerance),
+                LpObjective::Minimize => objective.le(value + tolerance),
+            });
+            last = Some(solution.with_related_problem(self));
+        }
+        Ok(last.expect("at least one objective"))
+    }
+
+    /// Validate the problem once and simplify its expressions, for repeated solves.
+    ///
+    /// Fails with the messages of the conflicting bounds, the empty or violated constraints and
+    /// the unused variables. Unbounded variables are only heuristic warnings, as the constraints
+    /// may bound them: they are kept in `FrozenProblem::warnings`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x").lower_bound(0.0).upper_bound(10.0);
+    /// let mut problem = LpProblem::new("Frozen", LpObjective::Maximize);
+    /// problem += x;
+    /// problem += x.le(5);
+    ///
+    /// let frozen = problem.freeze().unwrap();
+    /// assert_eq!(frozen.constraints.len(), 1);
+    /// ```
+    pub fn freeze(mut self) -> Result<FrozenProblem, Vec<String>> {
+        let mut errors = Vec::new();
+        if let Err(e) = self.check_bounds() {
+            errors.push(e.to_string());
+        }
+        let (warnings, rejected): (Vec<ModelWarning>, Vec<ModelWarning>) = match self.validate() {
+            Ok(warnings) => warnings.into_iter().partition(|w| matches!(w, ModelWarning::UnboundedVariable(_))),
+            Err(e) => return Err(vec![e.to_string()]),
+        };
+        errors.extend(rejected.iter().map(|w| w.to_string()));
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        if let Some(objective) = self.obj_expr_arena.as_mut() {
+            objective.simplify();
+        }
+        for LpConstraint(lhs, _, rhs) in self.all_constraints_mut() {
+            lhs.simplify();
+            rhs.simplify();
+        }
+        Ok(FrozenProblem { problem: self, warnings })
+    }
+
+    /// Check that the problem has a constraint, a special ordered set or a variable in its
+    /// objective: the solvers refuse to solve an empty problem instead of reporting a meaningless
+    /// optimum.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::error::LpError;
+    ///
+    /// let mut problem = LpProblem::new("Empty", LpObjective::Maximize);
+    /// problem += LpExpression::from(3);
+    /// assert!(matches!(problem.check_not_empty(), Err(LpError::EmptyProblem(_))));
+    ///
+    /// problem += LpContinuous::new("x").le(10);
+    /// assert!(problem.check_not_empty().is_ok());
+    /// ```
+    pub fn check_not_empty(&self) -> Result<(), LpError> {
+        let objective_empty = match &self.obj_expr_arena {
+            Some(objective) => objective.variable_nodes().is_empty(),
+            None => true,
+        };
+        if objective_empty && self.constraints.is_empty() && self.sos2.is_empty() {
+            Err(LpError::EmptyProblem(self.name.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check that the bounds given to the different occurrences of every variable
+    /// are compatible, i.e. that their intersection is not empty
+    pub fn check_bounds(&self) -> Result<(), LpError> {
+        self.intersected_bounds().map(|_| ())
+    }
+
+    /// Intersection of the bounds given to the different occurrences of every integer and
+    /// continuous variable, infinite when missing
+    pub(crate) fn intersected_bounds(&self) -> Result<HashMap<&str, (f64, f64)>, LpError> {
+        let mut bounds: HashMap<&str, (f64, f64)> = HashMap::new();
+        let expressions = self
+            .obj_expr_arena
+            .iter()
+            .chain(self.constraints.iter().flat_map(|LpConstraint(lhs, _, rhs)| vec![lhs, rhs]));
+        for expr in expressions {
+            for node in expr.variable_nodes() {
+                if let LpExprNode::ConsInt(LpInteger { name, lower_bound, upper_bound })
+                | LpExprNode::ConsCont(LpContinuous { name, lower_bound, upper_bound }) = node
+                {
+                    let (lower, upper) = bounds.entry(name).or_insert((f64::NEG_INFINITY, f64::INFINITY));
+                    if let Some(l) = lower_bound {
+                        *lower = lower.max(*l);
+                    }
+                    if let Some(u) = upper_bound {
+                        *upper = upper.min(*u);
+                    }
+                    if lower > upper {
+                        return Err(LpError::ConflictingBounds {
+                            name: name.clone(),
+                            lower: *lower,
+                            upper: *upper,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(bounds)
+    }
+
+    /// Check that the coefficients of the variables in the objective and the constraints are
+    /// finite. Infinite bounds are allowed.
+    pub fn check_coefficients(&self) -> Result<(), LpError> {
+        let expressions = self
+            .obj_expr_arena
+            .iter()
+            .chain(self.constraints.iter().flat_map(|LpConstraint(lhs, _, rhs)| vec![lhs, rhs]));
+        for expr in expressions {
+            let mut terms: Vec<(String, f64)> = expr.linear_terms()?.0.into_iter().collect();
+            terms.sort_by(|a, b| a.0.cmp(&b.0));
+            if let Some((name, coefficient)) = terms.into_iter().find(|(_, c)| !c.is_finite()) {
+                return Err(non_finite_coefficient(&name, coefficient));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that `solution` satisfies the constraints, the bounds and the integrality of the
+    /// variables, within the tolerance `tol`. Fails if a variable of the problem has no value.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref a = LpInteger::new("a").lower_bound(0.0);
+    /// let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
+    /// problem += a;
+    /// problem += a.le(5);
+    ///
+    /// let mut solution = HashMap::new();
+    /// solution.insert("a".to_string(), 5.0);
+    /// assert_eq!(problem.is_feasible(&solution, 1e-6).unwrap(), true);
+    /// solution.insert("a".to_string(), 4.5);
+    /// assert_eq!(problem.is_feasible(&solution, 1e-6).unwrap(), false);
+    /// ```
+    pub fn is_feasible(&self, solution: &HashMap<String, f64>, tol: f64) -> Result<bool, LpError> {
+        let variables: Vec<(String, &LpExprNode)> = self.variable_nodes().into_iter().collect();
+        let mut values = HashMap::new();
+        for (name, _) in &variables {
+            match solution.get(name) {
+                Some(&value) => values.insert(name.as_str(), value),
+                None => return Err(LpError::MissingValue(name.clone())),
+            };
+        }
+
+        let expressions = self
+            .obj_expr_arena
+            .iter()
+            .chain(self.constraints.iter().flat_map(|LpConstraint(lhs, _, rhs)| vec![lhs, rhs]));
+        for expr in expressions {
+            for node in expr.variable_nodes() {
+                if let LpExprNode::ConsSemiCont(LpSemiContinuous { name, lower, upper }) = node {
+                    let value = values[name.as_str()];
+                    if value.abs() > tol && (value < lower - tol || value > upper + tol) {
+                        return Ok(false);
+                    }
+                    continue;
+                }
+                let (name, lower_bound, upper_bound, integer) = match node {
+                    LpExprNode::ConsBin(LpBinary { name }) => (name, Some(0.0), Some(1.0), true),
+                    LpExprNode::ConsInt(LpInteger { name, lower_bound, upper_bound }) => {
+                        (name, *lower_bound, *upper_bound, true)
+                    }
+                    LpExprNode::ConsCont(LpContinuous { name, lower_bound, upper_bound }) => {
+                        (name, *lower_bound, *upper_bound, false)
+                    }
+                    _ => continue,
+                };
+                let value = values[name.as_str()];
+                if lower_bound.is_some_and(|l| value < l - tol)
+                    || upper_bound.is_some_and(|u| value > u + tol)
+                    || (integer && (value - value.round()).abs() > tol)
+                {
+                    return Ok(false);
+                }
+            }
+        }
+
+        let evaluate = |expr: &LpExpression| -> Result<f64, LpError> {
+            let (terms, constant) = expr.linear_terms()?;
+            Ok(terms.iter().fold(constant, |sum, (name, coefficient)| sum + coefficient * values[name.as_str()]))
+        };
+        for LpConstraint(lhs, op, rhs) in &self.constraints {
+            let difference = evaluate(lhs)? - evaluate(rhs)?;
+            let satisfied = match op {
+                Constraint::LessOrEqual => difference <= tol,
+                Constraint::GreaterOrEqual => difference >= -tol,
+                Constraint::Equal => difference.abs() <= tol,
+            };
+            if !satisfied {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Objective vector, constraint matrix, right-hand sides and bounds of the problem,
+    /// with the columns ordered by variable name. Fails if the problem is not linear.
+    pub fn to_standard_form(&self) -> Result<StandardForm, LpError> {
+        let mut bounds: BTreeMap<String, (Option<f64>, Option<f64>)> = BTreeMap::new();
+        let expressions = self
+            .obj_expr_arena
+            .iter()
+            .chain(self.constraints.iter().flat_map(|LpConstraint(lhs, _, rhs)| vec![lhs, rhs]));
+        for expr in expressions {
+            for node in expr.variable_nodes() {
+                let (name, lower_bound, upper_bound) = match node {
+                    LpExprNode::ConsBin(LpBinary { name }) => (name, Some(0.0), Some(1.0)),
+                    LpExprNode::ConsInt(LpInteger { name, lower_bound, upper_bound })
+                    | LpExprNode::ConsCont(LpContinuous { name, lower_bound, upper_bound }) => {
+                        (name, *lower_bound, *upper_bound)
+                    }
+                    LpExprNode::ConsSemiCont(var) => {
+                        let LpContinuous { name, lower_bound, upper_bound } = var.continuous_hull();
+                        bounds.entry(name).or_insert((lower_bound, upper_bound));
+                        continue;
+                    }
+                    _ => continue,
+                };
+                let (lower, upper) = bounds.entry(name.clone()).or_insert((None, None));
+                if let Some(l) = lower_bound {
+                    *lower = Some(lower.map_or(l, |current| current.max(l)));
+                }
+                if let Some(u) = upper_bound {
+                    *upper = Some(upper.map_or(u, |current| current.min(u)));
+                }
+            }
+        }
+        let columns: HashMap<&String, usize> = bounds.keys().enumerate().map(|(i, name)| (name, i)).collect();
+        let row = |terms: &HashMap<String, f64>| {
+            let mut row = vec![0.0; columns.len()];
+            for (name, coefficient) in terms {
+                row[columns[name]] += coefficient;
+            }
+            row
+        };
+
+        let (objective_terms, constant) = match &self.obj_expr_arena {
+            Some(objective) => objective.linear_terms()?,
+            None => Default::default(),
+        };
+        let mut a = Vec::with_capacity(self.constraints.len());
+        let mut b = Vec::with_capacity(self.constraints.len());
+        for LpConstraint(lhs, _, rhs) in &self.constraints {
+            let (lhs_terms, lhs_constant) = lhs.linear_terms()?;
+            let (rhs_terms, rhs_constant) = rhs.linear_terms()?;
+            let mut coefficients = row(&lhs_terms);
+            for (coefficient, rhs_coefficient) in coefficients.iter_mut().zip(row(&rhs_terms)) {
+                *coefficient -= rhs_coefficient;
+            }
+            a.push(coefficients);
+            b.push(rhs_constant - lhs_constant);
+        }
+
+        Ok(StandardForm {
+            c: row(&objective_terms),
+            objective_constant: constant + self.objective_constant,
+            a,
+            b,
+            senses: self.constraints.iter().map(|c| c.1.clone()).collect(),
+            var_names: bounds.keys().cloned().collect(),
+            bounds: bounds.values().cloned().collect(),
+        })
+    }
+
+    /// Dual of the linear program, built from `to_standard_form`.
+    ///
+    /// The dual has a variable `y_cN` per constraint `cN`, and a variable `y_lb_x` or `y_ub_x`
+    /// per finite lower or upper bound of a variable `x`, except the zero bound fixing the sign
+    /// of `x`. Its constraint `j` is the one of column `j` of the standard form, i.e. of the
+    /// `j`-th variable by name. The objective sense is swapped, and the signs of the dual
+    /// variables follow the senses of the primal constraints. At the optimum, both problems
+    /// have the same objective value.
+    ///
+    /// Fails if the problem has integer or binary variables, or special ordered sets.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::format::lp_format::LpFileFormat;
+    ///
+    /// let ref x = LpContinuous::new("x").lower_bound(0.0);
+    /// let mut problem = LpProblem::new("Primal", LpObjective::Maximize);
+    /// problem += 3 * x;
+    /// problem += (2 * x).le(4);
+    ///
+    /// // minimize 4 y_c1 subject to 2 y_c1 >= 3, y_c1 >= 0
+    /// let dual = problem.dual().unwrap();
+    /// assert_eq!(dual.objective_type, LpObjective::Minimize);
+    /// assert!(dual.to_lp_file_format().contains("c1: 2 y_c1 >= 3"));
+    /// ```
+    pub fn dual(&self) -> Result<LpProblem, LpError> {
+        let integers: Vec<String> = self
+            .variable_nodes()
+            .into_iter()
+            .filter(|(_, node)| matches!(node, LpExprNode::ConsInt(_) | LpExprNode::ConsBin(_) | LpExprNode::ConsSemiCont(_)))
+            .map(|(name, _)| name)
+            .collect();
+        if let Some(name) = integers.first() {
+            return Err(LpError::Unsupported(format!(
+                "The dual is only defined for linear programs, {} is an integer or semi-continuous variable",
+                name
+            )));
+        }
+        if !self.sos2.is_empty() {
+            return Err(LpError::Unsupported(
+                "The dual is only defined for linear programs, without special ordered sets".to_string(),
+            ));
+        }
+
+        let form = self.to_standard_form()?;
+        let maximize = self.objective_type == LpObjective::Maximize;
+        let mut rows: Vec<(String, Vec<f64>, Constraint, f64)> = form
+            .a
+            .iter()
+            .zip(form.senses.iter().zip(&form.b))
+            .enumerate()
+            .map(|(i, (row, (sense, &b)))| (format!("y_c{}", i + 1), row.clone(), sense.clone(), b))
+            .collect();
+        // Sense of the dual constraint of every column, from the sign of the primal variable
+        let mut column_senses = Vec::with_capacity(form.var_names.len());
+        for (j, (name, &(lower, upper))) in form.var_names.iter().zip(&form.bounds).enumerate() {
+            let mut unit = vec![0.0; form.var_names.len()];
+            unit[j] = 1.0;
+            let (nonnegative, nonpositive) = (lower == Some(0.0), lower != Some(0.0) && upper == Some(0.0));
+            column_senses.push(match (nonnegative, nonpositive, maximize) {
+                (true, _, false) | (_, true, true) => Constraint::LessOrEqual,
+                (true, _, true) | (_, true, false) => Constraint::GreaterOrEqual,
+                _ => Constraint::Equal,
+            });
+            if let Some(l) = lower.filter(|_| !nonnegative) {
+                rows.push((format!("y_lb_{}", name), unit.clone(), Constraint::GreaterOrEqual, l));
+            }
+            if let Some(u) = upper.filter(|_| !nonpositive) {
+                rows.push((format!("y_ub_{}", name), unit, Constraint::LessOrEqual, u));
+            }
+        }
+
+        let dual_objective = if maximize { LpObjective::Minimize } else { LpObjective::Maximize };
+        let mut dual = LpProblem::new(self.name, dual_objective);
+        let variables: Vec<LpContinuous> = rows
+            .iter()
+            .map(|(name, _, sense, _)| {
+                let y = LpContinuous::new(name);
+                // y >= 0 for the constraints tightening the optimum, y <= 0 for the others
+                match (sense, maximize) {
+                    (Constraint::Equal, _) => y,
+                    (Constraint::LessOrEqual, true) | (Constraint::GreaterOrEqual, false) => y.lower_bound(0.0),
+                    _ => y.upper_bound(0.0),
+                }
+            })
+            .collect();
+
+        let objective_terms: Vec<LpExpression> = variables
+            .iter()
+            .zip(&rows)
+            .filter(|(_, (_, _, _, b))| *b != 0.0)
+            .map(|(y, (_, _, _, b))| *b * y)
+            .collect();
+        if !objective_terms.is_empty() {
+            dual += lp_sum_iter(objective_terms);
+        }
+        dual.objective_constant = form.objective_constant;
+        for (j, (sense, c)) in column_senses.into_iter().zip(form.c).enumerate() {
+            let column = variables
+                .iter()
+                .zip(&rows)
+                .filter(|(_, (_, row, _, _))| row[j] != 0.0)
+                .map(|(y, (_, row, _, _))| row[j] * y);
+            dual += LpConstraint(lp_sum_iter(column), sense, LpExpression::literal(c));
+        }
+        Ok(dual)
+    }
+
+    /// Constraint matrix in compressed sparse column format, with the columns ordered
+    /// by variable name as in `to_standard_form`. Fails if the problem is not linear.
+    pub fn to_sparse(&self) -> Result<SparseMatrix, LpError> {
+        let mut columns: BTreeMap<String, Vec<(usize, f64)>> = BTreeMap::new();
+        if let Some(objective) = &self.obj_expr_arena {
+            for name in objective.linear_terms()?.0.into_keys() {
+                columns.entry(name).or_default();
+            }
+        }
+        for (row, LpConstraint(lhs, _, rhs)) in self.constraints.iter().enumerate() {
+            let (mut terms, _) = lhs.linear_terms()?;
+            for (name, coefficient) in rhs.linear_terms()?.0 {
+                *terms.entry(name).or_insert(0.0) -= coefficient;
+            }
+            for (name, coefficient) in terms {
+                let column = columns.entry(name).or_default();
+                if coefficient != 0.0 {
+                    column.push((row, coefficient));
+                }
+            }
+        }
+
+        let mut matrix = SparseMatrix {
+            row_indices: Vec::new(),
+            col_ptr: vec![0],
+            values: Vec::new(),
+            var_names: Vec::with_capacity(columns.len()),
+            row_names: (1..=self.constraints.len()).map(|i| format!("c{}", i)).collect(),
+        };
+        for (name, entries) in columns {
+            for (row, value) in entries {
+                matrix.row_indices.push(row);
+                matrix.values.push(value);
+            }
+            matrix.col_ptr.push(matrix.values.len());
+            matrix.var_names.push(name);
+        }
+        Ok(matrix)
+    }
+
+    /// Variable-constraint incidence: the names of the variables of every constraint,
+    /// by constraint name as in the LP file. Fails if a constraint is not linear.
+    pub fn incidence(&self) -> Result<HashMap<String, Vec<String>>, LpError> {
+        self.constraints
+            .iter()
+            .enumerate()
+            .map(|(index, LpConstraint(lhs, _, rhs))| {
+                let mut names: Vec<String> = lhs.linear_terms()?.0.into_keys().collect();
+                names.extend(rhs.linear_terms()?.0.into_keys());
+                names.sort();
+                names.dedup();
+                Ok((format!("c{}", index + 1), names))
+            })
+            .collect()
+    }
+
+    /// Split the problem into independent subproblems, sharing no variable
+    ///
+    /// Two variables are in the same component when they appear together in a constraint or
+    /// a SOS set. Every component gets the constraints and the objective terms of its variables;
+    /// the objective constant and the constraints without variables go to the first component.
+    /// Solving the components separately and combining the solutions with `Solution::combine`
+    /// gives a solution of the whole problem. Fails if the problem is not linear.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x");
+    /// let ref y = LpContinuous::new("y");
+    /// let mut problem = LpProblem::new("Blocks", LpObjective::Maximize);
+    /// problem += x + y;
+    /// problem += x.le(1);
+    /// problem += y.le(2);
+    ///
+    /// assert_eq!(problem.connected_components().unwrap().len(), 2);
+    /// ```
+    pub fn connected_components(&self) -> Result<Vec<LpProblem>, LpError> {
+        fn find(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        }
+
+        // variables are numbered in their order of appearance, to keep the components ordered
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        let mut parents: Vec<usize> = Vec::new();
+        let mut union = |names: Vec<String>, indices: &mut HashMap<String, usize>| {
+            let roots: Vec<usize> = names
+                .into_iter()
+                .map(|name| {
+                    let next = indices.len();
+                    let index = *indices.entry(name).or_insert(next);
+                    if index == parents.len() {
+                        parents.push(index);
+                    }
+                    find(&mut parents, index)
+                })
+                .collect();
+            for root in roots.iter().skip(1) {
+                let (a, b) = (find(&mut parents, roots[0]), find(&mut parents, *root));
+                parents[a.max(b)] = a.min(b);
+            }
+        };
+
+        let mut constraint_names: Vec<Vec<String>> = Vec::with_capacity(self.constraints.len());
+        for LpConstraint(lhs, _, rhs) in &self.constraints {
+            let mut names: Vec<String> = lhs.linear_terms()?.0.into_keys().collect();
+            names.extend(rhs.linear_terms()?.0.into_keys());
+            names.sort();
+            constraint_names.push(names.clone());
+            union(names, &mut indices);
+        }
+        for LpSos2(weights) in &self.sos2 {
+            union(weights.iter().map(|(var, _)| var.name.clone()).collect(), &mut indices);
+        }
+        let mut objective_terms: Vec<(String, f64)> = match &self.obj_expr_arena {
+            Some(objective) => objective.linear_terms()?.0.into_iter().collect(),
+            None => Vec::new(),
+        };
+        objective_terms.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, _) in &objective_terms {
+            union(vec![name.clone()], &mut indices);
+        }
+
+        let mut component_of_root: HashMap<usize, usize> = HashMap::new();
+        for i in 0..parents.len() {
+            let root = find(&mut parents, i);
+            let next = component_of_root.len();
+            component_of_root.entry(root).or_insert(next);
+        }
+        let mut component = |name: &str| {
+            let root = find(&mut parents, indices[name]);
+            component_of_root[&root]
+        };
+
+        let mut components: Vec<LpProblem> = (0..component_of_root.len().max(1))
+            .map(|_| {
+                let mut problem = LpProblem::new(self.name, self.objective_type);
+                problem.objective_name = self.objective_name.clone();
+                problem.auto_scale_objective = self.auto_scale_objective;
+                problem.lp_precision = self.lp_precision;
+                problem.metadata = self.metadata.clone();
+                problem
+            })
+            .collect();
+        components[0].objective_constant = self.objective_constant;
+        components[0].declared = self.declared.clone();
+
+        let mut objectives: Vec<Vec<LpExpression>> = vec![Vec::new(); components.len()];
+        for (name, coefficient) in objective_terms {
+            if let Ok(node) = self.find_variable(&name) {
+                let term = if coefficient == 1.0 {
+                    LpExpression::from(node)
+                } else {
+                    coefficient * LpExpression::from(node)
+                };
+                objectives[component(&name)].push(term);
+            }
+        }
+        for (problem, terms) in components.iter_mut().zip(objectives) {
+            if !terms.is_empty() {
+                *problem += lp_sum_iter(terms);
+            }
+        }
+        for (constraint, names) in self.constraints.iter().zip(&constraint_names) {
+            let index = names.first().map_or(0, |name| component(name));
+            components[index].constraints.push(constraint.clone());
+        }
+        // the tags of the constraints no longer in the problem are left out
+        for (constraint, tags) in &self.constraint_tags {
+            if let Some(position) = self.constraints.iter().position(|c| c == constraint) {
+                let index = constraint_names[position].first().map_or(0, |name| component(name));
+                components[index].constraint_tags.push((constraint.clone(), tags.clone()));
+            }
+        }
+        for sos in &self.sos2 {
+            let index = sos.0.first().map_or(0, |(var, _)| component(&var.name));
+            components[index].sos2.push(sos.clone());
+        }
+        Ok(components)
+    }
+
+    /// Names of the integer and binary variables, sorted
+    pub(crate) fn integer_variable_names(&self) -> Vec<String> {
+        self.variable_nodes()
+            .into_iter()
+            .filter(|(_, node)| matches!(node, LpExprNode::ConsInt(_) | LpExprNode::ConsBin(_)))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn find_variable(&self, name: &str) -> Result<LpExprNode, LpError> {
+        self.obj_expr_arena
+            .iter()
+            .chain(self.constraints.iter().map(|c| &c.0))
+            .find_map(|expr| expr.find_variable(name).cloned())
+            .ok_or_else(|| LpError::UnknownVariable(name.to_string()))
+    }
+
+    /// Variables of the objective and the constraints, sorted by name so that the LP file
+    /// and the other outputs do not depend on the hashing
+    pub(crate) fn variable_nodes(&self) -> BTreeMap<String, &LpExprNode> {
+        let mut found = BTreeMap::new();
+        for expr in self
+            .obj_expr_arena
+            .iter()
+            .chain(self.constraints.iter().flat_map(|LpConstraint(lhs, _, rhs)| vec![lhs, rhs]))
+        {
+            for node in expr.variable_nodes() {
+                let name = match node {
+                    LpExprNode::ConsBin(LpBinary { name })
+                    | LpExprNode::ConsInt(LpInteger { name, .. })
+                    | LpExprNode::ConsCont(LpContinuous { name, .. })
+                    | LpExprNode::ConsSemiCont(LpSemiContinuous { name, .. }) => name,
+                    _ => continue,
+                };
+                found.entry(name.clone()).or_insert(node);
+            }
+        }
+        found
+    }
 
     // TODO: Call once and pass into parameter
     // TODO: Check variables on the objective function
@@ -98,11 +1696,11 @@ impl Problem for LpProblem {
         if let Some(e) = &self.obj_expr_arena {
             let mut simple_expr = expr_arena
                 .merge_cloned_arenas(&e, LpExprOp::Addition);
-            let _ = simple_expr.simplify().split_off_constant();
+            self.objective_constant += simple_expr.simplify().split_off_constant();
             self.obj_expr_arena = Some(simple_expr);
         } else {
             let mut simple_expr = expr_arena.clone();
-            let _ = simple_expr.simplify().split_off_constant();
+            self.objective_constant += simple_expr.simplify().split_off_constant();
             self.obj_expr_arena = Some(simple_expr);
         }
     }
@@ -112,6 +1710,77 @@ impl Problem for LpProblem {
     }
 }
 
+/// Human-readable summary of the problem, independent of any solver file format
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref a = LpInteger::new("a").upper_bound(10.0);
+/// let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
+/// problem += 2 * a;
+/// problem += a.le(5);
+/// println!("{}", problem);
+/// ```
+impl fmt::Display for LpProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.name)?;
+        let sense = match self.objective_type {
+            LpObjective::Maximize => "Maximize",
+            LpObjective::Minimize => "Minimize",
+        };
+        write!(f, "{}\n  ", sense)?;
+        match &self.obj_expr_arena {
+            Some(objective) => write!(f, "{}", objective.to_lp_file_format())?,
+            None => write!(f, "0")?,
+        }
+        if self.objective_constant > 0.0 {
+            write!(f, " + {}", self.objective_constant)?;
+        } else if self.objective_constant < 0.0 {
+            write!(f, " - {}", -self.objective_constant)?;
+        }
+        writeln!(f)?;
+
+        if !self.constraints.is_empty() {
+            writeln!(f, "Subject to")?;
+            for (index, constraint) in self.constraints.iter().enumerate() {
+                writeln!(f, "  c{}: {}", index + 1, constraint.to_lp_file_format())?;
+            }
+        }
+        for (index, LpSos2(set)) in self.sos2.iter().enumerate() {
+            let members: Vec<String> = set.iter().map(|(var, weight)| format!("{}:{}", var.name, weight)).collect();
+            writeln!(f, "  s{}: SOS2 {}", index + 1, members.join(" "))?;
+        }
+
+        let variables = self.variable_nodes();
+        if !variables.is_empty() {
+            writeln!(f, "Variables")?;
+            for (name, node) in &variables {
+                let (category, lower_bound, upper_bound) = match node {
+                    LpExprNode::ConsBin(_) => ("binary", Some(0.0), Some(1.0)),
+                    LpExprNode::ConsInt(LpInteger { lower_bound, upper_bound, .. }) => ("integer", *lower_bound, *upper_bound),
+                    LpExprNode::ConsCont(LpContinuous { lower_bound, upper_bound, .. }) => {
+                        ("continuous", *lower_bound, *upper_bound)
+                    }
+                    LpExprNode::ConsSemiCont(LpSemiContinuous { lower, upper, .. }) => {
+                        ("semi-continuous", Some(*lower), Some(*upper))
+                    }
+                    _ => continue,
+                };
+                let bounds = match (lower_bound, upper_bound) {
+                    (Some(l), Some(u)) => format!("{} <= {} <= {}", l, name, u),
+                    (Some(l), None) => format!("{} >= {}", name, l),
+                    (None, Some(u)) => format!("{} <= {}", name, u),
+                    (None, None) => format!("{} free", name),
+                };
+                writeln!(f, "  {}: {}, {}", name, category, bounds)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 macro_rules! impl_addassign_for_generic_problem {
     ($problem: ty) => {
         /// Add constraints
@@ -132,3 +1801,10 @@ macro_rules! impl_addassign_for_generic_problem {
     };
 }
 impl_addassign_for_generic_problem!(LpProblem);
+
+/// Add a special ordered set
+impl AddAssign<LpSos2> for LpProblem {
+    fn add_assign(&mut self, _rhs: LpSos2) {
+        self.sos2.push(_rhs);
+    }
+}
@@ -3,16 +3,50 @@
 use self::LpExprNode::*;
 use self::LpExprOp::*;
 
+use error::LpError;
+use util::round_to;
+use format::lp_format::LpFileFormat;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::convert::Into;
+use std::fmt;
 use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+use std::iter::{FromIterator, Sum};
 
 pub trait BoundableLp: PartialEq + Clone {
     fn lower_bound(&self, lw: f64) -> Self;
     fn upper_bound(&self, up: f64) -> Self;
+
+    /// Same as `lower_bound`, for bounds computed after the variable has been created
+    fn with_lower_bound(&self, lw: f64) -> Self {
+        self.lower_bound(lw)
+    }
+    /// Same as `upper_bound`, for bounds computed after the variable has been created
+    fn with_upper_bound(&self, up: f64) -> Self {
+        self.upper_bound(up)
+    }
+    /// Copy of the variable bounded by `lw` and `up`
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let x = LpContinuous::new("x").bounded(0.0, 10.0);
+    /// assert_eq!(x, LpContinuous::new("x").lower_bound(0.0).upper_bound(10.0));
+    /// ```
+    fn bounded(&self, lw: f64, up: f64) -> Self {
+        self.lower_bound(lw).upper_bound(up)
+    }
+}
+
+/// Variable identified by its name, in the problem and in the solutions
+pub trait NamedVariable {
+    fn name(&self) -> &str;
 }
 
 // A binary variable is constrained to be either 1 or 0. Refer to the
@@ -55,6 +89,15 @@ impl LpInteger {
             upper_bound: None,
         }
     }
+    /// Variable fixed to `value`, both of its bounds being set to it.
+    /// The solvers receive it as a bound rather than as an equality constraint.
+    pub fn fixed(name: &str, value: f64) -> LpInteger {
+        LpInteger {
+            name: name.to_string(),
+            lower_bound: Some(value),
+            upper_bound: Some(value),
+        }
+    }
 }
 impl ToTokens for LpInteger {
     fn to_tokens(&self, stream: &mut TokenStream) {
@@ -91,6 +134,15 @@ impl LpContinuous {
             upper_bound: None,
         }
     }
+    /// Variable fixed to `value`, both of its bounds being set to it.
+    /// The solvers receive it as a bound rather than as an equality constraint.
+    pub fn fixed(name: &str, value: f64) -> LpContinuous {
+        LpContinuous {
+            name: name.to_string(),
+            lower_bound: Some(value),
+            upper_bound: Some(value),
+        }
+    }
 }
 impl ToTokens for LpContinuous {
     fn to_tokens(&self, stream: &mut TokenStream) {
@@ -113,6 +165,61 @@ impl ToTokens for LpContinuous {
     }
 }
 
+/// Semi-continuous variable, either 0 or between its `lower` and `upper` bounds, e.g. the
+/// capacity of a facility which is either closed or operated above a minimal size.
+/// The LP format lists it in the Semi-Continuous section, and the solvers without native support
+/// solve `LpProblem::lower_semi_continuous` instead.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::format::lp_format::LpFileFormat;
+///
+/// let ref size = LpSemiContinuous::new("size", 20.0, 100.0);
+/// let mut problem = LpProblem::new("Facility", LpObjective::Minimize);
+/// problem += 3 * size;
+/// problem += size.ge(10);
+/// assert!(problem.to_lp_file_format().contains("Semi-Continuous\n  size"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpSemiContinuous {
+    pub name: String,
+    pub lower: f64,
+    pub upper: f64,
+}
+impl LpSemiContinuous {
+    pub fn new(name: &str, lower: f64, upper: f64) -> LpSemiContinuous {
+        LpSemiContinuous {
+            name: name.to_string(),
+            lower,
+            upper,
+        }
+    }
+    /// Continuous variable of the same name covering 0 and the bounds, e.g. `[0, upper]`
+    pub(crate) fn continuous_hull(&self) -> LpContinuous {
+        LpContinuous {
+            name: self.name.clone(),
+            lower_bound: Some(self.lower.min(0.0)),
+            upper_bound: Some(self.upper.max(0.0)),
+        }
+    }
+}
+impl ToTokens for LpSemiContinuous {
+    fn to_tokens(&self, stream: &mut TokenStream) {
+        let name = &self.name;
+        let lower = self.lower;
+        let upper = self.upper;
+        stream.extend(quote! {
+            LpSemiContinuous{
+                name: #name.to_string(),
+                lower: #lower,
+                upper: #upper
+            }
+        });
+    }
+}
+
 macro_rules! implement_boundable {
     ($lp_type: ident) => {
         impl BoundableLp for $lp_type {
@@ -136,7 +243,43 @@ macro_rules! implement_boundable {
 implement_boundable!(LpInteger);
 implement_boundable!(LpContinuous);
 
-#[derive(Debug, Clone, PartialEq)]
+macro_rules! implement_collections {
+    ($lp_type: ident) => {
+        impl $lp_type {
+            /// Create `n` variables named `prefix_0` to `prefix_{n-1}`
+            pub fn vec(prefix: &str, n: usize) -> Vec<$lp_type> {
+                (0..n)
+                    .map(|i| $lp_type::new(&format!("{}_{}", prefix, i)))
+                    .collect()
+            }
+            /// Create a `rows` x `cols` matrix of variables named `prefix_i_j`
+            pub fn matrix(prefix: &str, rows: usize, cols: usize) -> Vec<Vec<$lp_type>> {
+                (0..rows)
+                    .map(|i| $lp_type::vec(&format!("{}_{}", prefix, i), cols))
+                    .collect()
+            }
+        }
+    };
+}
+implement_collections!(LpBinary);
+implement_collections!(LpInteger);
+implement_collections!(LpContinuous);
+
+macro_rules! implement_named {
+    ($lp_type: ident) => {
+        impl NamedVariable for $lp_type {
+            fn name(&self) -> &str {
+                &self.name
+            }
+        }
+    };
+}
+implement_named!(LpBinary);
+implement_named!(LpInteger);
+implement_named!(LpContinuous);
+implement_named!(LpSemiContinuous);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) enum LpExprOp {
     Multiplication,
     Addition,
@@ -155,6 +298,9 @@ impl ToTokens for LpExprOp {
 
 pub(crate) type LpExprArenaIndex = usize;
 
+/// Names, nodes and coefficients of the variables of a linear expression, and its constant
+pub(crate) type LinearTerms<'a> = (Vec<(&'a str, &'a LpExprNode, f64)>, f64);
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct LpCompExpr {
     operation: LpExprOp,
@@ -168,18 +314,37 @@ pub(crate) enum LpExprNode {
     ConsInt(LpInteger),
     ConsBin(LpBinary),
     ConsCont(LpContinuous),
+    ConsSemiCont(LpSemiContinuous),
     LitVal(f64),
     #[allow(dead_code)]
     EmptyExpr,
     LpCompExpr(LpExprOp, LpExprArenaIndex, LpExprArenaIndex),
 }
 
+impl LpExprNode {
+    /// Continuous variable of the same name standing for a variable node: with the bounds of an
+    /// integer variable, `[0, 1]` for a binary variable, or the continuous hull of a
+    /// semi-continuous variable. `None` for the other nodes.
+    pub(crate) fn relaxed_variable(&self) -> Option<LpContinuous> {
+        match self {
+            ConsCont(var) => Some(var.clone()),
+            ConsInt(LpInteger { name, lower_bound, upper_bound }) => {
+                Some(LpContinuous { name: name.clone(), lower_bound: *lower_bound, upper_bound: *upper_bound })
+            }
+            ConsBin(LpBinary { name }) => Some(LpContinuous { name: name.clone(), lower_bound: Some(0.0), upper_bound: Some(1.0) }),
+            ConsSemiCont(var) => Some(var.continuous_hull()),
+            _ => None,
+        }
+    }
+}
+
 impl ToTokens for LpExprNode {
     fn to_tokens(&self, stream: &mut TokenStream) {
         stream.extend(match self {
             LpExprNode::ConsInt(v) => quote!(LpExprNode::ConsInt(#v)),
             LpExprNode::ConsBin(v) => quote!(LpExprNode::ConsBin(#v)),
             LpExprNode::ConsCont(v) => quote!(LpExprNode::ConsCont(#v)),
+            LpExprNode::ConsSemiCont(v) => quote!(LpExprNode::ConsSemiCont(#v)),
             LpExprNode::LpCompExpr(op, lhs, rhs) => quote!(LpExprNode::LpCompExpr(#op, #lhs, #rhs)),
             LpExprNode::LitVal(v) => quote!(LpExprNode::LitVal(#v)),
             LpExprNode::EmptyExpr => quote!(LpExprNode::EmptyExpr),
@@ -205,6 +370,7 @@ macro_rules! cons_into_expr {
 cons_into_expr!(LpBinary, ConsBin);
 cons_into_expr!(LpInteger, ConsInt);
 cons_into_expr!(LpContinuous, ConsCont);
+cons_into_expr!(LpSemiContinuous, ConsSemiCont);
 
 macro_rules! lit_into_expr {
     ($type_from:ty) => {
@@ -223,10 +389,117 @@ macro_rules! lit_into_expr {
 lit_into_expr!(f64);
 lit_into_expr!(i32);
 
-#[derive(Debug, Clone, PartialEq)]
+/// Hash of the structure of a node, see `node_hash`
+type NodeHash = u64;
+
+#[derive(Clone)]
 pub struct LpExpression {
     root: LpExprArenaIndex,
     arena: Vec<LpExprNode>,
+    /// Index of the nodes by structure, so that identical subexpressions are stored once
+    shared_nodes: HashMap<NodeHash, LpExprArenaIndex>,
+}
+
+// The index of shared nodes is only a cache over the arena
+impl PartialEq for LpExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root && self.arena == other.arena
+    }
+}
+
+impl fmt::Debug for LpExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LpExpression")
+            .field("root", &self.root)
+            .field("arena", &self.arena)
+            .finish()
+    }
+}
+
+/// Readable form of the expression, e.g. `2 a + 3 b - 5`.
+/// The terms of a linear expression are gathered, each variable appearing once in the order of
+/// its first occurrence; other expressions are shown as in the LP format.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref a = LpContinuous::new("a");
+/// let ref b = LpContinuous::new("b");
+/// assert_eq!(format!("{}", 2 * a + b - 5 + a), "3 a + b - 5");
+/// ```
+impl fmt::Display for LpExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (terms, constant) = match self.linear_term_nodes() {
+            Some(linear) => linear,
+            None => return write!(f, "{}", self.to_lp_file_format()),
+        };
+        let mut first = true;
+        for (name, _, coefficient) in terms.into_iter().filter(|&(_, _, c)| c != 0.0) {
+            let sign = if coefficient < 0.0 { "-" } else { "+" };
+            match (first, coefficient.abs() == 1.0) {
+                (true, true) if coefficient < 0.0 => write!(f, "-{}", name)?,
+                (true, true) => write!(f, "{}", name)?,
+                (true, false) => write!(f, "{} {}", coefficient, name)?,
+                (false, true) => write!(f, " {} {}", sign, name)?,
+                (false, false) => write!(f, " {} {} {}", sign, coefficient.abs(), name)?,
+            }
+            first = false;
+        }
+        if first {
+            write!(f, "{}", constant)
+        } else if constant < 0.0 {
+            write!(f, " - {}", -constant)
+        } else if constant > 0.0 {
+            write!(f, " + {}", constant)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn node_hash(node: &LpExprNode) -> NodeHash {
+    fn hash_bound(bound: &Option<f64>, hasher: &mut DefaultHasher) {
+        bound.map(f64::to_bits).hash(hasher);
+    }
+    let mut hasher = DefaultHasher::new();
+    match node {
+        ConsInt(LpInteger { name, lower_bound, upper_bound }) => {
+            0.hash(&mut hasher);
+            name.hash(&mut hasher);
+            hash_bound(lower_bound, &mut hasher);
+            hash_bound(upper_bound, &mut hasher);
+        }
+        ConsBin(LpBinary { name }) => {
+            1.hash(&mut hasher);
+            name.hash(&mut hasher);
+        }
+        ConsCont(LpContinuous { name, lower_bound, upper_bound }) => {
+            2.hash(&mut hasher);
+            name.hash(&mut hasher);
+            hash_bound(lower_bound, &mut hasher);
+            hash_bound(upper_bound, &mut hasher);
+        }
+        ConsSemiCont(LpSemiContinuous { name, lower, upper }) => {
+            6.hash(&mut hasher);
+            name.hash(&mut hasher);
+            lower.to_bits().hash(&mut hasher);
+            upper.to_bits().hash(&mut hasher);
+        }
+        LitVal(value) => {
+            3.hash(&mut hasher);
+            value.to_bits().hash(&mut hasher);
+        }
+        EmptyExpr => 4.hash(&mut hasher),
+        LpCompExpr(op, left, right) => {
+            5.hash(&mut hasher);
+            op.hash(&mut hasher);
+            left.hash(&mut hasher);
+            right.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
 }
 
 impl ToTokens for LpExpression {
@@ -250,6 +523,7 @@ macro_rules! cons_into_expr_arena {
                 LpExpression {
                     root: 0,
                     arena: vec![$wrapper(from); 1],
+                    shared_nodes: HashMap::new(),
                 }
             }
         }
@@ -258,6 +532,7 @@ macro_rules! cons_into_expr_arena {
                 LpExpression {
                     root: 0,
                     arena: vec![$wrapper((*from).clone()); 1],
+                    shared_nodes: HashMap::new(),
                 }
             }
         }
@@ -266,6 +541,7 @@ macro_rules! cons_into_expr_arena {
 cons_into_expr_arena!(LpBinary, ConsBin);
 cons_into_expr_arena!(LpInteger, ConsInt);
 cons_into_expr_arena!(LpContinuous, ConsCont);
+cons_into_expr_arena!(LpSemiContinuous, ConsSemiCont);
 
 macro_rules! lit_into_expr_arena {
     ($type_from:ty) => {
@@ -274,6 +550,7 @@ macro_rules! lit_into_expr_arena {
                 LpExpression {
                     root: 0,
                     arena: vec![LitVal(from as f64); 1],
+                    shared_nodes: HashMap::new(),
                 }
             }
         }
@@ -282,19 +559,25 @@ macro_rules! lit_into_expr_arena {
                 LpExpression {
                     root: 0,
                     arena: vec![LitVal((*from).clone() as f64); 1],
+                    shared_nodes: HashMap::new(),
                 }
             }
         }
     };
 }
 lit_into_expr_arena!(f64);
+lit_into_expr_arena!(f32);
 lit_into_expr_arena!(i32);
+lit_into_expr_arena!(i64);
+lit_into_expr_arena!(u32);
+lit_into_expr_arena!(usize);
 
 impl From<LpExprNode> for LpExpression {
     fn from(expr: LpExprNode) -> Self {
         LpExpression {
             root: 0,
             arena: vec![expr; 1],
+            shared_nodes: HashMap::new(),
         }
     }
 }
@@ -304,6 +587,7 @@ impl From<&LpExprNode> for LpExpression {
         LpExpression {
             root: 0,
             arena: vec![expr.clone(); 1],
+            shared_nodes: HashMap::new(),
         }
     }
 }
@@ -313,6 +597,7 @@ impl LpExpression {
         LpExpression {
             root: 0,
             arena: Vec::new(),
+            shared_nodes: HashMap::new(),
         }
     }
 
@@ -325,6 +610,7 @@ impl LpExpression {
         LpExpression {
             root: root,
             arena: arena,
+            shared_nodes: HashMap::new(),
         }
     }
 
@@ -373,6 +659,249 @@ impl LpExpression {
         }
     }
 
+    /// Number of variable nodes of the arena. Identical nodes being stored once, this estimates
+    /// the number of distinct variables without visiting the expression.
+    #[cfg(feature = "minilp")]
+    pub(crate) fn variable_node_count(&self) -> usize {
+        self.arena.iter().filter(|node| matches!(node, ConsBin(_) | ConsInt(_) | ConsCont(_) | ConsSemiCont(_))).count()
+    }
+
+    /// Every occurrence of a variable reachable from the root
+    pub(crate) fn variable_nodes(&self) -> Vec<&LpExprNode> {
+        let mut found = Vec::new();
+        let mut indices = if self.arena.is_empty() { vec![] } else { vec![self.root] };
+        while let Some(index) = indices.pop() {
+            match self.expr_ref_at(index) {
+                node @ ConsBin(_) | node @ ConsInt(_) | node @ ConsCont(_) | node @ ConsSemiCont(_) => found.push(node),
+                &LpCompExpr(_, left, right) => {
+                    indices.push(left);
+                    indices.push(right);
+                }
+                _ => (),
+            }
+        }
+        found
+    }
+
+    /// Continuous variables reachable from the root, by name
+    pub(crate) fn continuous_variables(&self) -> HashMap<String, LpContinuous> {
+        let mut found = HashMap::new();
+        for node in self.variable_nodes() {
+            if let ConsCont(var) = node {
+                found.entry(var.name.clone()).or_insert_with(|| var.clone());
+            }
+        }
+        found
+    }
+
+    /// Node of the variable `name` reachable from the root, if any
+    pub(crate) fn find_variable(&self, name: &str) -> Option<&LpExprNode> {
+        let mut indices = if self.arena.is_empty() { vec![] } else { vec![self.root] };
+        while let Some(index) = indices.pop() {
+            match self.expr_ref_at(index) {
+                node @ ConsBin(LpBinary { name: n })
+                | node @ ConsInt(LpInteger { name: n, .. })
+                | node @ ConsCont(LpContinuous { name: n, .. })
+                | node @ ConsSemiCont(LpSemiContinuous { name: n, .. })
+                    if n == name =>
+                {
+                    return Some(node);
+                }
+                &LpCompExpr(_, left, right) => {
+                    indices.push(left);
+                    indices.push(right);
+                }
+                _ => (),
+            }
+        }
+        None
+    }
+
+    /// Coefficient of the variable `name` in the expression, which must be linear
+    pub(crate) fn coefficient_of(&self, name: &str) -> Result<f64, LpError> {
+        Ok(*self.linear_terms()?.0.get(name).unwrap_or(&0.0))
+    }
+
+    /// Coefficients of the variables and constant term of the expression.
+    /// Variables cancelling out are kept with a zero coefficient.
+    /// Fails if the expression is not linear, e.g. for a product of variables.
+    pub(crate) fn linear_terms(&self) -> Result<(HashMap<String, f64>, f64), LpError> {
+        match self.linear_term_nodes() {
+            Some((terms, constant)) => Ok((
+                terms.into_iter().map(|(name, _, coefficient)| (name.to_string(), coefficient)).collect(),
+                constant,
+            )),
+            None => Err(LpError::Nonlinear(
+                "Expected a linear expression, found a product of variables".to_string(),
+            )),
+        }
+    }
+
+    /// Coefficients of the variables by name, and constant term of the expression, e.g. to write
+    /// an adapter for a custom solver. Variables cancelling out are left out.
+    /// Fails if the expression is not linear, e.g. for a product of variables.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref a = LpContinuous::new("a");
+    /// let ref b = LpContinuous::new("b");
+    /// let (coefficients, constant) = (3 * a - 2 * (b - a) + 1).linear_coefficients().unwrap();
+    /// assert_eq!(coefficients["a"], 5.0);
+    /// assert_eq!(coefficients["b"], -2.0);
+    /// assert_eq!(constant, 1.0);
+    ///
+    /// assert!((a * b).linear_coefficients().is_err());
+    /// ```
+    pub fn linear_coefficients(&self) -> Result<(HashMap<String, f64>, f64), LpError> {
+        match self.linear_term_nodes() {
+            Some((terms, constant)) => Ok((
+                terms
+                    .into_iter()
+                    .filter(|&(_, _, coefficient)| coefficient != 0.0)
+                    .map(|(name, _, coefficient)| (name.to_string(), coefficient))
+                    .collect(),
+                constant,
+            )),
+            None => Err(LpError::Nonlinear(
+                "Expected a linear expression, found a product of variables".to_string(),
+            )),
+        }
+    }
+
+    /// Names, nodes and coefficients of the variables in order of first occurrence, and constant
+    /// term of the expression, or `None` if it is not linear. Nothing is cloned nor simplified.
+    pub(crate) fn linear_term_nodes(&self) -> Option<LinearTerms<'_>> {
+        if self.arena.is_empty() {
+            // all the terms have been split off as a constant
+            return Some((Vec::new(), 0.0));
+        }
+        self.accumulate_linear_terms(true)
+    }
+
+    /// Value of the expression, with `value_of` giving the value of the variables.
+    /// The nodes are visited with an explicit stack, as sums built term by term are deep.
+    pub(crate) fn evaluate(&self, value_of: &dyn Fn(&str) -> Result<f64, LpError>) -> Result<f64, LpError> {
+        if self.arena.is_empty() {
+            return Ok(0.0);
+        }
+        // (index, whether the operands have already been evaluated)
+        let mut pending = vec![(self.root, false)];
+        let mut values: Vec<f64> = Vec::new();
+        while let Some((index, operands_done)) = pending.pop() {
+            match self.expr_ref_at(index) {
+                &LpCompExpr(ref operation, left, right) => {
+                    if operands_done {
+                        let right = values.pop().expect("evaluated right operand");
+                        let left = values.pop().expect("evaluated left operand");
+                        values.push(match operation {
+                            Addition => left + right,
+                            Subtraction => left - right,
+                            Multiplication => left * right,
+                        });
+                    } else {
+                        pending.push((index, true));
+                        pending.push((right, false));
+                        pending.push((left, false));
+                    }
+                }
+                ConsBin(LpBinary { name })
+                | ConsInt(LpInteger { name, .. })
+                | ConsCont(LpContinuous { name, .. })
+                | ConsSemiCont(LpSemiContinuous { name, .. }) => values.push(value_of(name)?),
+                &LitVal(value) => values.push(value),
+                EmptyExpr => values.push(0.0),
+            }
+        }
+        Ok(values.pop().unwrap_or(0.0))
+    }
+
+    /// Overwrite the bounds of the integer and continuous variables found in `bounds`
+    pub(crate) fn set_bounds(&mut self, bounds: &HashMap<String, (Option<f64>, Option<f64>)>) {
+        for node in self.arena.iter_mut() {
+            match node {
+                ConsInt(LpInteger { name, lower_bound, upper_bound })
+                | ConsCont(LpContinuous { name, lower_bound, upper_bound }) => {
+                    if let Some(&(lower, upper)) = bounds.get(name) {
+                        *lower_bound = lower;
+                        *upper_bound = upper;
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Round the literal values to `decimals` decimals
+    pub(crate) fn round_literals(&mut self, decimals: usize) {
+        for node in self.arena.iter_mut() {
+            if let LitVal(value) = node {
+                *value = round_to(*value, decimals);
+            }
+        }
+    }
+
+    /// Rename the variables found in `names`
+    pub(crate) fn rename_variables(&mut self, names: &HashMap<String, String>) {
+        for node in self.arena.iter_mut() {
+            match node {
+                ConsBin(LpBinary { name })
+                | ConsInt(LpInteger { name, .. })
+                | ConsCont(LpContinuous { name, .. })
+                | ConsSemiCont(LpSemiContinuous { name, .. }) => {
+                    if let Some(new_name) = names.get(name) {
+                        *name = new_name.clone();
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Replace every integer, binary and semi-continuous variable by a continuous variable of
+    /// the same name, see `LpExprNode::relaxed_variable`
+    pub(crate) fn relax_variables(&mut self) {
+        for node in self.arena.iter_mut() {
+            if let ConsBin(_) | ConsInt(_) | ConsSemiCont(_) = node {
+                if let Some(relaxed) = node.relaxed_variable() {
+                    *node = ConsCont(relaxed);
+                }
+            }
+        }
+    }
+
+    /// Replace every semi-continuous variable by its continuous hull, bounded by 0 and its
+    /// upper bound
+    pub(crate) fn replace_semi_continuous(&mut self) {
+        for node in self.arena.iter_mut() {
+            if let ConsSemiCont(var) = node {
+                let hull = var.continuous_hull();
+                *node = ConsCont(hull);
+            }
+        }
+    }
+
+    /// Replace every continuous variable found in `substitutes` by the difference
+    /// of its two substitutes
+    pub(crate) fn substitute_differences(
+        &mut self,
+        substitutes: &HashMap<String, (LpContinuous, LpContinuous)>,
+    ) {
+        for index in 0..self.arena.len() {
+            let replacement = match &self.arena[index] {
+                ConsCont(LpContinuous { name, .. }) => substitutes.get(name),
+                _ => None,
+            };
+            if let Some((positive, negative)) = replacement {
+                let positive = self.push_as_expr(&ConsCont(positive.clone()));
+                let negative = self.push_as_expr(&ConsCont(negative.clone()));
+                self.overwrite_expr_at(index, LpCompExpr(Subtraction, positive, negative));
+            }
+        }
+    }
+
     pub(crate) fn get_root_expr_ref(&self) -> &LpExprNode {
         self.expr_ref_at(self.root)
     }
@@ -408,18 +937,87 @@ impl LpExpression {
         right_lp_expr_arena: &LpExpression,
         operation: LpExprOp,
     ) -> Self {
-        let mut new_arena = self.clone();
-        let index_at_insertion = new_arena.push_arena_at_root(right_lp_expr_arena);
-        let new_root = new_arena.push_as_expr(&LpCompExpr(
+        self.clone().merge_arenas(right_lp_expr_arena, operation)
+    }
+
+    /// Same as `merge_cloned_arenas`, but appends to the arena of `self` instead of cloning it,
+    /// so that building a sum term by term stays linear in its size
+    pub(crate) fn merge_arenas(
+        mut self,
+        right_lp_expr_arena: &LpExpression,
+        operation: LpExprOp,
+    ) -> Self {
+        let index_at_insertion = self.push_arena_at_root(right_lp_expr_arena);
+        let new_root = self.push_shared(LpCompExpr(
             operation,
-            new_arena.get_root_index(),
+            self.get_root_index(),
             index_at_insertion,
         ));
-        new_arena.set_root_to_index(new_root);
-        new_arena
+        self.set_root_to_index(new_root);
+        self
     }
 
+    /// Copy the nodes reachable from the root of `right_lp_expr_arena`, reusing the identical
+    /// nodes already in the arena, and return the index of the copied root
     fn push_arena_at_root(&mut self, right_lp_expr_arena: &LpExpression) -> LpExprArenaIndex {
+        if self.shared_nodes.is_empty() {
+            self.index_shared_nodes();
+        }
+        // children are copied before their parent, so that the parent can be looked up
+        let mut new_indices: Vec<Option<LpExprArenaIndex>> = vec![None; right_lp_expr_arena.arena.len()];
+        let mut move_stack = vec![(right_lp_expr_arena.get_root_index(), false)];
+        while let Some((index, children_moved)) = move_stack.pop() {
+            if new_indices[index].is_some() {
+                continue;
+            }
+            let new_expr = match right_lp_expr_arena.expr_ref_at(index) {
+                &LpCompExpr(ref operation, left_index, right_index) => {
+                    if !children_moved {
+                        move_stack.push((index, true));
+                        move_stack.push((right_index, false));
+                        move_stack.push((left_index, false));
+                        continue;
+                    }
+                    match (new_indices[left_index], new_indices[right_index]) {
+                        (Some(new_left_index), Some(new_right_index)) => {
+                            LpCompExpr(operation.clone(), new_left_index, new_right_index)
+                        }
+                        _ => panic!("Children were not moved before their parent. This is a bug."),
+                    }
+                }
+                expr => expr.clone(),
+            };
+            new_indices[index] = Some(self.push_shared(new_expr));
+        }
+        match new_indices[right_lp_expr_arena.get_root_index()] {
+            Some(new_index_right_root) => new_index_right_root,
+            None => panic!("The root was not moved. This is a bug."),
+        }
+    }
+
+    /// Index of a node identical to `lp_expr` in the arena, pushing it if there is none
+    fn push_shared(&mut self, lp_expr: LpExprNode) -> LpExprArenaIndex {
+        let hash = node_hash(&lp_expr);
+        if let Some(&index) = self.shared_nodes.get(&hash) {
+            // nodes may have been overwritten since they were indexed
+            if self.arena.get(index) == Some(&lp_expr) {
+                return index;
+            }
+        }
+        let index = self.arena.len();
+        self.arena.push(lp_expr);
+        self.shared_nodes.insert(hash, index);
+        index
+    }
+
+    fn index_shared_nodes(&mut self) {
+        for (index, lp_expr) in self.arena.iter().enumerate() {
+            self.shared_nodes.entry(node_hash(lp_expr)).or_insert(index);
+        }
+    }
+
+    /// Copy the nodes reachable from the root of `right_lp_expr_arena` without sharing them
+    fn push_arena_copy_at_root(&mut self, right_lp_expr_arena: &LpExpression) -> LpExprArenaIndex {
         let right_root_expr_ref = right_lp_expr_arena.get_root_expr_ref();
         let new_index_right_root = self.push_as_expr(right_root_expr_ref);
         let mut move_stack: Vec<LpExprArenaIndex> = Vec::new();
@@ -570,6 +1168,7 @@ impl LpExpression {
                     ConsBin(LpBinary { name: ref n, .. }) => result += n,
                     ConsInt(LpInteger { name: ref n, .. }) => result += n,
                     ConsCont(LpContinuous { name: ref n, .. }) => result += n,
+                    ConsSemiCont(LpSemiContinuous { name: ref n, .. }) => result += n,
                     EmptyExpr => result += "EmptyExpr!!!",
                 },
             }
@@ -577,7 +1176,142 @@ impl LpExpression {
         result
     }
 
+    /// Simplify the expression into a sum of variable terms followed by a constant.
+    /// Linear expressions are simplified in a single pass, keeping a term per occurrence of a
+    /// variable as written; other expressions are rewritten by `simplify_by_rewriting`.
     pub(crate) fn simplify(&mut self) -> &mut Self {
+        match self.arena.get(self.root) {
+            Some(LpCompExpr(..)) => (),
+            // a single leaf is already simple
+            _ => return self,
+        }
+        let simplified = self
+            .accumulate_linear_terms(false)
+            .map(|(terms, constant)| LpExpression::from_terms(&terms, constant));
+        match simplified {
+            Some(simplified) => *self = simplified,
+            None => {
+                // rewriting overwrites nodes in place, so they must not be shared
+                *self = self.clone_subtree_at_index_and_push(self.root);
+                self.simplify_by_rewriting();
+            }
+        }
+        self
+    }
+
+    /// Names and nodes of the variables with their coefficients, in order of occurrence,
+    /// and constant term of the expression, or `None` if it is not linear.
+    /// With `merge`, the coefficients of the occurrences of a variable are summed in a single term.
+    fn accumulate_linear_terms(&self, merge: bool) -> Option<LinearTerms<'_>> {
+        // value of every constant subtree, computed children first
+        let mut preorder = Vec::new();
+        let mut stack = vec![self.root];
+        while let Some(index) = stack.pop() {
+            preorder.push(index);
+            if let &LpCompExpr(_, left, right) = self.expr_ref_at(index) {
+                stack.push(left);
+                stack.push(right);
+            }
+        }
+        let mut constants: Vec<Option<f64>> = vec![None; self.arena.len()];
+        for &index in preorder.iter().rev() {
+            constants[index] = match self.expr_ref_at(index) {
+                &LitVal(value) => Some(value),
+                EmptyExpr => Some(0.0),
+                ConsBin(_) | ConsInt(_) | ConsCont(_) | ConsSemiCont(_) => None,
+                &LpCompExpr(ref op, left, right) => match (constants[left], constants[right]) {
+                    (Some(l), Some(r)) => Some(match op {
+                        Addition => l + r,
+                        Subtraction => l - r,
+                        Multiplication => l * r,
+                    }),
+                    _ => None,
+                },
+            };
+        }
+
+        let mut terms: Vec<(&str, &LpExprNode, f64)> = Vec::new();
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        let mut constant = 0.0;
+        let mut stack = vec![(self.root, 1.0)];
+        while let Some((index, factor)) = stack.pop() {
+            if let Some(value) = constants[index] {
+                constant += factor * value;
+                continue;
+            }
+            match self.expr_ref_at(index) {
+                node @ ConsBin(LpBinary { name })
+                | node @ ConsInt(LpInteger { name, .. })
+                | node @ ConsCont(LpContinuous { name, .. })
+                | node @ ConsSemiCont(LpSemiContinuous { name, .. }) => match positions.get(name.as_str()) {
+                    Some(&position) if merge => terms[position].2 += factor,
+                    _ => {
+                        positions.insert(name, terms.len());
+                        terms.push((name, node, factor));
+                    }
+                },
+                // the right operand is pushed first to keep the terms in order
+                &LpCompExpr(Addition, left, right) => {
+                    stack.push((right, factor));
+                    stack.push((left, factor));
+                }
+                &LpCompExpr(Subtraction, left, right) => {
+                    stack.push((right, -factor));
+                    stack.push((left, factor));
+                }
+                &LpCompExpr(Multiplication, left, right) => match (constants[left], constants[right]) {
+                    (Some(value), _) => stack.push((right, factor * value)),
+                    (_, Some(value)) => stack.push((left, factor * value)),
+                    _ => return None,
+                },
+                LitVal(_) | EmptyExpr => unreachable!("constant nodes are handled above"),
+            }
+        }
+        Some((terms, constant))
+    }
+
+    /// Sum of the non-zero terms and the constant
+    fn from_terms(terms: &[(&str, &LpExprNode, f64)], constant: f64) -> LpExpression {
+        let mut simplified = LpExpression::new();
+        simplified.arena.reserve(3 * terms.len() + 2);
+        let mut summands: Vec<LpExprArenaIndex> = Vec::with_capacity(terms.len());
+        for &(_, node, coefficient) in terms {
+            if coefficient == 0.0 {
+                continue;
+            }
+            let var_index = simplified.push_as_expr(node);
+            summands.push(if coefficient == 1.0 {
+                var_index
+            } else {
+                let coefficient_index = simplified.push_as_expr(&LitVal(coefficient));
+                simplified.push_as_expr(&LpCompExpr(Multiplication, coefficient_index, var_index))
+            });
+        }
+        // summands are added pairwise, keeping the tree balanced for the recursive visitors
+        while summands.len() > 1 {
+            let mut next = Vec::with_capacity(summands.len() / 2 + 1);
+            for pair in summands.chunks(2) {
+                next.push(match pair {
+                    &[left, right] => simplified.push_as_expr(&LpCompExpr(Addition, left, right)),
+                    _ => pair[0],
+                });
+            }
+            summands = next;
+        }
+        let sum = summands.pop();
+        let root = match sum {
+            Some(sum_index) if constant != 0.0 => {
+                let constant_index = simplified.push_as_expr(&LitVal(constant));
+                simplified.push_as_expr(&LpCompExpr(Addition, sum_index, constant_index))
+            }
+            Some(sum_index) => sum_index,
+            None => simplified.push_as_expr(&LitVal(constant)),
+        };
+        simplified.set_root_to_index(root);
+        simplified
+    }
+
+    fn simplify_by_rewriting(&mut self) -> &mut Self {
         let mut stop = false;
         let mut lp_expr_stack: Vec<LpExprArenaIndex> = Vec::new();
         while !stop {
@@ -610,7 +1344,7 @@ impl LpExpression {
                                 if let LpCompExpr(_, _, _) = i {
                                     let new_subtree =
                                         self.clone_subtree_at_index_and_push(left_index);
-                                    i_new_index = self.push_arena_at_root(&new_subtree);
+                                    i_new_index = self.push_arena_copy_at_root(&new_subtree);
                                 } else {
                                     // Cons or LitVal type
                                     i_new_index = self.clone_expr_at_and_push(left_index);
@@ -637,7 +1371,7 @@ impl LpExpression {
                                 if let LpCompExpr(_, _, _) = i {
                                     let new_subtree =
                                         self.clone_subtree_at_index_and_push(right_index);
-                                    i_new_index = self.push_arena_at_root(&new_subtree);
+                                    i_new_index = self.push_arena_copy_at_root(&new_subtree);
                                 } else {
                                     // Cons or LitVal type
                                     i_new_index = self.clone_expr_at_and_push(right_index);
@@ -665,7 +1399,7 @@ impl LpExpression {
                                 if let LpCompExpr(_, _, _) = i {
                                     let new_subtree =
                                         self.clone_subtree_at_index_and_push(right_index);
-                                    i_new_index = self.push_arena_at_root(&new_subtree);
+                                    i_new_index = self.push_arena_copy_at_root(&new_subtree);
                                 } else {
                                     // Cons or LitVal type
                                     i_new_index = self.clone_expr_at_and_push(right_index);
@@ -692,7 +1426,7 @@ impl LpExpression {
                                 if let LpCompExpr(_, _, _) = i {
                                     let new_subtree =
                                         self.clone_subtree_at_index_and_push(left_index);
-                                    i_new_index = self.push_arena_at_root(&new_subtree);
+                                    i_new_index = self.push_arena_copy_at_root(&new_subtree);
                                 } else {
                                     // Cons or LitVal type
                                     i_new_index = self.clone_expr_at_and_push(left_index);
@@ -1224,7 +1958,7 @@ impl LpExpression {
                             }
                         }
                     }
-                    ConsBin(_) | ConsInt(_) | ConsCont(_) | LitVal(_) | LpExprNode::EmptyExpr => {}
+                    ConsBin(_) | ConsInt(_) | ConsCont(_) | ConsSemiCont(_) | LitVal(_) | LpExprNode::EmptyExpr => {}
                 };
             }
         }
@@ -1266,6 +2000,42 @@ impl LpConstraint {
         LpConstraint(new_lhs_expr, (*op).clone(), new_rhs_expr_arena)
     }
 
+    /// Same constraint written with the opposite sense: once the variables are moved to the left,
+    /// both sides are negated, so that `a + b <= 5` becomes `-a - b >= -5`. An equality `a = b`
+    /// becomes its one-sided `-a + b >= 0`, i.e. `a <= b`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref a = LpContinuous::new("a");
+    /// let ref b = LpContinuous::new("b");
+    /// let LpConstraint(lhs, op, rhs) = (a + b).le(5).flipped();
+    /// assert_eq!(op, Constraint::GreaterOrEqual);
+    /// assert_eq!(lhs.linear_coefficients().unwrap().0["a"], -1.0);
+    /// assert_eq!(rhs.linear_coefficients().unwrap().1, -5.0);
+    /// ```
+    pub fn flipped(&self) -> LpConstraint {
+        let LpConstraint(lhs, op, rhs) = self.generalize();
+        let op = match op {
+            Constraint::LessOrEqual | Constraint::Equal => Constraint::GreaterOrEqual,
+            Constraint::GreaterOrEqual => Constraint::LessOrEqual,
+        };
+        let lhs = if lhs.variable_nodes().is_empty() {
+            lhs
+        } else {
+            let mut negated = -1.0 * lhs;
+            negated.simplify();
+            negated
+        };
+        let constant = match rhs.get_root_expr_ref() {
+            &LitVal(constant) => constant,
+            _ => unreachable!("the right-hand side of a generalized constraint is a constant"),
+        };
+        LpConstraint(lhs, op, LitVal(0.0 - constant).into())
+    }
+
     pub(crate) fn var(
         &self,
         expr_index: LpExprArenaIndex,
@@ -1275,7 +2045,8 @@ impl LpConstraint {
         match self.0.expr_ref_at(expr_index) {
             ConsBin(LpBinary { ref name, .. })
             | ConsInt(LpInteger { ref name, .. })
-            | ConsCont(LpContinuous { ref name, .. }) => {
+            | ConsCont(LpContinuous { ref name, .. })
+            | ConsSemiCont(LpSemiContinuous { ref name, .. }) => {
                 lst.insert(name.clone(), (constraint_index, expr_index));
             }
             LpCompExpr(Multiplication, _, e) => {
@@ -1303,6 +2074,11 @@ impl ToTokens for LpConstraint {
     }
 }
 
+/// Special ordered set of type 2: at most two consecutive variables of the set can be nonzero.
+/// Variables are ordered by their weights, which must be distinct.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpSos2(pub Vec<(LpContinuous, f64)>);
+
 /// make a complete expression or a constraint with a vector of expressions
 ///
 /// # Examples
@@ -1345,6 +2121,70 @@ where
     return lp_sum(&expr.iter().map(|t| f(t.into())).collect());
 }
 
+/// Sum of the expressions of an iterator, without collecting them first
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let vars = LpContinuous::vec("x", 10);
+/// let even = lp_sum_iter(vars.iter().step_by(2).map(|v| v.into()));
+/// let odd: LpExpression = vars.iter().skip(1).step_by(2).map(LpExpression::from).sum();
+/// ```
+pub fn lp_sum_iter<I>(iter: I) -> LpExpression
+where
+    I: IntoIterator<Item = LpExpression>,
+{
+    // Partial sums of 2^level expressions, merged pairwise like a binary counter,
+    // so that the resulting tree stays balanced as in `lp_sum`
+    let mut partial_sums: Vec<(usize, LpExpression)> = Vec::new();
+    for expr in iter {
+        let mut sum = (0, expr);
+        while let Some((level, _)) = partial_sums.last() {
+            if *level != sum.0 {
+                break;
+            }
+            let (level, left) = partial_sums.pop().expect("impossible because last is some");
+            sum = (level + 1, left + sum.1);
+        }
+        partial_sums.push(sum);
+    }
+    let mut partial_sums = partial_sums.into_iter().rev().map(|(_, e)| e);
+    match partial_sums.next() {
+        Some(last) => partial_sums.fold(last, |sum, left| left + sum),
+        None => LpExpression::literal(0.0),
+    }
+}
+
+impl Sum<LpExpression> for LpExpression {
+    fn sum<I: Iterator<Item = LpExpression>>(iter: I) -> Self {
+        lp_sum_iter(iter)
+    }
+}
+
+impl<'a> Sum<&'a LpExpression> for LpExpression {
+    fn sum<I: Iterator<Item = &'a LpExpression>>(iter: I) -> Self {
+        lp_sum_iter(iter.cloned())
+    }
+}
+
+/// Collecting expressions builds their sum, as `lp_sum_iter`
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let vars = LpContinuous::vec("x", 3);
+/// let weighted: LpExpression = vars.iter().zip(1..).map(|(v, c)| c * v).collect();
+/// ```
+impl FromIterator<LpExpression> for LpExpression {
+    fn from_iter<I: IntoIterator<Item = LpExpression>>(iter: I) -> Self {
+        lp_sum_iter(iter)
+    }
+}
+
 pub trait SummableExp {
     fn sum(&self) -> LpExpression;
 }
@@ -1418,6 +2258,37 @@ mod tests {
         assert_eq!(sum.simplify().split_off_constant(), count as f64);
     }
 
+    #[test]
+    fn simplify_large_sum() {
+        let count = 100_000;
+        let vars: Vec<LpExpression> =
+            (0..count).map(|i| 2 * &LpContinuous::new(&format!("v{}", i)) + 1).collect();
+        let mut sum = lp_sum(&vars);
+        sum += &LpContinuous::new("v0");
+        let (terms, constant) = sum.simplify().linear_terms().unwrap();
+        assert_eq!(terms.len(), count);
+        assert_eq!(terms["v0"], 3.0);
+        assert_eq!(constant, count as f64);
+    }
+
+    #[test]
+    fn shared_subexpressions() {
+        let a = &LpContinuous::new("a");
+        let b = &LpContinuous::new("b");
+        let common = 2 * a + 3 * (b - 1);
+        let count = 1000;
+        let mut expr = LpExpression::literal(0.0);
+        for x in LpContinuous::vec("x", count).iter() {
+            expr += x + &common;
+        }
+        // the common subexpression is stored once, each iteration adding a variable and two sums
+        assert!(expr.arena.len() <= 3 * count + common.arena.len() + 1);
+        let (terms, constant) = expr.linear_terms().unwrap();
+        assert_eq!(terms["a"], 2.0 * count as f64);
+        assert_eq!(terms["b"], 3.0 * count as f64);
+        assert_eq!(constant, -3.0 * count as f64);
+    }
+
     #[test]
     fn test_quotations() {
         let a = LpInteger {
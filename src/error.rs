@@ -0,0 +1,180 @@
+//! Error type of the modeler and the solvers.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[cfg(feature = "minilp")]
+use dsl::{LpBinary, LpContinuous, LpExprNode, LpExprOp, LpInteger, LpSemiContinuous};
+
+/// Error returned by the solvers and the fallible operations on problems.
+///
+/// The messages printed by the `Display` implementation are the ones of the former
+/// `String` errors.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::error::LpError;
+///
+/// let ref x = LpContinuous::new("x").lower_bound(3.0);
+/// let mut problem = LpProblem::new("Conflicting", LpObjective::Maximize);
+/// problem += x;
+/// problem += LpContinuous::new("x").upper_bound(2.0).le(5);
+///
+/// match problem.check_bounds() {
+///     Err(LpError::ConflictingBounds { name, .. }) => assert_eq!(name, "x"),
+///     _ => panic!("expected conflicting bounds"),
+/// }
+/// ```
+#[derive(Debug)]
+pub enum LpError {
+    /// Failure to write the problem or to read a solution file
+    Io(io::Error),
+    /// The solver could not be started, e.g. because its executable is not installed.
+    /// Holds the name of the solver.
+    SolverNotFound(String),
+    /// The solver has been cancelled, holds its name. `None` for the race of `FirstOf`.
+    Cancelled(Option<String>),
+    /// The solver ran but failed, e.g. with a non-zero exit status
+    Solver(String),
+    /// The solution or the sensitivity report written by the solver is malformed
+    ParseSolution(String),
+    /// The LP file is malformed
+    ParseLp(String),
+    /// The MPS file is malformed
+    ParseMps(String),
+    /// Expression that is not linear, or that the solver cannot handle
+    Nonlinear(String),
+    /// Expression that could not be decomposed into linear terms, see `DecomposeError`
+    Decompose(DecomposeError),
+    /// Feature of the problem not supported by the solver
+    Unsupported(String),
+    /// Variable with a NaN or infinite coefficient
+    NonFiniteCoefficient { name: String, coefficient: f64 },
+    /// Occurrences of a variable with incompatible bounds
+    ConflictingBounds { name: String, lower: f64, upper: f64 },
+    /// The problem has no objective to optimize
+    MissingObjective(String),
+    /// The problem has neither constraint nor variable in its objective, holds its name
+    EmptyProblem(String),
+    /// Variable of the problem without a value, holds its name
+    MissingValue(String),
+    /// Variable not part of the problem, holds its name
+    UnknownVariable(String),
+}
+
+impl fmt::Display for LpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LpError::Io(e) => write!(f, "{}", e),
+            LpError::SolverNotFound(solver) => write!(f, "Error running the {} solver", solver),
+            LpError::Cancelled(Some(solver)) => write!(f, "The {} solver has been cancelled", solver),
+            LpError::Cancelled(None) => write!(f, "The solvers have been cancelled"),
+            LpError::Solver(message)
+            | LpError::ParseSolution(message)
+            | LpError::ParseLp(message)
+            | LpError::ParseMps(message)
+            | LpError::Nonlinear(message)
+            | LpError::Unsupported(message)
+            | LpError::MissingObjective(message) => write!(f, "{}", message),
+            LpError::NonFiniteCoefficient { name, coefficient } => {
+                write!(f, "Variable {} has a non-finite coefficient {}", name, coefficient)
+            }
+            LpError::ConflictingBounds { name, lower, upper } => write!(
+                f,
+                "Conflicting bounds for variable {}: lower bound {} exceeds upper bound {}",
+                name, lower, upper
+            ),
+            LpError::Decompose(e) => write!(f, "{}", e),
+            LpError::EmptyProblem(name) => {
+                write!(f, "Problem {} has no constraint and no variable in its objective", name)
+            }
+            LpError::MissingValue(name) => write!(f, "Missing value for variable {}", name),
+            LpError::UnknownVariable(name) => write!(f, "Unknown variable {}", name),
+        }
+    }
+}
+
+impl Error for LpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LpError::Io(e) => Some(e),
+            LpError::Decompose(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LpError {
+    fn from(e: io::Error) -> LpError {
+        LpError::Io(e)
+    }
+}
+
+impl From<DecomposeError> for LpError {
+    fn from(e: DecomposeError) -> LpError {
+        LpError::Decompose(e)
+    }
+}
+
+/// Kind of a node of an expression, with the arena indices of the operands of an operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    /// Integer, binary or continuous variable, with its name
+    Variable(String),
+    Literal(f64),
+    Empty,
+    Addition(usize, usize),
+    Subtraction(usize, usize),
+    Multiplication(usize, usize),
+}
+
+impl NodeKind {
+    #[cfg(feature = "minilp")]
+    pub(crate) fn of(node: &LpExprNode) -> NodeKind {
+        match node {
+            LpExprNode::ConsBin(LpBinary { name })
+            | LpExprNode::ConsInt(LpInteger { name, .. })
+            | LpExprNode::ConsCont(LpContinuous { name, .. })
+            | LpExprNode::ConsSemiCont(LpSemiContinuous { name, .. }) => NodeKind::Variable(name.clone()),
+            LpExprNode::LitVal(value) => NodeKind::Literal(*value),
+            LpExprNode::EmptyExpr => NodeKind::Empty,
+            LpExprNode::LpCompExpr(LpExprOp::Addition, lhs, rhs) => NodeKind::Addition(*lhs, *rhs),
+            LpExprNode::LpCompExpr(LpExprOp::Subtraction, lhs, rhs) => NodeKind::Subtraction(*lhs, *rhs),
+            LpExprNode::LpCompExpr(LpExprOp::Multiplication, lhs, rhs) => NodeKind::Multiplication(*lhs, *rhs),
+        }
+    }
+}
+
+/// Why an expression could not be decomposed into linear terms
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecomposeReason {
+    /// Product whose left factor is not a constant, e.g. a product of two variables
+    Nonlinear,
+    /// Node that cannot appear in a simplified linear expression, e.g. a lone constant
+    UnsupportedNode,
+}
+
+/// Node of an expression that could not be decomposed into linear terms.
+/// The indices refer to the arena of the simplified expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecomposeError {
+    pub index: usize,
+    pub kind: NodeKind,
+    pub reason: DecomposeReason,
+    /// Debug representation of the node, as shown in the message
+    pub node: String,
+}
+
+impl fmt::Display for DecomposeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.reason {
+            DecomposeReason::Nonlinear => write!(f, "Non-simplified multiplication: {}", self.node),
+            DecomposeReason::UnsupportedNode => write!(f, "Unsupported expression: {}", self.node),
+        }
+    }
+}
+
+impl Error for DecomposeError {}
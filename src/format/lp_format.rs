@@ -1,83 +1,244 @@
-use std::fs::File;
+use std::collections::{HashMap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::{self, File};
 use std::io::prelude::*;
-use std::io::Result;
+use std::io::{BufWriter, Error, ErrorKind, Result};
 
 use dsl::*;
 use dsl::Constraint::*;
+use util::round_to;
+
+/// Maximum length of a line of an LP file, as CPLEX and some GLPK builds reject longer lines
+const MAX_LINE_LENGTH: usize = 255;
 
 pub trait LpFileFormat {
     fn to_lp_file_format(&self) -> String;
+    /// Write the LP format into `writer`, e.g. a `Vec<u8>` or a network stream
+    fn write_lp_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(self.to_lp_file_format().as_bytes())
+    }
+    /// Write the LP format into the file `file_model`, removed if writing fails
+    #[cfg(not(target_arch = "wasm32"))]
     fn write_lp(&self, file_model: &str) -> Result<()> {
-        let mut buffer = File::create(file_model)?;
-        buffer.write(self.to_lp_file_format().as_bytes())?;
-        Ok(())
+        write_file(file_model, |file| self.write_lp_to(file))
     }
 }
 
 impl LpFileFormat for LpProblem {
 
+    /// Write the problem, failing if a coefficient is not finite.
+    /// The file is written constraint by constraint through a `BufWriter`.
+    fn write_lp_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_renamed_lp_to(self, &sanitized_names(self), writer)
+    }
+
     fn to_lp_file_format(&self) -> String {
+        let mut buffer = Vec::new();
+        write_problem(self, &sanitized_names(self), &mut buffer).expect("Writing into a Vec cannot fail");
+        String::from_utf8(buffer).expect("The LP format is written from strings")
+    }
+}
+
+/// Create `file_model` and fill it with `write`, removing it if writing fails
+#[cfg(not(target_arch = "wasm32"))]
+fn write_file(file_model: &str, write: impl FnOnce(&mut File) -> Result<()>) -> Result<()> {
+    let written = write(&mut File::create(file_model)?);
+    if written.is_err() {
+        let _ = fs::remove_file(file_model);
+    }
+    written
+}
 
-        let mut buffer = String::new();
+/// `write_lp_to` with the variables renamed as in `names`, as computed once per run by the
+/// solvers with `sanitized_names`
+fn write_renamed_lp_to<W: Write>(prob: &LpProblem, names: &HashMap<String, String>, writer: &mut W) -> Result<()> {
+    prob.check_coefficients().map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let mut buffer = BufWriter::new(writer);
+    write_problem(prob, names, &mut buffer)?;
+    buffer.flush()
+}
 
-        buffer.push_str(format!("\\ {}\n\n", &self.name).as_str());
+/// `write_lp` with the variables renamed as in `names`, see `write_renamed_lp_to`
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+pub(crate) fn write_renamed_lp(prob: &LpProblem, names: &HashMap<String, String>, file_model: &str) -> Result<()> {
+    write_file(file_model, |file| write_renamed_lp_to(prob, names, file))
+}
 
-        buffer.push_str( &objective_lp_file_block(self) );
+/// Write `prob` in the LP format section by section, and the constraints one by one,
+/// so that no string holding the whole file is built.
+/// The variables are written with their names in `names`, see `sanitized_names`.
+fn write_problem<W: Write>(prob: &LpProblem, names: &HashMap<String, String>, writer: &mut W) -> Result<()> {
+    writeln!(writer, "\\ {}", comment_text(prob.name))?;
+    for (key, value) in &prob.metadata {
+        writeln!(writer, "\\ {}: {}", comment_text(key), comment_text(value))?;
+    }
+    writeln!(writer)?;
 
-        let constraints_block = constraints_lp_file_block(self);
-        if constraints_block.len() > 0 {
-            buffer.push_str(format!("\n\nSubject To\n{}", &constraints_block).as_str());
-        }
+    writer.write_all(objective_lp_file_block(prob, names).as_bytes())?;
 
-        let bounds_block = bounds_lp_file_block(self);
-        if bounds_block.len() > 0 {
-            buffer.push_str(format!("\nBounds\n{}", &bounds_block).as_str());
+    if !prob.constraints.is_empty() {
+        write!(writer, "\n\nSubject To\n")?;
+        for (index, constraint) in prob.constraints.iter().enumerate() {
+            let constraint = if names.is_empty() && prob.lp_precision.is_none() {
+                constraint.to_lp_file_format()
+            } else {
+                let LpConstraint(lhs, sense, rhs) = constraint;
+                LpConstraint(written(prob, lhs, names), sense.clone(), written(prob, rhs, names)).to_lp_file_format()
+            };
+            let row = format!("  c{}: {}", index + 1, constraint);
+            writeln!(writer, "{}", wrap_row(&row, is_operator))?;
         }
+    }
 
-        let integers_block = integers_lp_file_block(self);
-        if integers_block.len() > 0 {
-            buffer.push_str(format!("\nGenerals\n  {}\n", &integers_block).as_str());
+    // Variable sections are always written in this canonical order,
+    // whatever the order in which the variables were added to the problem
+    let sections = [
+        ("Bounds", bounds_lp_file_block(prob, names)),
+        ("Generals", integers_lp_file_block(prob, names)),
+        ("Binary", binaries_lp_file_block(prob, names)),
+        ("Semi-Continuous", semi_continuous_lp_file_block(prob, names)),
+        ("SOS", sos_lp_file_block(prob, names)),
+    ];
+    for (header, block) in sections.iter() {
+        if !block.is_empty() {
+            write!(writer, "\n{}\n{}", header, block)?;
         }
+    }
+
+    write!(writer, "\nEnd\n")
+}
 
-        let binaries_block = binaries_lp_file_block(self);
-        if binaries_block.len() > 0 {
-            buffer.push_str(format!("\nBinary\n  {}\n", &binaries_block).as_str());
+/// `text` written in a comment, with its line breaks escaped so that it cannot end the comment
+/// and be read as part of the model
+fn comment_text(text: &str) -> String {
+    text.replace('\r', "\\r").replace('\n', "\\n")
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || "!\"#$%&()/,.;?@_`'{}|~".contains(c);
+    match name.chars().next() {
+        Some(first) if !first.is_ascii_digit() && first != '.' => name.chars().all(valid_char),
+        _ => false,
+    }
+}
+
+/// Variables of `problem` whose names are not valid in the LP format (e.g. containing spaces or
+/// operators, or starting with a digit), with the unique names they are written with.
+///
+/// Invalid characters are replaced by `_`, and a `_` is prepended to names starting with a digit
+/// or a period. Names colliding with another variable get a numeric suffix. The mapping only
+/// depends on the variables of the problem, so the solvers restore the original names when
+/// reading a solution.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::format::lp_format::sanitized_names;
+///
+/// let ref a = LpContinuous::new("my var+1");
+/// let mut problem = LpProblem::new("Names", LpObjective::Maximize);
+/// problem += a;
+/// assert_eq!(sanitized_names(&problem)["my var+1"], "my_var_1");
+/// ```
+pub fn sanitized_names(problem: &LpProblem) -> HashMap<String, String> {
+    let mut names: Vec<String> = problem.variable_nodes().into_keys().collect();
+    for LpSos2(set) in &problem.sos2 {
+        names.extend(set.iter().map(|(var, _)| var.name.clone()));
+    }
+    names.sort();
+    names.dedup();
+    let (valid, invalid): (Vec<String>, Vec<String>) = names.into_iter().partition(|name| is_valid_name(name));
+
+    let mut taken: HashSet<String> = valid.into_iter().collect();
+    let mut sanitized = HashMap::new();
+    for name in invalid {
+        let mut base: String = name.chars().map(|c| if is_valid_name(&format!("a{}", c)) { c } else { '_' }).collect();
+        if !is_valid_name(&base) {
+            base.insert(0, '_');
+        }
+        let mut candidate = base.clone();
+        let mut suffix = 1;
+        while taken.contains(&candidate) {
+            candidate = format!("{}_{}", base, suffix);
+            suffix += 1;
         }
+        taken.insert(candidate.clone());
+        sanitized.insert(name, candidate);
+    }
+    sanitized
+}
 
-        buffer.push_str("\nEnd\n");
+/// `name` as written in the LP format, renamed as in `names`
+fn written_name<'a>(names: &'a HashMap<String, String>, name: &'a str) -> &'a str {
+    names.get(name).map_or(name, |name| name.as_str())
+}
+
+/// Copy of `expr` as written in the LP format: with the variables renamed as in `names`,
+/// simplified and with its coefficients rounded if the precision of `prob` is set
+fn written(prob: &LpProblem, expr: &LpExpression, names: &HashMap<String, String>) -> LpExpression {
+    let mut written = expr.clone();
+    if !names.is_empty() {
+        written.rename_variables(names);
+    }
+    if let Some(decimals) = prob.lp_precision {
+        written.simplify();
+        written.round_literals(decimals);
+    }
+    written
+}
 
-        buffer
+/// `value` as written in the LP format, rounded to the precision of `prob` if it is set
+fn number(prob: &LpProblem, value: f64) -> String {
+    match prob.lp_precision {
+        Some(decimals) => round_to(value, decimals).to_string(),
+        None => value.to_string(),
     }
 }
 
-fn objective_lp_file_block(prob: &LpProblem) -> String {
+fn objective_lp_file_block(prob: &LpProblem, names: &HashMap<String, String>) -> String {
     // Write objectives
     let obj_type = match prob.objective_type {
-        LpObjective::Maximize => "Maximize\n  ",
-        LpObjective::Minimize => "Minimize\n  "
+        LpObjective::Maximize => "Maximize\n",
+        LpObjective::Minimize => "Minimize\n"
+    };
+    let name = prob.objective_name.as_ref().map_or("obj", |name| name.as_str());
+    let scale = prob.objective_scale();
+    let objective_constant = prob.lp_precision.map_or(prob.objective_constant / scale, |decimals| {
+        round_to(prob.objective_constant / scale, decimals)
+    });
+    let constant = if objective_constant > 0.0 {
+        format!(" + {}", objective_constant)
+    } else if objective_constant < 0.0 {
+        format!(" - {}", -objective_constant)
+    } else {
+        String::new()
     };
-    match &prob.obj_expr_arena {
+    let row = match &prob.obj_expr_arena {
+        // the LP readers of the solvers expect a term in the objective: a constant objective, as
+        // the one of a feasibility problem, is written with a zero coefficient on a variable
+        Some(expr_arena) if expr_arena.variable_nodes().is_empty() => match prob.variable_nodes().keys().next() {
+            Some(variable) => format!("  {}: 0 {}{}", name, written_name(names, variable), constant),
+            None => return String::new(),
+        },
         Some(expr_arena) => {
-            format!("{}obj: {}", obj_type, expr_arena.to_lp_file_format())
+            let scaled = if scale != 1.0 { Some((1.0 / scale) * expr_arena.clone()) } else { None };
+            let expr = scaled.as_ref().unwrap_or(expr_arena);
+            let expr = if names.is_empty() && prob.lp_precision.is_none() {
+                expr.to_lp_file_format()
+            } else {
+                written(prob, expr, names).to_lp_file_format()
+            };
+            format!("  {}: {}{}", name, expr, constant)
         }
-        _ => String::new()
-    }
-}
-fn constraints_lp_file_block(prob: &LpProblem) -> String {
-    let mut res = String::new();
-    let mut constraints = prob.constraints.iter();
-    let mut index = 1;
-    while let Some(constraint) = constraints.next() {
-        res.push_str(&format!("  c{}: {}\n", index.to_string(), constraint.to_lp_file_format()));
-        index += 1;
-    }
-    res
+        _ => return String::new()
+    };
+    format!("{}{}", obj_type, wrap_row(&row, is_operator))
 }
-
-fn bounds_lp_file_block(prob: &LpProblem) -> String {
+fn bounds_lp_file_block(prob: &LpProblem, names: &HashMap<String, String>) -> String {
     let mut res = String::new();
-    for (_, (constraint_index, lp_expr_arena_index)) in prob.variables() {
-        let expr_ref = prob.constraints.get(constraint_index).unwrap().0.expr_ref_at(lp_expr_arena_index);
+    // variables appearing only in the objective are declared as well
+    for expr_ref in prob.variable_nodes().into_values() {
         match expr_ref {
             &LpExprNode::ConsInt(LpInteger {
                          ref name,
@@ -89,55 +250,141 @@ fn bounds_lp_file_block(prob: &LpProblem) -> String {
                             lower_bound,
                             upper_bound,
                         }) => {
-                if let Some(l) = lower_bound {
-                    res.push_str(&format!("  {} <= {}", &l.to_string(), &name));
-                    if let Some(u) = upper_bound {
-                        res.push_str(&format!(" <= {}", &u.to_string()));
+                let name = written_name(names, name);
+                match (lower_bound, upper_bound) {
+                    // fixed variable
+                    (Some(l), Some(u)) if l == u => {
+                        res.push_str(&format!("  {} = {}\n", &name, number(prob, l)));
                     }
-                    res.push_str("\n");
-                } else if let Some(u) = upper_bound {
-                    res.push_str(&format!("  {} <= {}\n", &name, &u.to_string()));
-                } else {
-                    match expr_ref {
+                    (Some(l), upper_bound) => {
+                        res.push_str(&format!("  {} <= {}", number(prob, l), &name));
+                        if let Some(u) = upper_bound {
+                            res.push_str(&format!(" <= {}", number(prob, u)));
+                        }
+                        res.push_str("\n");
+                    }
+                    (None, Some(u)) => {
+                        res.push_str(&format!("  {} <= {}\n", &name, number(prob, u)));
+                    }
+                    (None, None) => match expr_ref {
                         &LpExprNode::ConsCont(LpContinuous { .. }) => {
                             res.push_str(&format!("  {} free\n", &name));
                         } // TODO: IntegerVar => -INF to INF
                         _ => (),
-                    }
+                    },
                 }
             }
+            // the lower bound of a semi-continuous variable applies when it is not zero
+            &LpExprNode::ConsSemiCont(LpSemiContinuous { ref name, lower, upper }) => {
+                res.push_str(&format!("  {} <= {} <= {}\n", number(prob, lower), written_name(names, name), number(prob, upper)));
+            }
             _ => (),
         }
     }
     res
 }
 
-fn integers_lp_file_block(prob: &LpProblem) -> String {
+fn integers_lp_file_block(prob: &LpProblem, names: &HashMap<String, String>) -> String {
     let mut res = String::new();
-    for (_, (constraint_index, lp_expr_arena_index)) in prob.variables() {
-        match prob.constraints.get(constraint_index).unwrap().0.expr_ref_at(lp_expr_arena_index) {
+    for expr_ref in prob.variable_nodes().into_values() {
+        match expr_ref {
             &LpExprNode::ConsInt(LpInteger { ref name, .. }) => {
-                res.push_str(format!("{} ", name).as_str());
+                res.push_str(format!("{} ", written_name(names, name)).as_str());
             }
             _ => (),
         }
     }
-    res
+    name_list_block(res)
 }
 
-fn binaries_lp_file_block(prob: &LpProblem) -> String  {
+fn binaries_lp_file_block(prob: &LpProblem, names: &HashMap<String, String>) -> String  {
     let mut res = String::new();
-    for (_, (constraint_index, lp_expr_arena_index)) in prob.variables() {
-        match prob.constraints.get(constraint_index).unwrap().0.expr_ref_at(lp_expr_arena_index) {
+    for expr_ref in prob.variable_nodes().into_values() {
+        match expr_ref {
             &LpExprNode::ConsBin(LpBinary { ref name }) => {
-                res.push_str(format!("{} ", name).as_str());
+                res.push_str(format!("{} ", written_name(names, name)).as_str());
             }
             _ => (),
         }
     }
+    name_list_block(res)
+}
+
+fn semi_continuous_lp_file_block(prob: &LpProblem, names: &HashMap<String, String>) -> String {
+    let mut res = String::new();
+    for expr_ref in prob.variable_nodes().into_values() {
+        if let &LpExprNode::ConsSemiCont(LpSemiContinuous { ref name, .. }) = expr_ref {
+            res.push_str(format!("{} ", written_name(names, name)).as_str());
+        }
+    }
+    name_list_block(res)
+}
+
+fn sos_lp_file_block(prob: &LpProblem, names: &HashMap<String, String>) -> String {
+    let mut res = String::new();
+    for (index, LpSos2(set)) in prob.sos2.iter().enumerate() {
+        let mut row = format!("  s{}: S2::", index + 1);
+        for (var, weight) in set {
+            row.push_str(&format!(" {}:{}", written_name(names, &var.name), weight));
+        }
+        res.push_str(&wrap_row(&row, |_| true));
+        res.push('\n');
+    }
     res
 }
 
+fn name_list_block(names: String) -> String {
+    if !names.is_empty() {
+        format!("{}\n", wrap_row(&format!("  {}", names), |_| true))
+    } else {
+        names
+    }
+}
+
+fn is_operator(token: &str) -> bool {
+    ["+", "-", "<=", ">=", "="].contains(&token)
+}
+
+/// Split a row on several lines no longer than `MAX_LINE_LENGTH`, breaking only before the
+/// tokens accepted by `break_before`. Continuation lines are indented one space further.
+fn wrap_row(row: &str, break_before: impl Fn(&str) -> bool) -> String {
+    if row.len() <= MAX_LINE_LENGTH {
+        return row.to_string();
+    }
+    let content = row.trim_start();
+    let indent = &row[..row.len() - content.len()];
+    let mut wrapped = String::with_capacity(row.len() + row.len() / MAX_LINE_LENGTH * (indent.len() + 2));
+    let mut line = String::new();
+    let mut flush = |line: &mut String, segment: &str| {
+        if line.is_empty() {
+            line.push_str(indent);
+        } else if line.len() + 1 + segment.len() > MAX_LINE_LENGTH {
+            wrapped.push_str(line);
+            wrapped.push('\n');
+            line.clear();
+            line.push_str(indent);
+            line.push(' ');
+        } else {
+            line.push(' ');
+        }
+        line.push_str(segment);
+    };
+    let mut segment = String::new();
+    for token in content.split(' ') {
+        if !segment.is_empty() && break_before(token) {
+            flush(&mut line, &segment);
+            segment.clear();
+        }
+        if !segment.is_empty() {
+            segment.push(' ');
+        }
+        segment.push_str(token);
+    }
+    flush(&mut line, &segment);
+    wrapped.push_str(&line);
+    wrapped
+}
+
 impl LpFileFormat for LpExpression {
     fn to_lp_file_format(&self) -> String {
         fn formalize_signs(s: String) -> String {
@@ -153,9 +400,10 @@ impl LpFileFormat for LpExpression {
             }
             s
         }
-        let root_index = self.get_root_index();
         let mut clone = self.clone();
-        formalize_signs(clone.simplify().show(&root_index, false))
+        let simplified = clone.simplify();
+        let root_index = simplified.get_root_index();
+        formalize_signs(simplified.show(&root_index, false))
     }
 }
 
@@ -0,0 +1,328 @@
+//! Reader for the CPLEX LP format, as written by `LpFileFormat`.
+//!
+//! Supported sections: objective (`Maximize`/`Minimize`, with an optional row name and
+//! constant term), `Subject To`, `Bounds`, `Generals`, `Binary`, `SOS` (type 2 only) and `End`.
+//! As in the LP format, variables without bounds are nonnegative.
+
+use std::collections::{HashMap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+
+use dsl::*;
+use error::LpError;
+
+/// Linear terms of an expression and its constant
+type Terms = (Vec<(String, f64)>, f64);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Header,
+    Objective,
+    Constraints,
+    Bounds,
+    Generals,
+    Binaries,
+    Sos,
+    End,
+}
+
+fn section_of(line: &str) -> Option<(Section, Option<LpObjective>)> {
+    match line.to_lowercase().as_str() {
+        "maximize" | "maximise" | "maximum" | "max" => Some((Section::Objective, Some(LpObjective::Maximize))),
+        "minimize" | "minimise" | "minimum" | "min" => Some((Section::Objective, Some(LpObjective::Minimize))),
+        "subject to" | "such that" | "st" | "s.t." | "st." => Some((Section::Constraints, None)),
+        "bounds" | "bound" => Some((Section::Bounds, None)),
+        "generals" | "general" | "gen" | "integers" => Some((Section::Generals, None)),
+        "binary" | "binaries" | "bin" => Some((Section::Binaries, None)),
+        "sos" => Some((Section::Sos, None)),
+        "end" => Some((Section::End, None)),
+        _ => None,
+    }
+}
+
+fn is_comparison(token: &str) -> bool {
+    ["<=", ">=", "=", "<", ">", "=<", "=>"].contains(&token)
+}
+
+fn parse_number(token: &str) -> Option<f64> {
+    match token.to_lowercase().as_str() {
+        "inf" | "+inf" | "infinity" | "+infinity" => Some(f64::INFINITY),
+        "-inf" | "-infinity" => Some(f64::NEG_INFINITY),
+        _ => token.parse::<f64>().ok(),
+    }
+}
+
+/// Split the optional `name:` prefix of a row
+fn split_row_name(tokens: &[String]) -> (Option<String>, &[String]) {
+    match tokens.first() {
+        Some(first) if first.ends_with(':') => (Some(first.trim_end_matches(':').to_string()), &tokens[1..]),
+        _ => (None, tokens),
+    }
+}
+
+/// Split the tokens of a line, separating the operators glued to their operands
+fn tokenize(line: &str) -> Vec<String> {
+    let mut spaced = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' | '>' | '=' => {
+                spaced.push(' ');
+                spaced.push(c);
+                if let Some(&next) = chars.peek() {
+                    if next == '=' || next == '<' || next == '>' {
+                        spaced.push(next);
+                        chars.next();
+                    }
+                }
+                spaced.push(' ');
+            }
+            ':' => {
+                spaced.push(c);
+                spaced.push(' ');
+            }
+            _ => spaced.push(c),
+        }
+    }
+    spaced.split_whitespace().map(|t| t.to_string()).collect()
+}
+
+/// Parse a linear expression like `3 x + y - 2.5 z + 10`
+fn parse_linear(tokens: &[String]) -> Result<Terms, String> {
+    let mut terms = Vec::new();
+    let mut constant = 0.0;
+    let mut sign = 1.0;
+    let mut coefficient: Option<f64> = None;
+    for token in tokens {
+        match token.as_str() {
+            "+" => {
+                if let Some(c) = coefficient.take() {
+                    constant += sign * c;
+                }
+                sign = 1.0;
+            }
+            "-" => {
+                if let Some(c) = coefficient.take() {
+                    constant += sign * c;
+                }
+                sign = -1.0;
+            }
+            _ => match parse_number(token) {
+                Some(value) => {
+                    if coefficient.is_some() {
+                        return Err(format!("Unexpected number {} in expression", token));
+                    }
+                    coefficient = Some(value);
+                }
+                None => {
+                    terms.push((token.clone(), sign * coefficient.take().unwrap_or(1.0)));
+                    sign = 1.0;
+                }
+            },
+        }
+    }
+    if let Some(c) = coefficient {
+        constant += sign * c;
+    }
+    Ok((terms, constant))
+}
+
+/// Parse a bound line like `0 <= x <= 5`, `x >= 2`, `x = 3` or `x free`
+fn parse_bound(
+    tokens: &[String],
+    bounds: &mut HashMap<String, (Option<f64>, Option<f64>)>,
+) -> Result<(), String> {
+    let finite = |value: f64| if value.is_finite() { Some(value) } else { None };
+    let number = |token: &String| parse_number(token).ok_or_else(|| format!("Invalid bound {}", token));
+    let tokens: Vec<&String> = tokens.iter().collect();
+    match tokens.as_slice() {
+        [name, free] if free.to_lowercase() == "free" => {
+            bounds.insert(name.to_string(), (None, None));
+        }
+        [lower, op1, name, op2, upper] if op1.starts_with('<') && op2.starts_with('<') => {
+            bounds.insert(name.to_string(), (finite(number(lower)?), finite(number(upper)?)));
+        }
+        [left, op, right] => {
+            let (name, value, op) = match parse_number(left) {
+                // `value op name` is read as `name (flipped op) value`
+                Some(value) => {
+                    let flipped = match op.chars().next() {
+                        Some('<') => ">=",
+                        Some('>') => "<=",
+                        _ => "=",
+                    };
+                    (right.to_string(), value, flipped)
+                }
+                None => (left.to_string(), number(right)?, op.as_str()),
+            };
+            let entry = bounds.entry(name).or_insert((Some(0.0), None));
+            match op.chars().next() {
+                Some('<') => entry.1 = finite(value),
+                Some('>') => entry.0 = finite(value),
+                _ => *entry = (finite(value), finite(value)),
+            }
+        }
+        _ => return Err(format!("Invalid bound: {}", tokens.iter().map(|t| t.as_str()).collect::<Vec<_>>().join(" "))),
+    }
+    Ok(())
+}
+
+/// Parse a SOS line like `s1: S2:: x:1 y:2 z:3`
+fn parse_sos(line: &str) -> Result<Vec<(String, f64)>, String> {
+    let (kind, members) = match line.find("::") {
+        Some(index) => (&line[..index], &line[index + 2..]),
+        None => return Err(format!("Invalid SOS: {}", line)),
+    };
+    if !kind.trim_end().to_uppercase().ends_with("S2") {
+        return Err(format!("Only SOS of type 2 are supported: {}", line));
+    }
+    members
+        .split_whitespace()
+        .map(|member| {
+            let mut parts = member.rsplitn(2, ':');
+            let weight = parts.next().and_then(parse_number);
+            match (parts.next(), weight) {
+                (Some(name), Some(weight)) => Ok((name.to_string(), weight)),
+                _ => Err(format!("Invalid SOS member: {}", member)),
+            }
+        })
+        .collect()
+}
+
+/// Parse a problem written in the LP format
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::format::lp_reader::parse_lp;
+///
+/// let problem = parse_lp("Maximize\n obj: 3 x + 2 y\nSubject To\n c1: x + y <= 4\nEnd\n", "Parsed").unwrap();
+/// assert_eq!(problem.constraints.len(), 1);
+/// ```
+pub fn parse_lp(content: &str, name: &'static str) -> Result<LpProblem, LpError> {
+    parse_problem(content, name).map_err(LpError::ParseLp)
+}
+
+fn parse_problem(content: &str, name: &'static str) -> Result<LpProblem, String> {
+    let mut section = Section::Header;
+    let mut objective_type = None;
+    let mut objective_tokens: Vec<String> = Vec::new();
+    let mut constraints: Vec<(Terms, Constraint, f64)> = Vec::new();
+    let mut pending: Vec<String> = Vec::new();
+    let mut bounds: HashMap<String, (Option<f64>, Option<f64>)> = HashMap::new();
+    let mut generals: HashSet<String> = HashSet::new();
+    let mut binaries: HashSet<String> = HashSet::new();
+    let mut sos: Vec<Vec<(String, f64)>> = Vec::new();
+
+    for line in content.lines() {
+        let line = match line.find('\\') {
+            Some(index) => &line[..index],
+            None => line,
+        }
+        .trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some((next, objective)) = section_of(line) {
+            section = next;
+            if objective.is_some() {
+                objective_type = objective;
+            }
+            continue;
+        }
+        match section {
+            Section::Header | Section::End => return Err(format!("Unexpected line outside of a section: {}", line)),
+            Section::Objective => objective_tokens.extend(tokenize(line)),
+            Section::Constraints => {
+                pending.extend(tokenize(line));
+                // a constraint may span several lines, until its right-hand side
+                let position = pending.iter().position(|t| is_comparison(t));
+                if let Some(position) = position {
+                    if position + 1 < pending.len() {
+                        let (_, row) = split_row_name(&pending);
+                        let offset = pending.len() - row.len();
+                        let lhs = parse_linear(&pending[offset..position])?;
+                        let rhs = parse_number(&pending[position + 1])
+                            .ok_or_else(|| format!("Invalid right-hand side {}", pending[position + 1]))?;
+                        let op = match pending[position].chars().next() {
+                            Some('<') => Constraint::LessOrEqual,
+                            Some('>') => Constraint::GreaterOrEqual,
+                            _ => match pending[position].chars().nth(1) {
+                                Some('<') => Constraint::LessOrEqual,
+                                Some('>') => Constraint::GreaterOrEqual,
+                                _ => Constraint::Equal,
+                            },
+                        };
+                        constraints.push((lhs, op, rhs));
+                        pending.clear();
+                    }
+                }
+            }
+            Section::Bounds => parse_bound(&tokenize(line), &mut bounds)?,
+            Section::Generals => generals.extend(line.split_whitespace().map(|t| t.to_string())),
+            Section::Binaries => binaries.extend(line.split_whitespace().map(|t| t.to_string())),
+            Section::Sos => sos.push(parse_sos(line)?),
+        }
+    }
+    if !pending.is_empty() {
+        return Err(format!("Incomplete constraint: {}", pending.join(" ")));
+    }
+    let objective_type = objective_type.ok_or("Missing objective sense")?;
+
+    let variable = |name: &str| -> LpExpression {
+        let (lower_bound, upper_bound) = bounds.get(name).cloned().unwrap_or((Some(0.0), None));
+        if binaries.contains(name) {
+            LpBinary::new(name).into()
+        } else if generals.contains(name) {
+            LpInteger { name: name.to_string(), lower_bound, upper_bound }.into()
+        } else {
+            LpContinuous { name: name.to_string(), lower_bound, upper_bound }.into()
+        }
+    };
+    let expression = |terms: &[(String, f64)]| -> LpExpression {
+        lp_sum_iter(terms.iter().map(|(name, coefficient)| {
+            if *coefficient == 1.0 {
+                variable(name)
+            } else {
+                *coefficient * variable(name)
+            }
+        }))
+    };
+
+    let mut problem = LpProblem::new(name, objective_type);
+    let (objective_name, objective_row) = split_row_name(&objective_tokens);
+    let (objective_terms, objective_constant) = parse_linear(objective_row)?;
+    if !objective_terms.is_empty() {
+        problem += expression(&objective_terms);
+    }
+    problem.set_objective_constant(objective_constant);
+    if let Some(objective_name) = objective_name {
+        problem.set_objective_name(&objective_name);
+    }
+    for ((terms, constant), op, rhs) in constraints {
+        let lhs = expression(&terms);
+        problem += match op {
+            Constraint::LessOrEqual => lhs.le(rhs - constant),
+            Constraint::GreaterOrEqual => lhs.ge(rhs - constant),
+            Constraint::Equal => lhs.equal(rhs - constant),
+        };
+    }
+    for set in sos {
+        let weights = set
+            .into_iter()
+            .map(|(name, weight)| {
+                let (lower_bound, upper_bound) = bounds.get(&name).cloned().unwrap_or((Some(0.0), None));
+                (LpContinuous { name, lower_bound, upper_bound }, weight)
+            })
+            .collect();
+        problem += LpSos2(weights);
+    }
+    Ok(problem)
+}
+
+/// Read a problem from an LP file
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_lp(path: &str, name: &'static str) -> Result<LpProblem, LpError> {
+    let content = fs::read_to_string(path)?;
+    parse_lp(&content, name)
+}
@@ -0,0 +1,328 @@
+//! Writer for the MPS format, in its fixed or free flavor.
+//!
+//! The rows are the objective, named as in the LP format, and the constraints `c1`, `c2`, ...
+//! Integer columns are enclosed in `MARKER` lines, binary variables have a `BV` bound and
+//! semi-continuous variables an `SC` bound. The objective constant is written as the opposite
+//! of the right-hand side of the objective row, and maximization in an `OBJSENSE` section.
+
+use std::collections::BTreeMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind, Result};
+
+use dsl::*;
+use dsl::Constraint::*;
+
+/// Width of the name fields of the fixed MPS format
+const FIXED_NAME_WIDTH: usize = 8;
+/// Width of the number fields of the fixed MPS format
+const FIXED_NUMBER_WIDTH: usize = 12;
+/// Starting columns of the fields following the code of a fixed MPS line
+const FIXED_FIELD_COLUMNS: [usize; 5] = [5, 15, 25, 40, 50];
+
+/// Layout of the fields of an MPS file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MpsFlavor {
+    /// Fields at fixed columns: names are limited to 8 characters and numbers to 12
+    Fixed,
+    /// Fields separated by whitespace: names of any length, without spaces
+    Free,
+}
+
+pub trait MpsFileFormat {
+    /// The problem in the MPS format, failing on names the flavor cannot hold
+    fn to_mps(&self, flavor: MpsFlavor) -> Result<String>;
+    /// Write the MPS format into `writer`, e.g. a `Vec<u8>` or a network stream
+    fn write_mps_to<W: Write>(&self, writer: &mut W, flavor: MpsFlavor) -> Result<()> {
+        writer.write_all(self.to_mps(flavor)?.as_bytes())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_mps(&self, file_model: &str, flavor: MpsFlavor) -> Result<()> {
+        let mps = self.to_mps(flavor)?;
+        File::create(file_model)?.write_all(mps.as_bytes())
+    }
+    /// Write the free MPS format, which handles the names too long for the fixed columns
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_mps_free(&self, file_model: &str) -> Result<()> {
+        self.write_mps(file_model, MpsFlavor::Free)
+    }
+}
+
+impl MpsFileFormat for LpProblem {
+    /// # Examples:
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::format::mps_format::{MpsFileFormat, MpsFlavor};
+    ///
+    /// let ref production = LpContinuous::new("production");
+    /// let mut problem = LpProblem::new("Plant", LpObjective::Maximize);
+    /// problem += 3 * production;
+    /// problem += production.le(10);
+    ///
+    /// assert!(problem.to_mps(MpsFlavor::Free).unwrap().contains("    production obj 3\n"));
+    /// // `production` does not fit in the 8 columns of a fixed name field
+    /// assert!(problem.to_mps(MpsFlavor::Fixed).is_err());
+    /// ```
+    fn to_mps(&self, flavor: MpsFlavor) -> Result<String> {
+        let mut buffer = Vec::new();
+        self.write_mps_to(&mut buffer, flavor)?;
+        Ok(String::from_utf8(buffer).expect("The MPS format is written from strings"))
+    }
+
+    /// Write the problem, failing before writing anything if a name or a coefficient cannot be
+    /// written in `flavor`
+    fn write_mps_to<W: Write>(&self, writer: &mut W, flavor: MpsFlavor) -> Result<()> {
+        let rows = rows(self)?;
+        check_names(self, flavor)?;
+        let mut buffer = BufWriter::new(writer);
+        write_problem(self, &rows, flavor, &mut buffer)?;
+        buffer.flush()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_mps(&self, file_model: &str, flavor: MpsFlavor) -> Result<()> {
+        let rows = rows(self)?;
+        check_names(self, flavor)?;
+        let mut buffer = BufWriter::new(File::create(file_model)?);
+        write_problem(self, &rows, flavor, &mut buffer)?;
+        buffer.flush()
+    }
+}
+
+/// Row of the MPS file: its name, its type and its linear terms, with the constant moved to
+/// the right-hand side
+struct Row {
+    name: String,
+    kind: &'static str,
+    terms: Vec<(String, f64)>,
+    rhs: f64,
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+fn objective_name(prob: &LpProblem) -> &str {
+    prob.objective_name.as_ref().map_or("obj", |name| name.as_str())
+}
+
+/// Objective row followed by the constraint rows, failing on non-finite coefficients and
+/// products of variables
+fn rows(prob: &LpProblem) -> Result<Vec<Row>> {
+    prob.check_coefficients().map_err(|e| invalid_data(e.to_string()))?;
+    let linear = |name: &str, expr: &LpExpression| -> Result<(Vec<(String, f64)>, f64)> {
+        match expr.linear_term_nodes() {
+            Some((terms, constant)) => Ok((
+                terms.into_iter().map(|(name, _, coefficient)| (name.to_string(), coefficient)).collect(),
+                constant,
+            )),
+            None => Err(invalid_data(format!("Row {} is not linear", name))),
+        }
+    };
+    let mut rows = Vec::with_capacity(prob.constraints.len() + 1);
+    let name = objective_name(prob);
+    let (terms, _) = match &prob.obj_expr_arena {
+        Some(expr) => linear(name, expr)?,
+        None => (Vec::new(), 0.0),
+    };
+    rows.push(Row { name: name.to_string(), kind: "N", terms, rhs: -prob.objective_constant });
+    for (index, constraint) in prob.constraints.iter().enumerate() {
+        let name = format!("c{}", index + 1);
+        let LpConstraint(lhs, op, rhs) = constraint.generalize();
+        let (terms, constant) = linear(&name, &lhs)?;
+        let (_, rhs) = linear(&name, &rhs)?;
+        let kind = match op {
+            LessOrEqual => "L",
+            GreaterOrEqual => "G",
+            Equal => "E",
+        };
+        rows.push(Row { name, kind, terms, rhs: rhs - constant });
+    }
+    Ok(rows)
+}
+
+/// Fail on names with whitespace, which separates the fields of the free flavor, or too long
+/// for the fields of the fixed flavor
+fn check_names(prob: &LpProblem, flavor: MpsFlavor) -> Result<()> {
+    let sos_names = prob.sos2.iter().enumerate().flat_map(|(index, LpSos2(set))| {
+        std::iter::once(format!("s{}", index + 1)).chain(set.iter().map(|(var, _)| var.name.clone()))
+    });
+    let names = prob
+        .variable_nodes()
+        .into_keys()
+        .chain(std::iter::once(objective_name(prob).to_string()))
+        .chain(sos_names);
+    for name in names {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(invalid_data(format!("Name '{}' cannot be written in the MPS format", name)));
+        }
+        if flavor == MpsFlavor::Fixed && name.len() > FIXED_NAME_WIDTH {
+            return Err(invalid_data(format!(
+                "Name {} is longer than {} characters, use the free MPS format",
+                name, FIXED_NAME_WIDTH
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// `value` as written in `flavor`: numbers of the fixed flavor are shortened to fit their field
+fn number(value: f64, flavor: MpsFlavor) -> String {
+    let exact = value.to_string();
+    if flavor == MpsFlavor::Free || exact.len() <= FIXED_NUMBER_WIDTH {
+        return exact;
+    }
+    (0..FIXED_NUMBER_WIDTH)
+        .rev()
+        .map(|decimals| format!("{:.*e}", decimals, value))
+        .find(|scientific| scientific.len() <= FIXED_NUMBER_WIDTH)
+        .unwrap_or(exact)
+}
+
+/// Data line with a code, e.g. a row or bound type, and its fields
+fn line(flavor: MpsFlavor, code: &str, fields: &[&str]) -> String {
+    match flavor {
+        MpsFlavor::Free => {
+            let mut line = format!(" {:<2}", code);
+            for field in fields.iter().filter(|field| !field.is_empty()) {
+                line.push(' ');
+                line.push_str(field);
+            }
+            line
+        }
+        MpsFlavor::Fixed => {
+            let mut line = format!(" {:<2}", code);
+            for (field, column) in fields.iter().zip(FIXED_FIELD_COLUMNS.iter()) {
+                while line.len() < column - 1 {
+                    line.push(' ');
+                }
+                line.push_str(field);
+            }
+            line
+        }
+    }
+}
+
+fn write_problem<W: Write>(prob: &LpProblem, rows: &[Row], flavor: MpsFlavor, writer: &mut W) -> Result<()> {
+    match flavor {
+        MpsFlavor::Free => writeln!(writer, "NAME {}", prob.name)?,
+        MpsFlavor::Fixed => writeln!(writer, "NAME          {}", prob.name)?,
+    }
+    if prob.objective_type == LpObjective::Maximize {
+        writeln!(writer, "OBJSENSE\n    MAX")?;
+    }
+
+    writeln!(writer, "ROWS")?;
+    for row in rows {
+        writeln!(writer, "{}", line(flavor, row.kind, &[&row.name]))?;
+    }
+
+    // entries of each column, in the order of the rows
+    let variables = prob.variable_nodes();
+    let mut columns: BTreeMap<&str, Vec<(&str, f64)>> = variables.keys().map(|name| (name.as_str(), Vec::new())).collect();
+    for row in rows {
+        for (name, coefficient) in &row.terms {
+            if *coefficient != 0.0 {
+                if let Some(entries) = columns.get_mut(name.as_str()) {
+                    entries.push((&row.name, *coefficient));
+                }
+            }
+        }
+    }
+    writeln!(writer, "COLUMNS")?;
+    let mut in_integer_block = false;
+    for (name, entries) in &columns {
+        let integer = matches!(variables.get(*name), Some(&&LpExprNode::ConsInt(_)) | Some(&&LpExprNode::ConsBin(_)));
+        if integer != in_integer_block {
+            let marker = if integer { "'INTORG'" } else { "'INTEND'" };
+            writeln!(writer, "{}", line(flavor, "", &["MARKER", "'MARKER'", "", marker]))?;
+            in_integer_block = integer;
+        }
+        if entries.is_empty() {
+            // a column without entries is declared with a zero objective coefficient
+            writeln!(writer, "{}", line(flavor, "", &[name, &rows[0].name, "0"]))?;
+        }
+        for (row, coefficient) in entries {
+            writeln!(writer, "{}", line(flavor, "", &[name, row, &number(*coefficient, flavor)]))?;
+        }
+    }
+    if in_integer_block {
+        writeln!(writer, "{}", line(flavor, "", &["MARKER", "'MARKER'", "", "'INTEND'"]))?;
+    }
+
+    writeln!(writer, "RHS")?;
+    for row in rows {
+        if row.rhs != 0.0 {
+            writeln!(writer, "{}", line(flavor, "", &["RHS", &row.name, &number(row.rhs, flavor)]))?;
+        }
+    }
+
+    let bounds = bounds_mps_block(prob, flavor);
+    if !bounds.is_empty() {
+        write!(writer, "BOUNDS\n{}", bounds)?;
+    }
+
+    if !prob.sos2.is_empty() {
+        writeln!(writer, "SOS")?;
+        for (index, LpSos2(set)) in prob.sos2.iter().enumerate() {
+            let name = format!("s{}", index + 1);
+            writeln!(writer, "{}", line(flavor, "S2", &["SOS", &name]))?;
+            for (var, weight) in set {
+                writeln!(writer, "{}", line(flavor, "", &[&name, &var.name, &number(*weight, flavor)]))?;
+            }
+        }
+    }
+
+    writeln!(writer, "ENDATA")
+}
+
+/// Bounds of the variables, with the defaults of the LP format: a variable without lower bound
+/// is nonnegative, unless it is a free continuous variable
+fn bounds_mps_block(prob: &LpProblem, flavor: MpsFlavor) -> String {
+    let mut res = String::new();
+    let mut bound = |code: &str, name: &str, value: Option<f64>| {
+        let value = value.map(|v| number(v, flavor));
+        let mut fields = vec!["BND", name];
+        fields.extend(value.as_deref());
+        res.push_str(&line(flavor, code, &fields));
+        res.push('\n');
+    };
+    for (name, node) in prob.variable_nodes() {
+        match node {
+            &LpExprNode::ConsBin(_) => bound("BV", &name, None),
+            &LpExprNode::ConsInt(LpInteger { lower_bound, upper_bound, .. })
+            | &LpExprNode::ConsCont(LpContinuous { lower_bound, upper_bound, .. }) => {
+                match (lower_bound, upper_bound) {
+                    (Some(l), Some(u)) if l == u => bound("FX", &name, Some(l)),
+                    (None, None) => {
+                        if let &LpExprNode::ConsCont(_) = node {
+                            bound("FR", &name, None);
+                        }
+                    }
+                    (lower_bound, upper_bound) => {
+                        let lower = lower_bound.unwrap_or(0.0);
+                        // some readers make a variable with a negative upper bound unbounded
+                        // below, unless its lower bound is explicit
+                        if lower != 0.0 || matches!(upper_bound, Some(u) if u < 0.0) {
+                            bound("LO", &name, Some(lower));
+                        }
+                        if let Some(u) = upper_bound {
+                            bound("UP", &name, Some(u));
+                        }
+                    }
+                }
+            }
+            // the lower bound of a semi-continuous variable applies when it is not zero
+            &LpExprNode::ConsSemiCont(LpSemiContinuous { lower, upper, .. }) => {
+                if lower != 0.0 {
+                    bound("LO", &name, Some(lower));
+                }
+                bound("SC", &name, Some(upper));
+            }
+            _ => (),
+        }
+    }
+    res
+}
@@ -0,0 +1,271 @@
+//! Reader for the MPS format, as written by `MpsFileFormat`.
+//!
+//! The fields are separated by whitespace, so that both the free flavor and the fixed flavor
+//! without spaces in the names are read. Supported sections: `NAME`, `OBJSENSE`, `ROWS`,
+//! `COLUMNS` (with integer markers), `RHS`, `BOUNDS`, `SOS` (type 2 only) and `ENDATA`.
+//! The first `N` row is the objective, other `N` rows are ignored. As in the LP format,
+//! variables without bounds are nonnegative.
+
+use std::collections::{HashMap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+
+use dsl::*;
+use error::LpError;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Header,
+    ObjectiveSense,
+    Rows,
+    Columns,
+    Rhs,
+    Bounds,
+    Sos,
+    End,
+}
+
+fn section_of(keyword: &str) -> Option<Section> {
+    match keyword.to_uppercase().as_str() {
+        "NAME" => Some(Section::Header),
+        "OBJSENSE" => Some(Section::ObjectiveSense),
+        "ROWS" => Some(Section::Rows),
+        "COLUMNS" => Some(Section::Columns),
+        "RHS" => Some(Section::Rhs),
+        "BOUNDS" => Some(Section::Bounds),
+        "SOS" => Some(Section::Sos),
+        "ENDATA" => Some(Section::End),
+        _ => None,
+    }
+}
+
+fn objective_sense(token: &str) -> Result<LpObjective, String> {
+    match token.to_uppercase().as_str() {
+        "MAX" | "MAXIMIZE" => Ok(LpObjective::Maximize),
+        "MIN" | "MINIMIZE" => Ok(LpObjective::Minimize),
+        _ => Err(format!("Invalid objective sense {}", token)),
+    }
+}
+
+fn parse_number(token: &str) -> Result<f64, String> {
+    match token.to_lowercase().as_str() {
+        "inf" | "+inf" | "infinity" | "+infinity" => Ok(f64::INFINITY),
+        "-inf" | "-infinity" => Ok(f64::NEG_INFINITY),
+        _ => token.parse::<f64>().map_err(|_| format!("Invalid number {}", token)),
+    }
+}
+
+/// Pairs of row names and values ending a line of the `COLUMNS` or `RHS` section
+fn row_values<'a>(tokens: &[&'a str]) -> Result<Vec<(&'a str, f64)>, String> {
+    let pairs = tokens.chunks_exact(2);
+    if tokens.is_empty() || !pairs.remainder().is_empty() {
+        return Err(format!("Invalid row values: {}", tokens.join(" ")));
+    }
+    pairs.map(|pair| Ok((pair[0], parse_number(pair[1])?))).collect()
+}
+
+/// Parse a problem written in the MPS format
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::format::mps_reader::parse_mps;
+///
+/// let content = "NAME Parsed\nROWS\n N obj\n L c1\nCOLUMNS\n x obj 3 c1 1\n y obj 2 c1 1\nRHS\n RHS c1 4\nENDATA\n";
+/// let problem = parse_mps(content, "Parsed").unwrap();
+/// assert_eq!(problem.constraints.len(), 1);
+/// ```
+pub fn parse_mps(content: &str, name: &'static str) -> Result<LpProblem, LpError> {
+    parse_problem(content, name).map_err(LpError::ParseMps)
+}
+
+fn parse_problem(content: &str, name: &'static str) -> Result<LpProblem, String> {
+    let mut section = Section::Header;
+    let mut objective_type = LpObjective::Minimize;
+    let mut objective_row: Option<String> = None;
+    let mut rows: Vec<(String, Constraint)> = Vec::new();
+    let mut row_terms: HashMap<String, Vec<(String, f64)>> = HashMap::new();
+    let mut rhs: HashMap<String, f64> = HashMap::new();
+    let mut in_integer_block = false;
+    let mut integers: HashSet<String> = HashSet::new();
+    let mut binaries: HashSet<String> = HashSet::new();
+    let mut semi_continuous: HashMap<String, f64> = HashMap::new();
+    let mut bounds: HashMap<String, (Option<f64>, Option<f64>)> = HashMap::new();
+    let mut sos: Vec<Vec<(String, f64)>> = Vec::new();
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.starts_with('*') {
+            continue;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        // section headers start at the first column, data lines are indented
+        if !line.starts_with(char::is_whitespace) {
+            section = section_of(tokens[0]).ok_or_else(|| format!("Unknown section {}", tokens[0]))?;
+            if section == Section::ObjectiveSense && tokens.len() > 1 {
+                objective_type = objective_sense(tokens[1])?;
+            }
+            continue;
+        }
+        match section {
+            Section::Header | Section::End => return Err(format!("Unexpected line outside of a section: {}", line)),
+            Section::ObjectiveSense => objective_type = objective_sense(tokens[0])?,
+            Section::Rows => {
+                let (kind, row) = match tokens.as_slice() {
+                    [kind, row] => (kind.to_uppercase(), row.to_string()),
+                    _ => return Err(format!("Invalid row: {}", line.trim())),
+                };
+                match kind.as_str() {
+                    "N" if objective_row.is_none() => objective_row = Some(row.clone()),
+                    "N" => (),
+                    "L" => rows.push((row.clone(), Constraint::LessOrEqual)),
+                    "G" => rows.push((row.clone(), Constraint::GreaterOrEqual)),
+                    "E" => rows.push((row.clone(), Constraint::Equal)),
+                    _ => return Err(format!("Invalid row type {}", kind)),
+                }
+                row_terms.insert(row, Vec::new());
+            }
+            Section::Columns => {
+                if tokens.len() > 2 && tokens[1] == "'MARKER'" {
+                    match tokens.last() {
+                        Some(&"'INTORG'") => in_integer_block = true,
+                        Some(&"'INTEND'") => in_integer_block = false,
+                        _ => return Err(format!("Invalid marker: {}", line.trim())),
+                    }
+                    continue;
+                }
+                let column = tokens[0].to_string();
+                if in_integer_block {
+                    integers.insert(column.clone());
+                }
+                for (row, value) in row_values(&tokens[1..])? {
+                    row_terms
+                        .get_mut(row)
+                        .ok_or_else(|| format!("Unknown row {}", row))?
+                        .push((column.clone(), value));
+                }
+            }
+            Section::Rhs => {
+                // the name of the right-hand side vector is optional: it makes the count odd
+                let values = &tokens[tokens.len() % 2..];
+                for (row, value) in row_values(values)? {
+                    if !row_terms.contains_key(row) {
+                        return Err(format!("Unknown row {}", row));
+                    }
+                    rhs.insert(row.to_string(), value);
+                }
+            }
+            Section::Bounds => {
+                let kind = tokens[0].to_uppercase();
+                let with_value = !["FR", "MI", "PL", "BV"].contains(&kind.as_str());
+                // the name of the bound vector is optional
+                let (column, value) = match (with_value, tokens.len()) {
+                    (true, 3) | (true, 4) => (tokens[tokens.len() - 2], Some(parse_number(tokens[tokens.len() - 1])?)),
+                    (false, 2) | (false, 3) => (tokens[tokens.len() - 1], None),
+                    _ => return Err(format!("Invalid bound: {}", line.trim())),
+                };
+                let finite = value.filter(|v| v.is_finite());
+                let entry = bounds.entry(column.to_string()).or_insert((Some(0.0), None));
+                match kind.as_str() {
+                    "UP" => entry.1 = finite,
+                    "LO" => entry.0 = finite,
+                    "FX" => *entry = (finite, finite),
+                    "FR" => *entry = (None, None),
+                    "MI" => entry.0 = None,
+                    "PL" => entry.1 = None,
+                    "BV" => {
+                        binaries.insert(column.to_string());
+                    }
+                    "LI" | "UI" => {
+                        integers.insert(column.to_string());
+                        if kind == "LI" {
+                            entry.0 = finite;
+                        } else {
+                            entry.1 = finite;
+                        }
+                    }
+                    "SC" => {
+                        semi_continuous.insert(column.to_string(), value.unwrap_or(f64::INFINITY));
+                    }
+                    _ => return Err(format!("Invalid bound type {}", kind)),
+                }
+            }
+            Section::Sos => match tokens.as_slice() {
+                [kind, "SOS", ..] if kind.to_uppercase() == "S2" => sos.push(Vec::new()),
+                [kind, "SOS", ..] => return Err(format!("Only SOS of type 2 are supported: {}", kind)),
+                // members are written `set variable weight`, `variable weight` or `variable:weight`
+                members => {
+                    let (variable, weight) = match members {
+                        [_, variable, weight] | [variable, weight] => (variable.to_string(), parse_number(weight)?),
+                        [member] => match member.rfind(':') {
+                            Some(index) => (member[..index].to_string(), parse_number(&member[index + 1..])?),
+                            None => return Err(format!("Invalid SOS member: {}", member)),
+                        },
+                        _ => return Err(format!("Invalid SOS member: {}", line.trim())),
+                    };
+                    sos.last_mut().ok_or("SOS member before its set")?.push((variable, weight));
+                }
+            },
+        }
+    }
+    let objective_row = objective_row.ok_or("Missing objective row")?;
+
+    let variable = |name: &str| -> LpExpression {
+        let (lower_bound, upper_bound) = bounds.get(name).cloned().unwrap_or((Some(0.0), None));
+        if binaries.contains(name) {
+            LpBinary::new(name).into()
+        } else if let Some(&upper) = semi_continuous.get(name) {
+            LpSemiContinuous::new(name, lower_bound.unwrap_or(0.0), upper).into()
+        } else if integers.contains(name) {
+            LpInteger { name: name.to_string(), lower_bound, upper_bound }.into()
+        } else {
+            LpContinuous { name: name.to_string(), lower_bound, upper_bound }.into()
+        }
+    };
+    let expression = |terms: &[(String, f64)]| -> LpExpression {
+        lp_sum_iter(terms.iter().map(|(name, coefficient)| {
+            if *coefficient == 1.0 {
+                variable(name)
+            } else {
+                *coefficient * variable(name)
+            }
+        }))
+    };
+
+    let mut problem = LpProblem::new(name, objective_type);
+    let objective_terms = &row_terms[&objective_row];
+    if !objective_terms.is_empty() {
+        problem += expression(objective_terms);
+    }
+    // the right-hand side of the objective row is the opposite of its constant
+    problem.set_objective_constant(-rhs.get(&objective_row).cloned().unwrap_or(0.0));
+    if objective_row != "obj" {
+        problem.set_objective_name(&objective_row);
+    }
+    for (row, op) in rows {
+        let lhs = expression(&row_terms[&row]);
+        let rhs = rhs.get(&row).cloned().unwrap_or(0.0);
+        problem += match op {
+            Constraint::LessOrEqual => lhs.le(rhs),
+            Constraint::GreaterOrEqual => lhs.ge(rhs),
+            Constraint::Equal => lhs.equal(rhs),
+        };
+    }
+    for set in sos {
+        let weights = set
+            .into_iter()
+            .map(|(name, weight)| {
+                let (lower_bound, upper_bound) = bounds.get(&name).cloned().unwrap_or((Some(0.0), None));
+                (LpContinuous { name, lower_bound, upper_bound }, weight)
+            })
+            .collect();
+        problem += LpSos2(weights);
+    }
+    Ok(problem)
+}
+
+/// Read a problem from an MPS file
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_mps(path: &str, name: &'static str) -> Result<LpProblem, LpError> {
+    let content = fs::read_to_string(path)?;
+    parse_mps(&content, name)
+}
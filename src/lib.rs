@@ -1,3 +1,4 @@
+#[cfg(all(feature = "uuid", not(target_arch = "wasm32")))]
 extern crate uuid;
 extern crate proc_macro2;
 extern crate quote;
@@ -6,9 +7,15 @@ extern crate quote;
 extern crate coin_cbc;
 #[cfg(feature = "minilp")]
 extern crate minilp;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 
 pub mod util;
 
+pub mod error;
+
 pub mod dsl {
     pub mod variables;
     pub use self::variables::*;
@@ -16,10 +23,17 @@ pub mod dsl {
     pub use self::operations::*;
     pub mod problem;
     pub use self::problem::*;
+    pub mod formulations;
+    pub use self::formulations::*;
 }
 
 pub mod format {
    pub mod lp_format;
+   pub mod lp_reader;
+   pub mod mps_format;
+   pub mod mps_reader;
 }
 
 pub mod solvers;
+
+pub mod prelude;
@@ -0,0 +1,53 @@
+//! # Prelude
+//!
+//! Types and traits used in a typical modeling session: the variables, the problem and its
+//! operators, the solvers enabled by the features and their traits.
+//!
+//! # Examples:
+//!
+//! ```
+//! # #[cfg(feature = "minilp")]
+//! # fn main() {
+//! use lp_modeler::prelude::*;
+//!
+//! let ref a = LpInteger::new("a");
+//! let ref b = LpContinuous::new("b");
+//!
+//! let mut problem = LpProblem::new("Prelude", LpObjective::Maximize);
+//! problem += 2 * a + b;
+//! problem += (a + b).le(10);
+//! problem += lp_sum(&vec![a, a]).ge(b);
+//! problem += b.ge(0);
+//!
+//! let solution = MiniLpSolver::new().relax_integers().run(&problem).unwrap();
+//! assert_eq!(solution.status, Status::Optimal);
+//! assert_eq!(solution.get_int(a), 10);
+//! # }
+//! # #[cfg(not(feature = "minilp"))]
+//! # fn main() {}
+//! ```
+
+pub use dsl::{
+    lp_sum, lp_sum_iter, BoundableLp, LpBinary, LpConstraint, LpContinuous, LpExpression, LpInteger, LpObjective,
+    LpOperations, LpProblem, LpSemiContinuous, NamedVariable, Problem, SummableExp,
+};
+pub use error::LpError;
+pub use format::lp_format::LpFileFormat;
+pub use solvers::{Solution, SolverTrait, Status, WithMaxSeconds, WithNbThreads, WithWarmStart};
+
+#[cfg(all(feature = "cbc", not(target_arch = "wasm32")))]
+pub use solvers::CbcSolver;
+#[cfg(all(feature = "glpk", not(target_arch = "wasm32")))]
+pub use solvers::GlpkSolver;
+#[cfg(all(feature = "gurobi", not(target_arch = "wasm32")))]
+pub use solvers::GurobiSolver;
+#[cfg(all(feature = "scip", not(target_arch = "wasm32")))]
+pub use solvers::ScipSolver;
+#[cfg(all(feature = "lpsolve", not(target_arch = "wasm32")))]
+pub use solvers::LpSolveSolver;
+#[cfg(feature = "minilp")]
+pub use solvers::MiniLpSolver;
+#[cfg(feature = "native_coin_cbc")]
+pub use solvers::NativeCbcSolver;
+#[cfg(all(feature = "glpk-ffi", not(target_arch = "wasm32")))]
+pub use solvers::GlpkFfiSolver;
@@ -1,23 +1,29 @@
-extern crate uuid;
-use self::uuid::Uuid;
-
 use std::fs;
 use std::collections::HashMap;
+use std::env;
 use std::fs::File;
-use std::io::{BufReader, BufRead};
-use std::process::Command;
+use std::io;
+use std::io::{BufReader, BufRead, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
 
 use dsl::LpProblem;
+use error::LpError;
 use format::lp_format::*;
-use solvers::{Status, SolverTrait, WithMaxSeconds, WithNbThreads, SolverWithSolutionParsing, Solution};
+use solvers::{DEFAULT_INTEGER_TOLERANCE, Status, SolverTrait, WithMaxSeconds, WithNbThreads, WithWarmStart, SolverWithSolutionParsing, Solution, command_available, sorted_warm_start, process_error, wait_cancellable, work_file, solution_file, run_file_stem};
 
 #[derive(Debug, Clone)]
 pub struct CbcSolver {
     name: String,
     command_name: String,
-    temp_solution_file: String,
+    temp_solution_file: Option<String>,
+    work_dir: PathBuf,
     threads: Option<u32>,
     seconds: Option<u32>,
+    warm_start: Option<HashMap<String, f64>>,
+    integer_tolerance: f64,
 }
 
 impl CbcSolver {
@@ -25,9 +31,12 @@ impl CbcSolver {
         CbcSolver {
             name: "Cbc".to_string(),
             command_name: "cbc".to_string(),
-            temp_solution_file: format!("{}.sol", Uuid::new_v4().to_string()),
+            temp_solution_file: None,
+            work_dir: env::temp_dir(),
             threads: None,
             seconds: None,
+            warm_start: None,
+            integer_tolerance: DEFAULT_INTEGER_TOLERANCE,
         }
     }
 
@@ -36,8 +45,11 @@ impl CbcSolver {
             name: self.name.clone(),
             command_name,
             temp_solution_file: self.temp_solution_file.clone(),
+            work_dir: self.work_dir.clone(),
             threads: None,
             seconds: None,
+            warm_start: self.warm_start.clone(),
+            integer_tolerance: self.integer_tolerance,
         }
     }
 
@@ -45,15 +57,59 @@ impl CbcSolver {
         CbcSolver {
             name: self.name.clone(),
             command_name: self.command_name.clone(),
-            temp_solution_file,
+            temp_solution_file: Some(temp_solution_file),
+            work_dir: self.work_dir.clone(),
             threads: None,
             seconds: None,
+            warm_start: self.warm_start.clone(),
+            integer_tolerance: self.integer_tolerance,
+        }
+    }
+
+    /// Create the model and solution files in `work_dir`, the temporary directory of the system
+    /// by default
+    pub fn with_work_dir<P: Into<PathBuf>>(&self, work_dir: P) -> CbcSolver {
+        CbcSolver {
+            work_dir: work_dir.into(),
+            ..self.clone()
+        }
+    }
+
+    /// Round the values of integer variables within `epsilon` of an integer,
+    /// `DEFAULT_INTEGER_TOLERANCE` by default
+    pub fn with_integer_tolerance(&self, epsilon: f64) -> CbcSolver {
+        CbcSolver {
+            integer_tolerance: epsilon,
+            ..self.clone()
+        }
+    }
+
+    /// Whether the solver executable can be found, checked by `run` before writing any file
+    pub fn is_available(&self) -> bool {
+        command_available(&self.command_name)
+    }
+}
+
+impl CbcSolver {
+    /// Write the warm start values as a Cbc solution file, as read by its `mipstart` command,
+    /// with the variables renamed as in the LP file by `names`, see `sanitized_names`
+    pub fn write_warm_start(&self, start_file: &str, names: &HashMap<String, String>) -> io::Result<()> {
+        let mut buffer = File::create(start_file)?;
+        writeln!(buffer, "Stopped on iterations - objective value 0.00000000")?;
+        if let Some(values) = &self.warm_start {
+            for (index, (name, value)) in sorted_warm_start(values, names).into_iter().enumerate() {
+                writeln!(buffer, "{:>7} {} {}", index, name, value)?;
+            }
         }
+        Ok(())
     }
 }
 
 impl SolverWithSolutionParsing for CbcSolver {
-    fn read_specific_solution<'a>(&self, f: &File, problem: Option<&'a LpProblem>) -> Result<Solution<'a>, String> {
+    fn integer_tolerance(&self) -> f64 {
+        self.integer_tolerance
+    }
+    fn read_specific_solution<'a>(&self, f: &File, problem: Option<&'a LpProblem>) -> Result<Solution<'a>, LpError> {
         let mut vars_value: HashMap<_, _> = HashMap::new();
 
         // populate default values for all vars
@@ -80,7 +136,7 @@ impl SolverWithSolutionParsing for CbcSolver {
                 _ => Status::NotSolved,
             }
         } else {
-            return Err("Incorrect solution format".to_string());
+            return Err(LpError::ParseSolution("Incorrect solution format".to_string()));
         };
         for line in file.lines() {
             let l = line.unwrap();
@@ -93,10 +149,10 @@ impl SolverWithSolutionParsing for CbcSolver {
                     Ok(n) => {
                         vars_value.insert(result_line[1].to_string(), n);
                     }
-                    Err(e) => return Err(e.to_string()),
+                    Err(e) => return Err(LpError::ParseSolution(e.to_string())),
                 }
             } else {
-                return Err("Incorrect solution format".to_string());
+                return Err(LpError::ParseSolution("Incorrect solution format".to_string()));
             }
         }
         if let Some(p) = problem {
@@ -130,12 +186,44 @@ impl WithNbThreads<CbcSolver> for CbcSolver {
     }
 }
 
+impl WithWarmStart<CbcSolver> for CbcSolver {
+    fn warm_start(&self) -> Option<&HashMap<String, f64>> {
+        self.warm_start.as_ref()
+    }
+    fn with_warm_start(&self, values: HashMap<String, f64>) -> CbcSolver {
+        CbcSolver {
+            warm_start: Some(values),
+            ..(*self).clone()
+        }
+    }
+}
+
 impl SolverTrait for CbcSolver {
     type P = LpProblem;
 
-    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
-        let file_model = format!("{}.lp", problem.unique_name);
-        problem.write_lp(&file_model).map_err(|e| e.to_string())?;
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+        self.run_cancellable(problem, &AtomicBool::new(false))
+    }
+
+    fn run_cancellable<'a>(&self, problem: &'a Self::P, cancel: &AtomicBool) -> Result<Solution<'a>, LpError> {
+        problem.check_not_empty()?;
+        if !self.is_available() {
+            return Err(LpError::SolverNotFound(self.name.clone()));
+        }
+        let stem = run_file_stem(problem);
+        let file_model = work_file(&self.work_dir, &format!("{}.lp", stem));
+        let start_file = work_file(&self.work_dir, &format!("{}_start.sol", stem));
+        let solution_file = solution_file(&self.work_dir, self.temp_solution_file.as_ref(), &file_model);
+        let names = sanitized_names(problem);
+        write_renamed_lp(problem, &names, &file_model)?;
+        let mut start_params = Vec::new();
+        if self.warm_start.is_some() {
+            if let Err(e) = self.write_warm_start(&start_file, &names) {
+                let _ = fs::remove_file(file_model);
+                return Err(e.into());
+            }
+            start_params = vec!["mipstart".to_string(), start_file.clone()];
+        }
 
         let mut params: HashMap<String, String> = Default::default();
         let optional_params: Vec<Option<(String, u32)>> = vec![
@@ -147,23 +235,45 @@ impl SolverTrait for CbcSolver {
         }
         params.iter().for_each( |(a,b)| println!("{},{}",a,b));
 
-        let result = Command::new(&self.command_name)
+        let start = Instant::now();
+        let output = Command::new(&self.command_name)
             .arg(&file_model)
             .args(params.iter().flat_map(|(k, v)| vec![k, v]))
+            .args(&start_params)
             .arg("solve")
             .arg("solution")
-            .arg(&self.temp_solution_file)
-            .output()
-            .map_err(|_| format!("Error running the {} solver", self.name))
-            .and_then(|r| {
-                if r.status.success() {
-                    self.read_solution(&self.temp_solution_file, Some(problem))
-                } else {
-                    Err(r.status.to_string())
+            .arg(&solution_file)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .and_then(|child| wait_cancellable(child, cancel, self.seconds.map(|s| Duration::from_secs(s.into()))));
+        let solve_time = start.elapsed();
+        let result = output
+            .map_err(|e| {
+                let _ = fs::remove_file(&solution_file);
+                process_error(&self.name, e)
+            })
+            .and_then(|r| match r {
+                Some(r) => {
+                    if r.status.success() {
+                        self.read_renamed_solution(&solution_file, Some(problem), &names).map(|solution| {
+                            Solution {
+                                solve_time: Some(solve_time),
+                                ..solution
+                            }
+                        })
+                    } else {
+                        Err(LpError::Solver(r.status.to_string()))
+                    }
+                }
+                None => {
+                    let _ = fs::remove_file(&solution_file);
+                    Err(LpError::Cancelled(Some(self.name.clone())))
                 }
             });
 
         let _ = fs::remove_file(file_model);
+        let _ = fs::remove_file(start_file);
         result
     }
 }
@@ -0,0 +1,125 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use dsl::LpProblem;
+use error::LpError;
+use solvers::{Solution, SolverTrait};
+
+/// Solver racing several solvers on the same problem and returning the first successful result.
+///
+/// Every solver runs in its own thread. As soon as one of them returns a `Solution`,
+/// the others are cancelled: process based solvers kill their child process and remove
+/// their temporary files.
+///
+/// # Examples:
+///
+/// ```
+/// # #[cfg(all(feature = "glpk", feature = "cbc"))]
+/// # fn main() {
+/// use lp_modeler::solvers::{FirstOf, GlpkSolver, CbcSolver};
+///
+/// let solver = FirstOf::new(vec![Box::new(GlpkSolver::new()), Box::new(CbcSolver::new())]);
+/// # }
+/// # #[cfg(not(all(feature = "glpk", feature = "cbc")))]
+/// # fn main() {}
+/// ```
+pub struct FirstOf {
+    solvers: Vec<Box<dyn SolverTrait<P = LpProblem> + Send + Sync>>,
+}
+
+impl FirstOf {
+    pub fn new(solvers: Vec<Box<dyn SolverTrait<P = LpProblem> + Send + Sync>>) -> FirstOf {
+        FirstOf { solvers }
+    }
+}
+
+impl SolverTrait for FirstOf {
+    type P = LpProblem;
+
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+        self.run_cancellable(problem, &AtomicBool::new(false))
+    }
+
+    fn run_cancellable<'a>(&self, problem: &'a Self::P, cancel: &AtomicBool) -> Result<Solution<'a>, LpError> {
+        if self.solvers.is_empty() {
+            return Err(LpError::Solver("No solver to run".to_string()));
+        }
+        let race_over = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+            for solver in &self.solvers {
+                let sender = sender.clone();
+                let race_over = &race_over;
+                scope.spawn(move || {
+                    let _ = sender.send(solver.run_cancellable(problem, race_over));
+                });
+            }
+            drop(sender);
+
+            let mut errors = Vec::new();
+            let result = loop {
+                match receiver.recv_timeout(Duration::from_millis(10)) {
+                    Ok(Ok(solution)) => break Ok(solution),
+                    Ok(Err(e)) => errors.push(e.to_string()),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        if cancel.load(Ordering::SeqCst) {
+                            break Err(LpError::Cancelled(None));
+                        }
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        break Err(LpError::Solver(format!("All solvers failed: {}", errors.join("; "))))
+                    }
+                }
+            };
+            // Stop the remaining solvers, the scope waits for them before returning
+            race_over.store(true, Ordering::SeqCst);
+            result
+        })
+    }
+}
+
+#[cfg(all(test, feature = "minilp"))]
+mod tests {
+    use super::*;
+    use dsl::*;
+    use solvers::MiniLpSolver;
+    use std::time::Instant;
+
+    struct SlowSolver;
+
+    impl SolverTrait for SlowSolver {
+        type P = LpProblem;
+        fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+            self.run_cancellable(problem, &AtomicBool::new(false))
+        }
+        fn run_cancellable<'a>(&self, _problem: &'a Self::P, cancel: &AtomicBool) -> Result<Solution<'a>, LpError> {
+            for _ in 0..1000 {
+                if cancel.load(Ordering::SeqCst) {
+                    return Err(LpError::Cancelled(None));
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            Err(LpError::Solver("too slow".to_string()))
+        }
+    }
+
+    #[test]
+    fn first_of_returns_fastest_solution() {
+        let a = &LpContinuous::new("a");
+        let b = &LpContinuous::new("b");
+        let mut problem = LpProblem::new("Race", LpObjective::Maximize);
+        problem += a + b;
+        problem += a.le(3);
+        problem += b.le(4);
+
+        let expected = MiniLpSolver::new().run(&problem).unwrap().results;
+
+        let solver = FirstOf::new(vec![Box::new(SlowSolver), Box::new(MiniLpSolver::new())]);
+        let start = Instant::now();
+        let solution = solver.run(&problem).unwrap();
+        assert_eq!(solution.results, expected);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
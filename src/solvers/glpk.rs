@@ -1,20 +1,33 @@
-extern crate uuid;
-use self::uuid::Uuid;
-
 use std::fs;
 use std::collections::HashMap;
+use std::env;
 use std::fs::File;
+use std::io;
 use std::io::{Error, BufReader, BufRead};
-use std::process::Command;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
 
 use dsl::LpProblem;
+use error::LpError;
 use format::lp_format::*;
-use solvers::{Status, SolverTrait, SolverWithSolutionParsing, Solution};
+use solvers::{DEFAULT_INTEGER_TOLERANCE, Ranging, Sensitivity, Status, SolverTrait, SolverWithSolutionParsing, Solution, WithNbThreads, WithWarmStart, command_available, compute_iis, restore_names, process_error, run_lowered, wait_cancellable, work_file, solution_file, run_file_stem};
 
+#[derive(Debug, Clone)]
 pub struct GlpkSolver {
     name: String,
     command_name: String,
-    temp_solution_file: String,
+    temp_solution_file: Option<String>,
+    work_dir: PathBuf,
+    ranges_file: Option<String>,
+    warm_start: Option<HashMap<String, f64>>,
+    lp_relaxation: bool,
+    initial_basis: Option<String>,
+    saved_basis: Option<String>,
+    threads: Option<u32>,
+    integer_tolerance: f64,
+    options: Vec<String>,
 }
 
 impl GlpkSolver {
@@ -22,37 +35,249 @@ impl GlpkSolver {
         GlpkSolver {
             name: "Glpk".to_string(),
             command_name: "glpsol".to_string(),
-            temp_solution_file: format!("{}.sol", Uuid::new_v4().to_string()),
+            temp_solution_file: None,
+            work_dir: env::temp_dir(),
+            ranges_file: None,
+            warm_start: None,
+            lp_relaxation: false,
+            initial_basis: None,
+            saved_basis: None,
+            threads: None,
+            integer_tolerance: DEFAULT_INTEGER_TOLERANCE,
+            options: Vec::new(),
         }
     }
     pub fn command_name(&self, command_name: String) -> GlpkSolver {
         GlpkSolver {
-            name: self.name.clone(),
             command_name,
-            temp_solution_file: self.temp_solution_file.clone(),
+            ..self.clone()
         }
     }
     pub fn with_temp_solution_file(&self, temp_solution_file: String) -> GlpkSolver {
         GlpkSolver {
-            name: self.name.clone(),
-            command_name: self.command_name.clone(),
-            temp_solution_file,
+            temp_solution_file: Some(temp_solution_file),
+            ..self.clone()
+        }
+    }
+    /// Create the model and solution files in `work_dir`, the temporary directory of the system
+    /// by default
+    pub fn with_work_dir<P: Into<PathBuf>>(&self, work_dir: P) -> GlpkSolver {
+        GlpkSolver {
+            work_dir: work_dir.into(),
+            ..self.clone()
+        }
+    }
+    /// Ask glpsol to write its sensitivity analysis report into `ranges_file`, relative to the
+    /// working directory (see `with_work_dir`) unless it is absolute.
+    /// The report is parsed and attached to the solution.
+    /// glpsol only analyses continuous problems: `run` fails on problems with integer variables,
+    /// unless they are relaxed with `with_lp_relaxation`.
+    pub fn with_ranges(&self, ranges_file: String) -> GlpkSolver {
+        GlpkSolver {
+            ranges_file: Some(ranges_file),
+            ..self.clone()
+        }
+    }
+    /// Solve the LP relaxation of the problem: glpsol ignores the integer restrictions (`--nomip`)
+    pub fn with_lp_relaxation(&self) -> GlpkSolver {
+        GlpkSolver {
+            lp_relaxation: true,
+            ..self.clone()
+        }
+    }
+
+    /// Start the simplex from the basis saved in `basis_file` by a previous run
+    /// (see `with_saved_basis`), e.g. to re-solve a slightly perturbed LP (`--ini`)
+    pub fn with_initial_basis(&self, basis_file: String) -> GlpkSolver {
+        GlpkSolver {
+            initial_basis: Some(basis_file),
+            ..self.clone()
+        }
+    }
+    /// Save the final basis into `basis_file`, in the GLPK plain text solution format (`-w`)
+    pub fn with_saved_basis(&self, basis_file: String) -> GlpkSolver {
+        GlpkSolver {
+            saved_basis: Some(basis_file),
+            ..self.clone()
+        }
+    }
+
+    /// Pass a raw argument to glpsol, e.g. `--exact` or `--mipgap`, before the output file
+    pub fn with_option(&self, option: String) -> GlpkSolver {
+        let mut options = self.options.clone();
+        options.push(option);
+        GlpkSolver {
+            options,
+            ..self.clone()
+        }
+    }
+    /// Pass raw arguments to glpsol, e.g. `vec!["--mipgap".to_string(), "0.01".to_string()]`
+    pub fn with_options(&self, options: Vec<String>) -> GlpkSolver {
+        let mut all = self.options.clone();
+        all.extend(options);
+        GlpkSolver {
+            options: all,
+            ..self.clone()
+        }
+    }
+
+    /// Time limit given to glpsol by a `--tmlim` option, in seconds
+    pub fn time_limit(&self) -> Option<Duration> {
+        let position = self.options.iter().position(|option| option == "--tmlim")?;
+        let seconds = self.options.get(position + 1)?.parse::<u64>().ok()?;
+        Some(Duration::from_secs(seconds))
+    }
+
+    /// Arguments given to glpsol to solve the model written in `file_model`
+    pub fn arguments(&self, file_model: &str) -> Vec<String> {
+        let mut arguments = vec!["--lp".to_string(), file_model.to_string()];
+        if self.lp_relaxation {
+            arguments.push("--nomip".to_string());
+        }
+        if let Some(basis_file) = &self.initial_basis {
+            arguments.push("--ini".to_string());
+            arguments.push(basis_file.clone());
+        }
+        if let Some(basis_file) = &self.saved_basis {
+            arguments.push("-w".to_string());
+            arguments.push(basis_file.clone());
+        }
+        if let Some(ranges_file) = &self.ranges_file {
+            arguments.push("--ranges".to_string());
+            arguments.push(work_file(&self.work_dir, ranges_file));
+        }
+        arguments.extend(self.options.iter().cloned());
+        arguments.push("-o".to_string());
+        arguments.push(solution_file(&self.work_dir, self.temp_solution_file.as_ref(), file_model));
+        arguments
+    }
+
+    /// Parse a sensitivity analysis report written by `glpsol --ranges`
+    pub fn read_ranges(&self, ranges_file: &str) -> Result<Sensitivity, LpError> {
+        fn parse_value(value: &str) -> Result<f64, LpError> {
+            match value {
+                "." => Ok(0.0),
+                "+Inf" => Ok(f64::INFINITY),
+                "-Inf" => Ok(f64::NEG_INFINITY),
+                _ => value
+                    .parse::<f64>()
+                    .map_err(|_| LpError::ParseSolution(format!("Incorrect ranges format: invalid value {}", value))),
+            }
+        }
+        let f = File::open(ranges_file).map_err(|e| io::Error::new(e.kind(), "Cannot open file"))?;
+        let mut sensitivity = Sensitivity::default();
+        let mut in_columns: Option<bool> = None;
+        let mut lines = BufReader::new(f).lines();
+        while let Some(line) = lines.next() {
+            let line = line?;
+            if line.contains("Row name") {
+                in_columns = Some(false);
+                continue;
+            } else if line.contains("Column name") {
+                in_columns = Some(true);
+                continue;
+            }
+            let mut first: Vec<String> = line.split_whitespace().map(|t| t.to_string()).collect();
+            let in_columns = match (in_columns, first.first().map(|t| t.parse::<usize>())) {
+                (Some(in_columns), Some(Ok(_))) => in_columns,
+                _ => continue,
+            };
+            let mut next_line = || -> Result<Vec<String>, LpError> {
+                match lines.next() {
+                    Some(Ok(l)) => Ok(l.split_whitespace().map(|t| t.to_string()).collect()),
+                    _ => Err(LpError::ParseSolution("Incorrect ranges format: truncated entry".to_string())),
+                }
+            };
+            // Names longer than 12 characters are written on their own line
+            if first.len() == 2 {
+                first.extend(next_line()?);
+            }
+            let second = next_line()?;
+            if first.len() < 9 || second.len() < 5 {
+                return Err(LpError::ParseSolution("Incorrect ranges format: entry has too few fields".to_string()));
+            }
+            let ranging = Ranging {
+                status: first[2].clone(),
+                activity: parse_value(&first[3])?,
+                slack_or_cost: parse_value(&first[4])?,
+                marginal: parse_value(&second[0])?,
+                bounds: (parse_value(&first[5])?, parse_value(&second[1])?),
+                activity_range: (parse_value(&first[6])?, parse_value(&second[2])?),
+                objective_coefficient_range: (parse_value(&first[7])?, parse_value(&second[3])?),
+                objective_value_range: (parse_value(&first[8])?, parse_value(&second[4])?),
+                limiting: (first.get(9).cloned(), second.get(5).cloned()),
+            };
+            if in_columns {
+                sensitivity.variables.insert(first[1].clone(), ranging);
+            } else {
+                sensitivity.constraints.insert(first[1].clone(), ranging);
+            }
+        }
+        Ok(sensitivity)
+    }
+    /// Round the values of integer variables within `epsilon` of an integer,
+    /// `DEFAULT_INTEGER_TOLERANCE` by default
+    pub fn with_integer_tolerance(&self, epsilon: f64) -> GlpkSolver {
+        GlpkSolver {
+            integer_tolerance: epsilon,
+            ..self.clone()
+        }
+    }
+
+    /// Whether the solver executable can be found, checked by `run` before writing any file
+    pub fn is_available(&self) -> bool {
+        command_available(&self.command_name)
+    }
+
+    /// Irreducible infeasible set of `problem`, see `compute_iis`.
+    /// glpsol cannot compute it natively, so the problem is solved once per constraint.
+    pub fn compute_iis(&self, problem: &LpProblem) -> Result<Vec<String>, LpError> {
+        compute_iis(self, problem)
+    }
+}
+
+/// GLPK has no MIP start: the values are accepted but not used.
+impl WithWarmStart<GlpkSolver> for GlpkSolver {
+    fn warm_start(&self) -> Option<&HashMap<String, f64>> {
+        self.warm_start.as_ref()
+    }
+    fn with_warm_start(&self, values: HashMap<String, f64>) -> GlpkSolver {
+        GlpkSolver {
+            warm_start: Some(values),
+            ..self.clone()
+        }
+    }
+}
+
+/// glpsol is single-threaded: the number of threads is stored but ignored
+impl WithNbThreads<GlpkSolver> for GlpkSolver {
+    fn nb_threads(&self) -> Option<u32> {
+        self.threads
+    }
+    fn with_nb_threads(&self, threads: u32) -> GlpkSolver {
+        GlpkSolver {
+            threads: Some(threads),
+            ..self.clone()
         }
     }
 }
 
 impl SolverWithSolutionParsing for GlpkSolver {
-    fn read_specific_solution<'a>(&self, f: &File, problem: Option<&'a LpProblem>) -> Result<Solution<'a>, String> {
-        fn read_size(line: Option<Result<String, Error>>) -> Result<usize, String> {
+    fn integer_tolerance(&self) -> f64 {
+        self.integer_tolerance
+    }
+    fn read_specific_solution<'a>(&self, f: &File, problem: Option<&'a LpProblem>) -> Result<Solution<'a>, LpError> {
+        fn read_size(line: Option<Result<String, Error>>) -> Result<usize, LpError> {
+            let incorrect = || LpError::ParseSolution("Incorrect solution format".to_string());
             match line {
                 Some(Ok(l)) => match l.split_whitespace().nth(1) {
                     Some(value) => match value.parse::<usize>() {
                         Ok(v) => Ok(v),
-                        _ => return Err("Incorrect solution format".to_string()),
+                        _ => return Err(incorrect()),
                     },
-                    _ => return Err("Incorrect solution format".to_string()),
+                    _ => return Err(incorrect()),
                 },
-                _ => return Err("Incorrect solution format".to_string()),
+                _ => return Err(incorrect()),
             }
         }
         let mut vars_value: HashMap<_, _> = HashMap::new();
@@ -60,34 +285,47 @@ impl SolverWithSolutionParsing for GlpkSolver {
         let file = BufReader::new(f);
 
         let mut iter = file.lines();
-        let row = match read_size(iter.nth(1)) {
-            Ok(value) => value,
-            Err(e) => return Err(e.to_string()),
-        };
-        let col = match read_size(iter.nth(0)) {
-            Ok(value) => value,
-            Err(e) => return Err(e.to_string()),
-        };
+        let row = read_size(iter.nth(1))?;
+        let col = read_size(iter.nth(0))?;
         let status = match iter.nth(1) {
             Some(Ok(status_line)) => match &status_line[12..] {
                 "INTEGER OPTIMAL" | "OPTIMAL" => Status::Optimal,
+                "INTEGER NON-OPTIMAL" | "FEASIBLE" => Status::SubOptimal,
                 "INFEASIBLE (FINAL)" | "INTEGER EMPTY" => Status::Infeasible,
                 "UNDEFINED" => Status::NotSolved,
                 "INTEGER UNDEFINED" | "UNBOUNDED" => Status::Unbounded,
                 _ => {
-                    return Err("Incorrect solution format: Unknown solution status".to_string())
+                    return Err(LpError::ParseSolution(
+                        "Incorrect solution format: Unknown solution status".to_string(),
+                    ))
                 }
             },
-            _ => return Err("Incorrect solution format: No solution status found".to_string()),
+            _ => {
+                return Err(LpError::ParseSolution(
+                    "Incorrect solution format: No solution status found".to_string(),
+                ))
+            }
         };
-        let mut result_lines = iter.skip(row + 7);
+        let objective = match iter.next() {
+            Some(Ok(objective_line)) => match status {
+                Status::Optimal | Status::SubOptimal => parse_glpk_objective(&objective_line),
+                // glpsol writes a zero objective when there is no solution
+                _ => None,
+            },
+            _ => {
+                return Err(LpError::ParseSolution(
+                    "Incorrect solution format: No objective found".to_string(),
+                ))
+            }
+        };
+        let mut result_lines = iter.skip(row + 6);
         for _ in 0..col {
             let line = match result_lines.next() {
                 Some(Ok(l)) => l,
                 _ => {
-                    return Err(
-                        "Incorrect solution format: Not all columns are present".to_string()
-                    )
+                    return Err(LpError::ParseSolution(
+                        "Incorrect solution format: Not all columns are present".to_string(),
+                    ))
                 }
             };
             let result_line: Vec<_> = line.split_whitespace().collect();
@@ -96,51 +334,117 @@ impl SolverWithSolutionParsing for GlpkSolver {
                     Ok(n) => {
                         vars_value.insert(result_line[1].to_string(), n);
                     }
-                    Err(e) => return Err(e.to_string()),
+                    Err(e) => return Err(LpError::ParseSolution(e.to_string())),
                 }
             } else {
-                return Err(
+                return Err(LpError::ParseSolution(
                     "Incorrect solution format: Column specification has to few fields"
                         .to_string(),
-                );
+                ));
             }
         }
-        if let Some(p) = problem {
-            Ok( Solution::with_problem(status, vars_value, p) )
+        let solution = if let Some(p) = problem {
+            Solution::with_problem(status, vars_value, p)
         } else {
-            Ok( Solution::new(status, vars_value) )
-        }
+            Solution::new(status, vars_value)
+        };
+        Ok(Solution { objective, ..solution })
     }
 }
 
+/// Objective value from the `Objective:` line of the header of a GLPK solution, e.g.
+/// `Objective:  obj = 100 (MAXimum)`. The objective name and the `=` sign are left out when
+/// the objective has no name.
+pub fn parse_glpk_objective(line: &str) -> Option<f64> {
+    let value = line.trim_start().strip_prefix("Objective:")?;
+    let value = value.split('(').next()?;
+    let value = value.rsplit('=').next()?;
+    value.trim().parse::<f64>().ok()
+}
+
+/// Whether `glpsol` stopped its search at the time limit, from its output
+pub fn glpk_time_limit_reached(output: &str) -> bool {
+    output.contains("TIME LIMIT EXCEEDED")
+}
+
 impl SolverTrait for GlpkSolver {
     type P = LpProblem;
-    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
-        let file_model = &format!("{}.lp", problem.unique_name);
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+        self.run_cancellable(problem, &AtomicBool::new(false))
+    }
+
+    fn run_cancellable<'a>(&self, problem: &'a Self::P, cancel: &AtomicBool) -> Result<Solution<'a>, LpError> {
+        problem.check_not_empty()?;
+        if !problem.semi_continuous_variables().is_empty() {
+            return run_lowered(problem, |lowered| self.run_cancellable(lowered, cancel));
+        }
+        if self.ranges_file.is_some() && !self.lp_relaxation {
+            let integers = problem.integer_variable_names();
+            if !integers.is_empty() {
+                return Err(LpError::Unsupported(format!(
+                    "GLPK cannot compute the sensitivity ranges of a MIP, found integer variables {}",
+                    integers.join(", ")
+                )));
+            }
+        }
+        if !self.is_available() {
+            return Err(LpError::SolverNotFound(self.name.clone()));
+        }
+        let file_model = &work_file(&self.work_dir, &format!("{}.lp", run_file_stem(problem)));
+        let solution_file = &solution_file(&self.work_dir, self.temp_solution_file.as_ref(), file_model);
 
-        match problem.write_lp(file_model) {
+        let names = sanitized_names(problem);
+        match write_renamed_lp(problem, &names, file_model) {
             Ok(_) => {
-                let result = match Command::new(&self.command_name)
-                    .arg("--lp")
-                    .arg(file_model)
-                    .arg("-o")
-                    .arg(&self.temp_solution_file)
-                    .output()
-                    {
-                        Ok(r) => {
+                let start = Instant::now();
+                let output = Command::new(&self.command_name)
+                    .args(self.arguments(file_model))
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .and_then(|child| wait_cancellable(child, cancel, self.time_limit()));
+                let solve_time = start.elapsed();
+                let result = match output {
+                        Ok(Some(r)) => {
                             if r.status.success() {
-                                self.read_solution(&self.temp_solution_file, Some(problem))
+                                self.read_renamed_solution(solution_file, Some(problem), &names)
+                                    .and_then(|solution| match &self.ranges_file {
+                                        Some(ranges_file) => {
+                                            let mut sensitivity = self.read_ranges(&work_file(&self.work_dir, ranges_file))?;
+                                            restore_names(&mut sensitivity.variables, &names);
+                                            Ok(Solution { sensitivity: Some(sensitivity), ..solution })
+                                        }
+                                        None => Ok(solution),
+                                    })
+                                    .map(|solution| Solution {
+                                        status: match solution.status {
+                                            Status::SubOptimal if glpk_time_limit_reached(&String::from_utf8_lossy(&r.stdout)) => {
+                                                Status::TimeLimit
+                                            }
+                                            status => status,
+                                        },
+                                        solve_time: Some(solve_time),
+                                        objective: solution.objective.map(|o| o * problem.objective_scale()),
+                                        ..solution
+                                    })
                             } else {
-                                Err(r.status.to_string())
+                                Err(LpError::Solver(r.status.to_string()))
                             }
                         }
-                        Err(_) => Err(format!("Error running the {} solver", self.name)),
+                        Ok(None) => {
+                            let _ = fs::remove_file(solution_file);
+                            Err(LpError::Cancelled(Some(self.name.clone())))
+                        }
+                        Err(e) => {
+                            let _ = fs::remove_file(solution_file);
+                            Err(process_error(&self.name, e))
+                        }
                     };
                 let _ = fs::remove_file(&file_model);
 
                 result
             }
-            Err(e) => Err(e.to_string()),
+            Err(e) => Err(e.into()),
         }
     }
 }
@@ -0,0 +1,795 @@
+//! GLPK called in process through its C API, without writing any file.
+//!
+//! The `glpk-ffi` feature loads the `glpk` shared library when the solver is first run, so it
+//! builds without GLPK: the solver is then reported unavailable. The library has to be installed
+//! on the system to solve, GLPK 4.63 or later, e.g. `libglpk40` on Debian. GLPK aborts the whole
+//! process on an invalid call: the problem is only handed to GLPK after its bounds have been checked.
+//!
+//! The bindings are declared here rather than taken from `glpk-sys`, which links GLPK when the
+//! crate is built and would make the feature fail to compile wherever GLPK is missing, while the
+//! other solvers only check for their program when run. The control parameter structures follow
+//! `glpk.h`: their sizes are asserted at compile time and compared with the header in CI, and the
+//! library is only used if `glp_version` reports a version with that layout.
+
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use dsl::{Constraint, LpConstraint, LpExprNode, LpObjective, LpProblem};
+use error::LpError;
+use solvers::{DEFAULT_INTEGER_TOLERANCE, Ranging, Sensitivity, Solution, SolverTrait, Status, run_lowered};
+
+#[allow(non_camel_case_types)]
+enum glp_prob {}
+
+const GLP_MIN: c_int = 1;
+const GLP_MAX: c_int = 2;
+
+const GLP_IV: c_int = 2;
+const GLP_BV: c_int = 3;
+
+const GLP_FR: c_int = 1;
+const GLP_LO: c_int = 2;
+const GLP_UP: c_int = 3;
+const GLP_DB: c_int = 4;
+const GLP_FX: c_int = 5;
+
+const GLP_BS: c_int = 1;
+const GLP_NL: c_int = 2;
+const GLP_NU: c_int = 3;
+const GLP_NF: c_int = 4;
+const GLP_NS: c_int = 5;
+
+const GLP_FEAS: c_int = 2;
+const GLP_INFEAS: c_int = 3;
+const GLP_NOFEAS: c_int = 4;
+const GLP_OPT: c_int = 5;
+const GLP_UNBND: c_int = 6;
+
+const GLP_EITLIM: c_int = 0x08;
+const GLP_ETMLIM: c_int = 0x09;
+const GLP_ENOPFS: c_int = 0x0A;
+const GLP_ENODFS: c_int = 0x0B;
+const GLP_ESTOP: c_int = 0x0D;
+const GLP_EMIPGAP: c_int = 0x0E;
+
+const GLP_MSG_OFF: c_int = 0;
+
+/// Control parameters of `glp_simplex`, as declared in `glpk.h` since GLPK 4.63
+#[allow(non_camel_case_types)]
+#[repr(C)]
+struct glp_smcp {
+    msg_lev: c_int,
+    meth: c_int,
+    pricing: c_int,
+    r_test: c_int,
+    tol_bnd: f64,
+    tol_dj: f64,
+    tol_piv: f64,
+    obj_ll: f64,
+    obj_ul: f64,
+    it_lim: c_int,
+    tm_lim: c_int,
+    out_frq: c_int,
+    out_dly: c_int,
+    presolve: c_int,
+    excl: c_int,
+    shift: c_int,
+    aorn: c_int,
+    foo_bar: [f64; 33],
+}
+
+/// Control parameters of `glp_intopt`, as declared in `glpk.h` since GLPK 4.63
+#[allow(non_camel_case_types)]
+#[repr(C)]
+struct glp_iocp {
+    msg_lev: c_int,
+    br_tech: c_int,
+    bt_tech: c_int,
+    tol_int: f64,
+    tol_obj: f64,
+    tm_lim: c_int,
+    out_frq: c_int,
+    out_dly: c_int,
+    cb_func: Option<unsafe extern "C" fn(*mut c_void, *mut c_void)>,
+    cb_info: *mut c_void,
+    cb_size: c_int,
+    pp_tech: c_int,
+    mip_gap: f64,
+    mir_cuts: c_int,
+    gmi_cuts: c_int,
+    cov_cuts: c_int,
+    clq_cuts: c_int,
+    presolve: c_int,
+    binarize: c_int,
+    fp_heur: c_int,
+    ps_heur: c_int,
+    ps_tm_lim: c_int,
+    sr_heur: c_int,
+    use_sol: c_int,
+    save_sol: *const c_char,
+    alien: c_int,
+    flip: c_int,
+    foo_bar: [f64; 23],
+}
+
+// sizes of the structures compiled from `glpk.h`, also checked against the header in CI
+const _: () = assert!(std::mem::size_of::<glp_smcp>() == 352);
+#[cfg(target_pointer_width = "64")]
+const _: () = assert!(std::mem::size_of::<glp_iocp>() == 328);
+
+impl glp_smcp {
+    /// Default parameters of `glp_init_smcp`, without any terminal output
+    fn quiet() -> glp_smcp {
+        // all zero is a valid value of every field, overwritten by `glp_init_smcp`
+        let mut parm: glp_smcp = unsafe { std::mem::zeroed() };
+        unsafe { glp_init_smcp(&mut parm) };
+        parm.msg_lev = GLP_MSG_OFF;
+        parm
+    }
+}
+
+impl glp_iocp {
+    /// Default parameters of `glp_init_iocp`, without any terminal output
+    fn quiet() -> glp_iocp {
+        let mut parm: glp_iocp = unsafe { std::mem::zeroed() };
+        unsafe { glp_init_iocp(&mut parm) };
+        parm.msg_lev = GLP_MSG_OFF;
+        parm
+    }
+}
+
+/// Oldest version of GLPK with the layout of `glp_smcp` and `glp_iocp` declared above
+const MIN_VERSION: (u32, u32) = (4, 63);
+
+/// Names of the GLPK shared library tried in turn, the unversioned name first
+#[cfg(target_os = "macos")]
+const LIBRARY_NAMES: &[&str] = &["libglpk.dylib", "libglpk.40.dylib"];
+#[cfg(windows)]
+const LIBRARY_NAMES: &[&str] = &["glpk.dll", "glpk_5_0.dll", "glpk_4_65.dll"];
+#[cfg(not(any(target_os = "macos", windows)))]
+const LIBRARY_NAMES: &[&str] = &["libglpk.so", "libglpk.so.40"];
+
+#[cfg(unix)]
+mod dynamic {
+    use std::os::raw::{c_char, c_int, c_void};
+
+    const RTLD_NOW: c_int = 2;
+
+    extern "C" {
+        fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+
+    pub(super) unsafe fn open(name: *const c_char) -> *mut c_void {
+        dlopen(name, RTLD_NOW)
+    }
+
+    pub(super) unsafe fn symbol(library: *mut c_void, name: *const c_char) -> *mut c_void {
+        dlsym(library, name)
+    }
+}
+
+#[cfg(windows)]
+mod dynamic {
+    use std::os::raw::{c_char, c_void};
+
+    extern "system" {
+        fn LoadLibraryA(filename: *const c_char) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+    }
+
+    pub(super) unsafe fn open(name: *const c_char) -> *mut c_void {
+        LoadLibraryA(name)
+    }
+
+    pub(super) unsafe fn symbol(library: *mut c_void, name: *const c_char) -> *mut c_void {
+        GetProcAddress(library, name)
+    }
+}
+
+/// Declare the functions of the GLPK API used here, as wrappers calling the pointers resolved
+/// by `Api::load`. They must only be called once `api()` returned the loaded library.
+macro_rules! glpk_api {
+    ($(fn $name:ident($($arg:ident: $type:ty),*) $(-> $ret:ty)?;)*) => {
+        struct Api {
+            $($name: unsafe extern "C" fn($($type),*) $(-> $ret)?,)*
+        }
+
+        impl Api {
+            /// Resolve every function in `library`, or `None` if one is missing
+            unsafe fn load(library: *mut c_void) -> Option<Api> {
+                Some(Api {
+                    $($name: {
+                        let symbol = dynamic::symbol(library, concat!(stringify!($name), "\0").as_ptr() as *const c_char);
+                        if symbol.is_null() {
+                            return None;
+                        }
+                        std::mem::transmute::<*mut c_void, unsafe extern "C" fn($($type),*) $(-> $ret)?>(symbol)
+                    },)*
+                })
+            }
+        }
+
+        $(#[allow(dead_code, clippy::too_many_arguments)]
+        unsafe fn $name($($arg: $type),*) $(-> $ret)? {
+            (api().expect("GLPK library loaded").$name)($($arg),*)
+        })*
+    };
+}
+
+glpk_api! {
+    fn glp_create_prob() -> *mut glp_prob;
+    fn glp_delete_prob(p: *mut glp_prob);
+    fn glp_set_obj_dir(p: *mut glp_prob, dir: c_int);
+    fn glp_add_rows(p: *mut glp_prob, nrs: c_int) -> c_int;
+    fn glp_add_cols(p: *mut glp_prob, ncs: c_int) -> c_int;
+    fn glp_set_row_bnds(p: *mut glp_prob, i: c_int, kind: c_int, lb: f64, ub: f64);
+    fn glp_set_col_bnds(p: *mut glp_prob, j: c_int, kind: c_int, lb: f64, ub: f64);
+    fn glp_set_obj_coef(p: *mut glp_prob, j: c_int, coef: f64);
+    fn glp_set_col_kind(p: *mut glp_prob, j: c_int, kind: c_int);
+    fn glp_set_mat_row(p: *mut glp_prob, i: c_int, len: c_int, ind: *const c_int, val: *const f64);
+    fn glp_version() -> *const c_char;
+    fn glp_init_smcp(parm: *mut glp_smcp);
+    fn glp_init_iocp(parm: *mut glp_iocp);
+    fn glp_simplex(p: *mut glp_prob, parm: *const glp_smcp) -> c_int;
+    fn glp_intopt(p: *mut glp_prob, parm: *const glp_iocp) -> c_int;
+    fn glp_get_status(p: *mut glp_prob) -> c_int;
+    fn glp_get_obj_val(p: *mut glp_prob) -> f64;
+    fn glp_get_row_stat(p: *mut glp_prob, i: c_int) -> c_int;
+    fn glp_get_row_prim(p: *mut glp_prob, i: c_int) -> f64;
+    fn glp_get_row_dual(p: *mut glp_prob, i: c_int) -> f64;
+    fn glp_get_col_stat(p: *mut glp_prob, j: c_int) -> c_int;
+    fn glp_get_col_prim(p: *mut glp_prob, j: c_int) -> f64;
+    fn glp_get_col_dual(p: *mut glp_prob, j: c_int) -> f64;
+    fn glp_mip_status(p: *mut glp_prob) -> c_int;
+    fn glp_mip_obj_val(p: *mut glp_prob) -> f64;
+    fn glp_mip_col_val(p: *mut glp_prob, j: c_int) -> f64;
+    fn glp_bf_exists(p: *mut glp_prob) -> c_int;
+    fn glp_analyze_bound(
+        p: *mut glp_prob,
+        k: c_int,
+        value1: *mut f64,
+        var1: *mut c_int,
+        value2: *mut f64,
+        var2: *mut c_int
+    );
+    fn glp_analyze_coef(
+        p: *mut glp_prob,
+        k: c_int,
+        coef1: *mut f64,
+        var1: *mut c_int,
+        value1: *mut f64,
+        coef2: *mut f64,
+        var2: *mut c_int,
+        value2: *mut f64
+    );
+}
+
+/// GLPK API, loaded from the shared library on first use, or `None` if it is not installed
+fn api() -> Option<&'static Api> {
+    static API: OnceLock<Option<Api>> = OnceLock::new();
+    API.get_or_init(|| {
+        LIBRARY_NAMES.iter().find_map(|name| {
+            let name = CString::new(*name).expect("library name without NUL");
+            unsafe {
+                let library = dynamic::open(name.as_ptr());
+                // the library is never unloaded, as the resolved functions are kept
+                if library.is_null() {
+                    None
+                } else {
+                    Api::load(library).filter(|api| supported_version(CStr::from_ptr((api.glp_version)())))
+                }
+            }
+        })
+    })
+    .as_ref()
+}
+
+/// Whether a version returned by `glp_version`, e.g. `5.0`, has the control parameters declared here
+fn supported_version(version: &CStr) -> bool {
+    let mut numbers = version.to_str().unwrap_or("").split('.').map(|number| number.parse::<u32>().ok());
+    match (numbers.next().flatten(), numbers.next().flatten()) {
+        (Some(major), Some(minor)) => (major, minor) >= MIN_VERSION,
+        _ => false,
+    }
+}
+
+/// GLPK problem object, deleted when dropped
+struct GlpkProblem(*mut glp_prob);
+
+impl Drop for GlpkProblem {
+    fn drop(&mut self) {
+        unsafe { glp_delete_prob(self.0) }
+    }
+}
+
+/// Status of a solution of GLPK, from `glp_get_status` or `glp_mip_status`
+fn status_from_glpk(status: c_int) -> Status {
+    match status {
+        GLP_OPT => Status::Optimal,
+        GLP_FEAS => Status::SubOptimal,
+        GLP_INFEAS | GLP_NOFEAS => Status::Infeasible,
+        GLP_UNBND => Status::Unbounded,
+        _ => Status::NotSolved,
+    }
+}
+
+/// Whether a return code of `glp_simplex` or `glp_intopt` still leaves a solution to read
+fn solution_available(code: c_int) -> bool {
+    matches!(code, 0 | GLP_EITLIM | GLP_ETMLIM | GLP_ENOPFS | GLP_ENODFS | GLP_ESTOP | GLP_EMIPGAP)
+}
+
+/// Name of the status of a row or column in the basis, as in the reports of glpsol
+fn basis_status_name(status: c_int) -> &'static str {
+    match status {
+        GLP_BS => "BS",
+        GLP_NL => "NL",
+        GLP_NU => "NU",
+        GLP_NF => "NF",
+        GLP_NS => "NS",
+        _ => "??",
+    }
+}
+
+/// Infinite value for the `-DBL_MAX` and `DBL_MAX` returned by the GLPK analysis routines
+fn unbounded(value: f64) -> f64 {
+    if value <= -f64::MAX {
+        f64::NEG_INFINITY
+    } else if value >= f64::MAX {
+        f64::INFINITY
+    } else {
+        value
+    }
+}
+
+/// Bound type and finite bounds given to `glp_set_row_bnds` and `glp_set_col_bnds`
+fn bounds_type(lower: f64, upper: f64) -> (c_int, f64, f64) {
+    match (lower.is_finite(), upper.is_finite()) {
+        (true, true) if lower == upper => (GLP_FX, lower, upper),
+        (true, true) => (GLP_DB, lower, upper),
+        (true, false) => (GLP_LO, lower, 0.0),
+        (false, true) => (GLP_UP, 0.0, upper),
+        (false, false) => (GLP_FR, 0.0, 0.0),
+    }
+}
+
+/// Solver calling GLPK through its C API: the problem is built in memory and the values of the
+/// variables are read back directly, without spawning `glpsol` nor writing any file.
+///
+/// The variables get the bounds they would have in an LP file: a continuous variable without
+/// bounds is free, the other variables are non-negative unless given a lower bound.
+/// Integer and binary variables are solved with `glp_intopt` once the LP relaxation is optimal.
+///
+/// # Examples:
+///
+/// ```no_run
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::solvers::{GlpkFfiSolver, SolverTrait};
+///
+/// let ref a = LpContinuous::new("a");
+/// let ref b = LpContinuous::new("b");
+/// let mut problem = LpProblem::new("In process", LpObjective::Maximize);
+/// problem += 10 * a + 20 * b;
+/// problem += (500 * a - 1000 * b).ge(10000);
+/// problem += a.le(b);
+///
+/// let solution = GlpkFfiSolver::new().with_sensitivity().run(&problem).unwrap();
+/// println!("dual of c1: {}", solution.sensitivity.unwrap().constraints["c1"].marginal);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GlpkFfiSolver {
+    sensitivity: bool,
+    integer_tolerance: f64,
+}
+
+impl Default for GlpkFfiSolver {
+    fn default() -> Self {
+        GlpkFfiSolver::new()
+    }
+}
+
+impl GlpkFfiSolver {
+    pub fn new() -> GlpkFfiSolver {
+        GlpkFfiSolver {
+            sensitivity: false,
+            integer_tolerance: DEFAULT_INTEGER_TOLERANCE,
+        }
+    }
+    /// Attach the sensitivity analysis of the optimal basis to the solution: the duals of the
+    /// constraints (`c1`, `c2`, ... as in the LP file), the reduced costs of the variables and
+    /// their ranges, as reported by `glpsol --ranges`.
+    /// Only continuous problems are analysed: `run` fails on problems with integer variables.
+    pub fn with_sensitivity(&self) -> GlpkFfiSolver {
+        GlpkFfiSolver {
+            sensitivity: true,
+            ..self.clone()
+        }
+    }
+    /// Round the values of integer variables within `epsilon` of an integer,
+    /// `DEFAULT_INTEGER_TOLERANCE` by default
+    pub fn with_integer_tolerance(&self, epsilon: f64) -> GlpkFfiSolver {
+        GlpkFfiSolver {
+            integer_tolerance: epsilon,
+            ..self.clone()
+        }
+    }
+    /// Whether the GLPK shared library can be loaded, checked by `run` before building the problem
+    pub fn is_available(&self) -> bool {
+        api().is_some()
+    }
+}
+
+/// Columns of the variables of a problem, in order of first occurrence
+struct Columns<'a> {
+    names: Vec<&'a str>,
+    indexes: HashMap<&'a str, c_int>,
+    integer: bool,
+}
+
+impl<'a> Columns<'a> {
+    fn index(&mut self, name: &'a str, node: &'a LpExprNode, glp: &GlpkProblem, bounds: &HashMap<&str, (f64, f64)>) -> c_int {
+        if let Some(&index) = self.indexes.get(name) {
+            return index;
+        }
+        let index = unsafe { glp_add_cols(glp.0, 1) };
+        let (lower, upper) = bounds.get(name).cloned().unwrap_or((f64::NEG_INFINITY, f64::INFINITY));
+        match node {
+            LpExprNode::ConsBin(_) => {
+                unsafe { glp_set_col_kind(glp.0, index, GLP_BV) };
+                self.integer = true;
+            }
+            _ => {
+                let integer = matches!(node, LpExprNode::ConsInt(_));
+                // variables are non-negative by default in the LP format, unless free
+                let lower = if lower == f64::NEG_INFINITY && (integer || upper.is_finite()) { 0.0 } else { lower };
+                let (kind, lower, upper) = bounds_type(lower, upper);
+                unsafe {
+                    glp_set_col_bnds(glp.0, index, kind, lower, upper);
+                    if integer {
+                        glp_set_col_kind(glp.0, index, GLP_IV);
+                    }
+                }
+                self.integer |= integer;
+            }
+        }
+        self.names.push(name);
+        self.indexes.insert(name, index);
+        index
+    }
+}
+
+/// Name of the row or column `k` of the GLPK problem, in the numbering of the analysis routines
+/// (rows first), or `None` for 0
+fn ordinal_name(k: c_int, rows: usize, columns: &Columns) -> Option<String> {
+    match k as usize {
+        0 => None,
+        k if k <= rows => Some(format!("c{}", k)),
+        k => columns.names.get(k - rows - 1).map(|name| name.to_string()),
+    }
+}
+
+/// Sensitivity analysis of the optimal basis of `glp`, with the same columns as `glpsol --ranges`
+fn sensitivity_analysis(
+    glp: &GlpkProblem,
+    problem: &LpProblem,
+    rows: usize,
+    columns: &Columns,
+) -> Result<Sensitivity, LpError> {
+    let mut sensitivity = Sensitivity::default();
+    let objective = unsafe { glp_get_obj_val(glp.0) };
+    let objective_coefficients = match &problem.obj_expr_arena {
+        Some(objective) => objective.linear_terms()?.0,
+        None => HashMap::new(),
+    };
+    for k in 1..=(rows + columns.names.len()) {
+        let is_row = k <= rows;
+        let (status, activity, marginal) = unsafe {
+            if is_row {
+                (glp_get_row_stat(glp.0, k as c_int), glp_get_row_prim(glp.0, k as c_int), glp_get_row_dual(glp.0, k as c_int))
+            } else {
+                let j = (k - rows) as c_int;
+                (glp_get_col_stat(glp.0, j), glp_get_col_prim(glp.0, j), glp_get_col_dual(glp.0, j))
+            }
+        };
+        let name = ordinal_name(k as c_int, rows, columns).expect("row or column");
+        let coefficient = if is_row { 0.0 } else { objective_coefficients.get(&name).cloned().unwrap_or(0.0) };
+        let bounds = if is_row {
+            let LpConstraint(_, op, _) = &problem.constraints[k - 1];
+            let rhs = activity_bound(problem, k - 1)?;
+            match op {
+                Constraint::LessOrEqual => (f64::NEG_INFINITY, rhs),
+                Constraint::GreaterOrEqual => (rhs, f64::INFINITY),
+                Constraint::Equal => (rhs, rhs),
+            }
+        } else {
+            (f64::NEG_INFINITY, f64::INFINITY)
+        };
+        let slack_or_cost = if is_row {
+            match bounds {
+                (lower, upper) if lower == upper => 0.0,
+                (lower, _) if lower.is_finite() => activity - lower,
+                (_, upper) => upper - activity,
+            }
+        } else {
+            coefficient
+        };
+        let (mut value1, mut var1, mut value2, mut var2) = (0.0, 0, 0.0, 0);
+        let (activity_range, objective_coefficient_range, objective_value_range) = if status == GLP_BS {
+            let (mut coef1, mut coef2) = (0.0, 0.0);
+            unsafe {
+                glp_analyze_coef(glp.0, k as c_int, &mut coef1, &mut var1, &mut value1, &mut coef2, &mut var2, &mut value2)
+            };
+            let (coef1, coef2) = (unbounded(coef1), unbounded(coef2));
+            (
+                (unbounded(value1), unbounded(value2)),
+                (coef1, coef2),
+                (objective + (coef1 - coefficient) * activity, objective + (coef2 - coefficient) * activity),
+            )
+        } else {
+            unsafe { glp_analyze_bound(glp.0, k as c_int, &mut value1, &mut var1, &mut value2, &mut var2) };
+            let (value1, value2) = (unbounded(value1), unbounded(value2));
+            // the basis stays optimal until the reduced cost of the variable changes sign
+            let breaking = coefficient - marginal;
+            let coefficient_range = match (status, &problem.objective_type) {
+                (GLP_NL, LpObjective::Minimize) | (GLP_NU, LpObjective::Maximize) => (breaking, f64::INFINITY),
+                (GLP_NU, LpObjective::Minimize) | (GLP_NL, LpObjective::Maximize) => (f64::NEG_INFINITY, breaking),
+                (GLP_NS, _) => (f64::NEG_INFINITY, f64::INFINITY),
+                _ => (breaking, breaking),
+            };
+            (
+                (value1, value2),
+                coefficient_range,
+                (objective + marginal * (value1 - activity), objective + marginal * (value2 - activity)),
+            )
+        };
+        let ranging = Ranging {
+            status: basis_status_name(status).to_string(),
+            activity,
+            slack_or_cost,
+            marginal,
+            bounds,
+            activity_range,
+            objective_coefficient_range,
+            objective_value_range,
+            limiting: (ordinal_name(var1, rows, columns), ordinal_name(var2, rows, columns)),
+        };
+        if is_row {
+            sensitivity.constraints.insert(name, ranging);
+        } else {
+            sensitivity.variables.insert(name, ranging);
+        }
+    }
+    Ok(sensitivity)
+}
+
+/// Constant of the constraint at `index`, once its variables are moved to the left
+fn activity_bound(problem: &LpProblem, index: usize) -> Result<f64, LpError> {
+    let LpConstraint(lhs, _, rhs) = &problem.constraints[index];
+    Ok(rhs.linear_terms()?.1 - lhs.linear_terms()?.1)
+}
+
+impl SolverTrait for GlpkFfiSolver {
+    type P = LpProblem;
+
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+        problem.check_not_empty()?;
+        if !problem.semi_continuous_variables().is_empty() {
+            return run_lowered(problem, |lowered| self.run(lowered));
+        }
+        if !problem.sos2.is_empty() {
+            return Err(LpError::Unsupported(
+                "Special ordered sets are not supported by the GLPK C API solver".to_string(),
+            ));
+        }
+        if self.sensitivity {
+            let integers = problem.integer_variable_names();
+            if !integers.is_empty() {
+                return Err(LpError::Unsupported(format!(
+                    "GLPK cannot compute the sensitivity ranges of a MIP, found integer variables {}",
+                    integers.join(", ")
+                )));
+            }
+        }
+        problem.check_coefficients()?;
+        let bounds = problem.intersected_bounds()?;
+        if !self.is_available() {
+            return Err(LpError::SolverNotFound("Glpk library".to_string()));
+        }
+
+        let glp = GlpkProblem(unsafe { glp_create_prob() });
+        let mut columns = Columns { names: Vec::new(), indexes: HashMap::new(), integer: false };
+        unsafe {
+            glp_set_obj_dir(glp.0, match problem.objective_type {
+                LpObjective::Maximize => GLP_MAX,
+                LpObjective::Minimize => GLP_MIN,
+            })
+        };
+        if let Some(objective) = &problem.obj_expr_arena {
+            let (terms, constant) = objective
+                .linear_term_nodes()
+                .ok_or_else(|| LpError::Nonlinear("The objective is not linear".to_string()))?;
+            for (name, node, coefficient) in terms {
+                let index = columns.index(name, node, &glp, &bounds);
+                unsafe { glp_set_obj_coef(glp.0, index, coefficient) };
+            }
+            unsafe { glp_set_obj_coef(glp.0, 0, constant) };
+        }
+        for (i, constraint) in problem.constraints.iter().enumerate() {
+            let LpConstraint(lhs, op, rhs) = constraint;
+            let nonlinear = || LpError::Nonlinear(format!("Constraint c{} is not linear", i + 1));
+            let (lhs_terms, lhs_constant) = lhs.linear_term_nodes().ok_or_else(nonlinear)?;
+            let (rhs_terms, rhs_constant) = rhs.linear_term_nodes().ok_or_else(nonlinear)?;
+            // GLPK rejects a row with the same column twice: coefficients are summed by column
+            let mut row: Vec<(c_int, f64)> = Vec::with_capacity(lhs_terms.len() + rhs_terms.len());
+            let terms = lhs_terms
+                .into_iter()
+                .chain(rhs_terms.into_iter().map(|(name, node, coefficient)| (name, node, -coefficient)));
+            for (name, node, coefficient) in terms {
+                let index = columns.index(name, node, &glp, &bounds);
+                match row.iter_mut().find(|(column, _)| *column == index) {
+                    Some((_, sum)) => *sum += coefficient,
+                    None => row.push((index, coefficient)),
+                }
+            }
+            row.retain(|(_, coefficient)| *coefficient != 0.0);
+            let constant = rhs_constant - lhs_constant;
+            // arrays are indexed from 1 by GLPK
+            let indexes: Vec<c_int> = Some(0).into_iter().chain(row.iter().map(|(column, _)| *column)).collect();
+            let values: Vec<f64> = Some(0.0).into_iter().chain(row.iter().map(|(_, value)| *value)).collect();
+            let (kind, lower, upper) = match op {
+                Constraint::LessOrEqual => bounds_type(f64::NEG_INFINITY, constant),
+                Constraint::GreaterOrEqual => bounds_type(constant, f64::INFINITY),
+                Constraint::Equal => bounds_type(constant, constant),
+            };
+            unsafe {
+                let index = glp_add_rows(glp.0, 1);
+                glp_set_row_bnds(glp.0, index, kind, lower, upper);
+                glp_set_mat_row(glp.0, index, row.len() as c_int, indexes.as_ptr(), values.as_ptr());
+            }
+        }
+
+        // the output is turned off by the parameters of every call, as `glp_term_out`
+        // would turn it off for all the threads
+        let start = Instant::now();
+        let simplex = unsafe { glp_simplex(glp.0, &glp_smcp::quiet()) };
+        let lp_status = unsafe { glp_get_status(glp.0) };
+        let intopt = if columns.integer && simplex == 0 && lp_status == GLP_OPT {
+            Some(unsafe { glp_intopt(glp.0, &glp_iocp::quiet()) })
+        } else {
+            None
+        };
+        let solve_time = start.elapsed();
+
+        if !solution_available(simplex) {
+            return Err(LpError::Solver(format!("glp_simplex failed with code {:#04x}", simplex)));
+        }
+        let (status, objective, results) = match intopt {
+            Some(code) if !solution_available(code) => {
+                return Err(LpError::Solver(format!("glp_intopt failed with code {:#04x}", code)));
+            }
+            Some(code) => {
+                let status = match unsafe { glp_mip_status(glp.0) } {
+                    GLP_FEAS if code == GLP_ETMLIM => Status::TimeLimit,
+                    status => status_from_glpk(status),
+                };
+                let results = columns
+                    .names
+                    .iter()
+                    .enumerate()
+                    .map(|(j, name)| (name.to_string(), unsafe { glp_mip_col_val(glp.0, j as c_int + 1) }))
+                    .collect();
+                (status, unsafe { glp_mip_obj_val(glp.0) }, results)
+            }
+            None => {
+                let status = match status_from_glpk(lp_status) {
+                    // the relaxation of a MIP has not been solved to optimality
+                    Status::Optimal if columns.integer => Status::NotSolved,
+                    status => status,
+                };
+                let results = columns
+                    .names
+                    .iter()
+                    .enumerate()
+                    .map(|(j, name)| (name.to_string(), unsafe { glp_get_col_prim(glp.0, j as c_int + 1) }))
+                    .collect();
+                (status, unsafe { glp_get_obj_val(glp.0) }, results)
+            }
+        };
+        let sensitivity = if self.sensitivity && status == Status::Optimal && unsafe { glp_bf_exists(glp.0) } != 0 {
+            Some(sensitivity_analysis(&glp, problem, problem.constraints.len(), &columns)?)
+        } else {
+            None
+        };
+
+        let mut solution = Solution {
+            sensitivity,
+            solve_time: Some(solve_time),
+            objective: Some(objective),
+            ..Solution::with_problem(status, results, problem)
+        };
+        solution.round_integers(self.integer_tolerance);
+        solution.add_unused_declared(problem);
+        Ok(solution)
+    }
+}
+
+#[test]
+fn test_solve() {
+    use dsl::operations::LpOperations;
+    use dsl::LpContinuous;
+    let a = &LpContinuous::new("a");
+    let b = &LpContinuous::new("b");
+
+    // Define problem and objective sense
+    let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
+    problem += 10 * a + 20 * b;
+    problem += (500 * a - 1000 * b).ge(10000);
+    problem += (a).le(b);
+
+    if !GlpkFfiSolver::new().is_available() {
+        assert!(matches!(GlpkFfiSolver::new().run(&problem), Err(LpError::SolverNotFound(_))));
+        return;
+    }
+    let solution = GlpkFfiSolver::new().run(&problem).expect("could not solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.results["a"] + 20.).abs() < 1e-9);
+    assert!((solution.results["b"] + 20.).abs() < 1e-9);
+    assert!((solution.objective.unwrap() + 600.).abs() < 1e-9);
+}
+
+#[test]
+fn test_mip_and_duals() {
+    use dsl::operations::LpOperations;
+    use dsl::{BoundableLp, LpContinuous, LpInteger};
+    if !GlpkFfiSolver::new().is_available() {
+        return;
+    }
+    let x = &LpInteger::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+
+    let mut problem = LpProblem::new("Duals", LpObjective::Maximize);
+    problem += 3 * x + 2 * y;
+    problem += (2 * x + y).le(7.5);
+    problem += (x + y).le(4);
+    let solution = GlpkFfiSolver::new().run(&problem).expect("could not solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results["x"], 3.0);
+    assert!((solution.results["y"] - 1.0).abs() < 1e-9);
+    assert!(GlpkFfiSolver::new().with_sensitivity().run(&problem).is_err());
+
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let mut problem = LpProblem::new("Duals", LpObjective::Maximize);
+    problem += 3 * x + 2 * y;
+    problem += (2 * x + y).le(8);
+    problem += (x + y).le(5);
+    let solution = GlpkFfiSolver::new().with_sensitivity().run(&problem).expect("could not solve");
+    let sensitivity = solution.sensitivity.expect("sensitivity analysis");
+    assert!((sensitivity.constraints["c1"].marginal - 1.0).abs() < 1e-9);
+    assert!((sensitivity.constraints["c2"].marginal - 1.0).abs() < 1e-9);
+    assert_eq!(sensitivity.variables["x"].status, "BS");
+}
+
+#[test]
+fn test_control_parameters_layout() {
+    if !GlpkFfiSolver::new().is_available() {
+        return;
+    }
+    // defaults documented for glp_init_smcp and glp_init_iocp, each read from its declared field
+    let mut smcp: glp_smcp = unsafe { std::mem::zeroed() };
+    unsafe { glp_init_smcp(&mut smcp) };
+    assert_eq!((smcp.msg_lev, smcp.meth), (4, 1));
+    assert_eq!((smcp.tol_bnd, smcp.tol_dj), (1e-7, 1e-7));
+    assert_eq!((smcp.obj_ll, smcp.obj_ul), (-f64::MAX, f64::MAX));
+    assert_eq!((smcp.it_lim, smcp.tm_lim, smcp.presolve), (c_int::MAX, c_int::MAX, 0));
+
+    let mut iocp: glp_iocp = unsafe { std::mem::zeroed() };
+    unsafe { glp_init_iocp(&mut iocp) };
+    assert_eq!((iocp.msg_lev, iocp.br_tech, iocp.bt_tech), (4, 4, 3));
+    assert_eq!((iocp.tol_int, iocp.tol_obj, iocp.mip_gap), (1e-5, 1e-7, 0.0));
+    assert_eq!((iocp.tm_lim, iocp.pp_tech), (c_int::MAX, 2));
+    assert!(iocp.cb_func.is_none() && iocp.cb_info.is_null() && iocp.save_sol.is_null());
+    assert_eq!((iocp.ps_tm_lim, iocp.sr_heur), (60000, 1));
+}
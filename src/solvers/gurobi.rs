@@ -1,21 +1,29 @@
-extern crate uuid;
-use self::uuid::Uuid;
-
 use std::collections::HashMap;
 use std::fs;
+use std::env;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::process::Command;
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
 
 use dsl::LpProblem;
+use error::LpError;
 use format::lp_format::*;
-use solvers::{Solution, SolverTrait, SolverWithSolutionParsing, Status};
+use solvers::{DEFAULT_INTEGER_TOLERANCE, Solution, SolverTrait, SolverWithSolutionParsing, Status, WithNbThreads, WithWarmStart, sorted_warm_start, command_available, process_error, wait_cancellable, work_file, solution_file, run_file_stem};
 
+#[derive(Debug, Clone)]
 pub struct GurobiSolver {
     name: String,
     command_name: String,
-    temp_solution_file: String,
+    work_dir: PathBuf,
     keep_problem_file: bool,
+    warm_start: Option<HashMap<String, f64>>,
+    threads: Option<u32>,
+    integer_tolerance: f64,
+    params: Vec<(String, String)>,
 }
 
 impl GurobiSolver {
@@ -23,29 +31,193 @@ impl GurobiSolver {
         GurobiSolver {
             name: "Gurobi".to_string(),
             command_name: "gurobi_cl".to_string(),
-            temp_solution_file: format!("{}.sol", Uuid::new_v4().to_string()),
+            work_dir: env::temp_dir(),
             keep_problem_file: false,
+            warm_start: None,
+            threads: None,
+            integer_tolerance: DEFAULT_INTEGER_TOLERANCE,
+            params: Vec::new(),
         }
     }
     pub fn command_name(&self, command_name: String) -> GurobiSolver {
         GurobiSolver {
             name: self.name.clone(),
             command_name,
-            temp_solution_file: self.temp_solution_file.clone(),
+            work_dir: self.work_dir.clone(),
             keep_problem_file: false,
+            warm_start: self.warm_start.clone(),
+            threads: self.threads,
+            integer_tolerance: self.integer_tolerance,
+            params: self.params.clone(),
         }
     }
+    /// Keep the model written for `gurobi_cl` after the run. It is written in the working
+    /// directory (see `with_work_dir`), the temporary directory of the system by default,
+    /// as `{unique_name}_{uuid}.lp` with the unique name of the problem and a random uuid.
     pub fn keep_problem_file(&mut self) {
         self.keep_problem_file = true;
     }
+    /// Create the model and solution files in `work_dir`, the temporary directory of the system
+    /// by default
+    pub fn with_work_dir<P: Into<PathBuf>>(&self, work_dir: P) -> GurobiSolver {
+        GurobiSolver {
+            work_dir: work_dir.into(),
+            ..self.clone()
+        }
+    }
+    /// Set a Gurobi parameter, e.g. `with_param("Presolve", "2")`, given as `Key=Value` to `gurobi_cl`.
+    /// Setting a parameter again overrides its previous value.
+    pub fn with_param(&self, key: &str, value: &str) -> GurobiSolver {
+        let mut params: Vec<(String, String)> = self.params.iter().filter(|(k, _)| k != key).cloned().collect();
+        params.push((key.to_string(), value.to_string()));
+        GurobiSolver {
+            params,
+            ..self.clone()
+        }
+    }
+    /// Time limit given to `gurobi_cl` by the `TimeLimit` parameter, in seconds
+    pub fn time_limit(&self) -> Option<Duration> {
+        let (_, value) = self.params.iter().find(|(key, _)| key.eq_ignore_ascii_case("TimeLimit"))?;
+        value.parse::<f64>().ok().filter(|seconds| seconds.is_finite() && *seconds >= 0.0).map(Duration::from_secs_f64)
+    }
+    /// Arguments given to `gurobi_cl` to solve `file_model`, reading the warm start from `start_file`
+    pub fn arguments(&self, file_model: &str, start_file: &str) -> Vec<String> {
+        let mut arguments = vec![format!("ResultFile={}", solution_file(&self.work_dir, None, file_model))];
+        if self.warm_start.is_some() {
+            arguments.push(format!("InputFile={}", start_file));
+        }
+        if let Some(threads) = self.threads {
+            arguments.push(format!("Threads={}", threads));
+        }
+        arguments.extend(self.params.iter().map(|(key, value)| format!("{}={}", key, value)));
+        arguments.push(file_model.to_string());
+        arguments
+    }
+    /// Write the warm start values as a Gurobi MIP start (`.mst`) file, with the variables
+    /// renamed as in the LP file by `names`, see `sanitized_names`
+    pub fn write_warm_start(&self, start_file: &str, names: &HashMap<String, String>) -> io::Result<()> {
+        let mut buffer = File::create(start_file)?;
+        writeln!(buffer, "# MIP start")?;
+        if let Some(values) = &self.warm_start {
+            for (name, value) in sorted_warm_start(values, names) {
+                writeln!(buffer, "{} {}", name, value)?;
+            }
+        }
+        Ok(())
+    }
+    /// Round the values of integer variables within `epsilon` of an integer,
+    /// `DEFAULT_INTEGER_TOLERANCE` by default
+    pub fn with_integer_tolerance(&self, epsilon: f64) -> GurobiSolver {
+        GurobiSolver {
+            integer_tolerance: epsilon,
+            ..self.clone()
+        }
+    }
+
+    /// Whether the solver executable can be found, checked by `run` before writing any file
+    pub fn is_available(&self) -> bool {
+        command_available(&self.command_name)
+    }
+}
+
+/// Relative MIP gap and best objective bound from the `Best objective ..., best bound ..., gap ...%`
+/// line of the `gurobi_cl` output. Both are `None` for continuous problems.
+pub fn parse_mip_gap(output: &str) -> (Option<f64>, Option<f64>) {
+    let line = match output.lines().rev().find(|l| l.trim_start().starts_with("Best objective")) {
+        Some(line) => line,
+        None => return (None, None),
+    };
+    let value_after = |label: &str| {
+        line.find(label).and_then(|index| {
+            line[index + label.len()..]
+                .trim_start()
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .next()
+                .and_then(|value| value.trim_end_matches('%').parse::<f64>().ok())
+        })
+    };
+    let gap = value_after("gap").map(|percent| percent / 100.0);
+    (gap, value_after("best bound"))
+}
+
+/// Objective value from the `Optimal objective` line of the `gurobi_cl` output for continuous
+/// problems, or from the `Best objective` line for MIPs. `None` when no solution was found.
+pub fn parse_gurobi_objective(output: &str) -> Option<f64> {
+    output.lines().rev().find_map(|line| {
+        let line = line.trim_start();
+        let value = line.strip_prefix("Optimal objective").or_else(|| line.strip_prefix("Best objective"))?;
+        value.trim_start().split(|c: char| c == ',' || c.is_whitespace()).next()?.parse::<f64>().ok()
+    })
+}
+
+/// Status of a `gurobi_cl` run, from its output.
+///
+/// Gurobi statuses without an equivalent are mapped to the nearest one: a model found infeasible
+/// or unbounded (`INF_OR_UNBD`) is `Infeasible`, and a run stopped by numerical trouble
+/// (`NUMERIC`) or interrupted (`INTERRUPTED`) is `SubOptimal` if it found a solution,
+/// `NotSolved` otherwise.
+pub fn parse_gurobi_status(output: &str) -> Status {
+    let has_solution = !output
+        .lines()
+        .any(|l| l.trim() == "Solution count 0" || l.trim_start().starts_with("Solution count 0:"));
+    let output = output.to_lowercase();
+    let contains_any = |patterns: &[&str]| patterns.iter().any(|p| output.contains(p));
+    if contains_any(&["optimal solution found", "optimal objective"]) {
+        Status::Optimal
+    } else if output.contains("time limit reached") {
+        if has_solution {
+            Status::TimeLimit
+        } else {
+            Status::NotSolved
+        }
+    } else if contains_any(&["infeasible or unbounded", "inf_or_unbd"]) {
+        Status::Infeasible
+    } else if contains_any(&["unbounded model", "model is unbounded"]) {
+        Status::Unbounded
+    } else if output.contains("infeasible") {
+        Status::Infeasible
+    } else if contains_any(&["sub-optimal", "suboptimal"]) {
+        Status::SubOptimal
+    } else if contains_any(&["numeric", "interrupted"]) && !has_solution {
+        Status::NotSolved
+    } else {
+        Status::SubOptimal
+    }
+}
+
+impl WithWarmStart<GurobiSolver> for GurobiSolver {
+    fn warm_start(&self) -> Option<&HashMap<String, f64>> {
+        self.warm_start.as_ref()
+    }
+    fn with_warm_start(&self, values: HashMap<String, f64>) -> GurobiSolver {
+        GurobiSolver {
+            warm_start: Some(values),
+            ..self.clone()
+        }
+    }
+}
+
+impl WithNbThreads<GurobiSolver> for GurobiSolver {
+    fn nb_threads(&self) -> Option<u32> {
+        self.threads
+    }
+    fn with_nb_threads(&self, threads: u32) -> GurobiSolver {
+        GurobiSolver {
+            threads: Some(threads),
+            ..self.clone()
+        }
+    }
 }
 
 impl SolverWithSolutionParsing for GurobiSolver {
+    fn integer_tolerance(&self) -> f64 {
+        self.integer_tolerance
+    }
     fn read_specific_solution<'a>(
         &self,
         f: &File,
         problem: Option<&'a LpProblem>,
-    ) -> Result<Solution<'a>, String> {
+    ) -> Result<Solution<'a>, LpError> {
         let mut vars_value: HashMap<_, _> = HashMap::new();
         let mut file = BufReader::new(f);
         let mut buffer = String::new();
@@ -66,14 +238,14 @@ impl SolverWithSolutionParsing for GurobiSolver {
                         Ok(n) => {
                             vars_value.insert(result_line[0].to_string(), n);
                         }
-                        Err(e) => return Err(format!("{}", e.to_string())),
+                        Err(e) => return Err(LpError::ParseSolution(e.to_string())),
                     }
                 } else {
-                    return Err("Incorrect solution format".to_string());
+                    return Err(LpError::ParseSolution("Incorrect solution format".to_string()));
                 }
             }
         } else {
-            return Err("Incorrect solution format".to_string());
+            return Err(LpError::ParseSolution("Incorrect solution format".to_string()));
         }
         // TODO/FIX: always optimal if no err...
         if let Some(p) = problem {
@@ -86,50 +258,81 @@ impl SolverWithSolutionParsing for GurobiSolver {
 
 impl SolverTrait for GurobiSolver {
     type P = LpProblem;
-    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
-        let file_model = &format!("{}.lp", problem.unique_name);
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+        self.run_cancellable(problem, &AtomicBool::new(false))
+    }
 
-        match problem.write_lp(file_model) {
+    fn run_cancellable<'a>(&self, problem: &'a Self::P, cancel: &AtomicBool) -> Result<Solution<'a>, LpError> {
+        problem.check_not_empty()?;
+        if !self.is_available() {
+            return Err(LpError::SolverNotFound(self.name.clone()));
+        }
+        let stem = run_file_stem(problem);
+        let file_model = &work_file(&self.work_dir, &format!("{}.lp", stem));
+        let start_file = &work_file(&self.work_dir, &format!("{}.mst", stem));
+        let solution_file = &solution_file(&self.work_dir, None, file_model);
+        let names = sanitized_names(problem);
+        if self.warm_start.is_some() {
+            self.write_warm_start(start_file, &names)?;
+        }
+
+        match write_renamed_lp(problem, &names, file_model) {
             Ok(_) => {
-                let result = match Command::new(&self.command_name)
-                    .arg(format!("ResultFile={}", self.temp_solution_file))
-                    .arg(file_model)
-                    .output()
-                {
-                    Ok(r) => {
+                let start = Instant::now();
+                let output = Command::new(&self.command_name)
+                    .args(self.arguments(file_model, start_file))
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .and_then(|child| wait_cancellable(child, cancel, self.time_limit()));
+                let solve_time = start.elapsed();
+                let result = match output {
+                    Ok(Some(r)) => {
                         if r.status.success() {
-                            let mut status = Status::SubOptimal;
                             let result = String::from_utf8(r.stdout).expect("");
-                            if result.contains("Optimal solution found") {
-                                status = Status::Optimal;
-                            } else if result.contains("infeasible") {
-                                status = Status::Infeasible;
-                            }
-                            self.read_solution(&self.temp_solution_file, Some(problem)).map(
+                            let status = parse_gurobi_status(&result);
+                            let (mip_gap, objective_bound) = parse_mip_gap(&result);
+                            let objective = parse_gurobi_objective(&result);
+                            self.read_renamed_solution(solution_file, Some(problem), &names).map(
                                 |solution| Solution {
                                     status,
-                                    ..solution.clone()
+                                    solve_time: Some(solve_time),
+                                    mip_gap,
+                                    objective_bound: objective_bound.map(|b| b * problem.objective_scale()),
+                                    objective: objective.map(|o| o * problem.objective_scale()),
+                                    ..solution
                                 },
                             )
                         } else {
-                            Err(format!(
+                            Err(LpError::Solver(format!(
                                 "{} exited with {}\n\nSTDOUT:\n{}\n\nSTDERR:\n{}\n\n",
                                 self.command_name,
                                 r.status,
                                 String::from_utf8_lossy(&r.stdout),
                                 String::from_utf8_lossy(&r.stderr),
-                            ))
+                            )))
                         }
                     }
-                    Err(_) => Err(format!("Error running the {} solver", self.name)),
+                    Ok(None) => {
+                        let _ = fs::remove_file(solution_file);
+                        Err(LpError::Cancelled(Some(self.name.clone())))
+                    }
+                    Err(e) => {
+                        let _ = fs::remove_file(solution_file);
+                        Err(process_error(&self.name, e))
+                    }
                 };
                 if !self.keep_problem_file {
                     let _ = fs::remove_file(&file_model);
                 }
+                let _ = fs::remove_file(start_file);
 
                 result
             }
-            Err(e) => Err(e.to_string()),
+            Err(e) => {
+                let _ = fs::remove_file(start_file);
+                Err(e.into())
+            }
         }
     }
 }
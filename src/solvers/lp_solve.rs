@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::fs;
+use std::env;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+use dsl::LpProblem;
+use error::LpError;
+use format::lp_format::*;
+use solvers::{DEFAULT_INTEGER_TOLERANCE, Solution, SolverTrait, SolverWithSolutionParsing, Status, WithMaxSeconds, command_available, process_error, run_lowered, wait_cancellable, work_file, solution_file, run_file_stem};
+
+/// Solver running the `lp_solve` executable.
+///
+/// lp_solve has its own LP syntax, so the problem is read through the CPLEX LP reader plugin
+/// of lp_solve (`-rxli`), which has to be installed next to the executable.
+/// The solution is parsed from the output of lp_solve, saved into the temporary solution file.
+#[derive(Debug, Clone)]
+pub struct LpSolveSolver {
+    name: String,
+    command_name: String,
+    xli_name: String,
+    temp_solution_file: Option<String>,
+    work_dir: PathBuf,
+    seconds: Option<u32>,
+    integer_tolerance: f64,
+}
+
+impl LpSolveSolver {
+    pub fn new() -> LpSolveSolver {
+        LpSolveSolver {
+            name: "LpSolve".to_string(),
+            command_name: "lp_solve".to_string(),
+            xli_name: "xli_CPLEX".to_string(),
+            temp_solution_file: None,
+            work_dir: env::temp_dir(),
+            seconds: None,
+            integer_tolerance: DEFAULT_INTEGER_TOLERANCE,
+        }
+    }
+    pub fn command_name(&self, command_name: String) -> LpSolveSolver {
+        LpSolveSolver {
+            command_name,
+            ..self.clone()
+        }
+    }
+    /// Name or path of the reader plugin for the CPLEX LP format, `xli_CPLEX` by default
+    pub fn xli_name(&self, xli_name: String) -> LpSolveSolver {
+        LpSolveSolver {
+            xli_name,
+            ..self.clone()
+        }
+    }
+    pub fn with_temp_solution_file(&self, temp_solution_file: String) -> LpSolveSolver {
+        LpSolveSolver {
+            temp_solution_file: Some(temp_solution_file),
+            ..self.clone()
+        }
+    }
+    /// Create the model and solution files in `work_dir`, the temporary directory of the system
+    /// by default
+    pub fn with_work_dir<P: Into<PathBuf>>(&self, work_dir: P) -> LpSolveSolver {
+        LpSolveSolver {
+            work_dir: work_dir.into(),
+            ..self.clone()
+        }
+    }
+    /// Round the values of integer variables within `epsilon` of an integer,
+    /// `DEFAULT_INTEGER_TOLERANCE` by default
+    pub fn with_integer_tolerance(&self, epsilon: f64) -> LpSolveSolver {
+        LpSolveSolver {
+            integer_tolerance: epsilon,
+            ..self.clone()
+        }
+    }
+
+    /// Arguments given to lp_solve to solve `file_model`, printing all the values (`-S3`)
+    pub fn arguments(&self, file_model: &str) -> Vec<String> {
+        let mut arguments = vec!["-S3".to_string()];
+        if let Some(seconds) = self.seconds {
+            arguments.push("-timeout".to_string());
+            arguments.push(seconds.to_string());
+        }
+        arguments.push("-rxli".to_string());
+        arguments.push(self.xli_name.clone());
+        arguments.push(file_model.to_string());
+        arguments
+    }
+
+    /// Whether the solver executable can be found, checked by `run` before writing any file
+    pub fn is_available(&self) -> bool {
+        command_available(&self.command_name)
+    }
+}
+
+impl Default for LpSolveSolver {
+    fn default() -> LpSolveSolver {
+        LpSolveSolver::new()
+    }
+}
+
+impl WithMaxSeconds<LpSolveSolver> for LpSolveSolver {
+    fn max_seconds(&self) -> Option<u32> {
+        self.seconds
+    }
+    fn with_max_seconds(&self, seconds: u32) -> LpSolveSolver {
+        LpSolveSolver {
+            seconds: Some(seconds),
+            ..self.clone()
+        }
+    }
+}
+
+impl SolverWithSolutionParsing for LpSolveSolver {
+    fn integer_tolerance(&self) -> f64 {
+        self.integer_tolerance
+    }
+    fn read_specific_solution<'a>(&self, f: &File, problem: Option<&'a LpProblem>) -> Result<Solution<'a>, LpError> {
+        let mut vars_value: HashMap<_, _> = HashMap::new();
+        let mut status = None;
+        let mut in_variables = false;
+        for line in BufReader::new(f).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.starts_with("This problem is infeasible") {
+                status = Some(Status::Infeasible);
+            } else if line.starts_with("This problem is unbounded") {
+                status = Some(Status::Unbounded);
+            } else if line.starts_with("The model is sub-optimal") {
+                status = Some(Status::SubOptimal);
+            } else if line.starts_with("Value of objective function:") {
+                status = status.or(Some(Status::Optimal));
+            } else if line.starts_with("Actual values of the variables") {
+                in_variables = true;
+            } else if line.starts_with("Actual values of the constraints") || line.starts_with("Dual value") {
+                in_variables = false;
+            } else if in_variables && !line.is_empty() {
+                let result_line: Vec<_> = line.split_whitespace().collect();
+                if result_line.len() != 2 {
+                    return Err(LpError::ParseSolution(
+                        "Incorrect solution format: Variable line has to have two fields".to_string(),
+                    ));
+                }
+                match result_line[1].parse::<f64>() {
+                    Ok(n) => {
+                        vars_value.insert(result_line[0].to_string(), n);
+                    }
+                    Err(e) => return Err(LpError::ParseSolution(e.to_string())),
+                }
+            }
+        }
+        let status = status.ok_or_else(|| {
+            LpError::ParseSolution("Incorrect solution format: No solution status found".to_string())
+        })?;
+
+        if let Some(p) = problem {
+            Ok(Solution::with_problem(status, vars_value, p))
+        } else {
+            Ok(Solution::new(status, vars_value))
+        }
+    }
+}
+
+impl SolverTrait for LpSolveSolver {
+    type P = LpProblem;
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+        self.run_cancellable(problem, &AtomicBool::new(false))
+    }
+
+    fn run_cancellable<'a>(&self, problem: &'a Self::P, cancel: &AtomicBool) -> Result<Solution<'a>, LpError> {
+        problem.check_not_empty()?;
+        if !problem.semi_continuous_variables().is_empty() {
+            return run_lowered(problem, |lowered| self.run_cancellable(lowered, cancel));
+        }
+        if !self.is_available() {
+            return Err(LpError::SolverNotFound(self.name.clone()));
+        }
+        let file_model = &work_file(&self.work_dir, &format!("{}.lp", run_file_stem(problem)));
+        let solution_file = &solution_file(&self.work_dir, self.temp_solution_file.as_ref(), file_model);
+        let names = sanitized_names(problem);
+        write_renamed_lp(problem, &names, file_model)?;
+
+        let start = Instant::now();
+        let output = Command::new(&self.command_name)
+            .args(self.arguments(file_model))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .and_then(|child| wait_cancellable(child, cancel, self.seconds.map(|s| Duration::from_secs(s.into()))));
+        let solve_time = start.elapsed();
+        let result = match output {
+            Ok(Some(r)) => {
+                // lp_solve exits with a non-zero code for infeasible and unbounded problems as well,
+                // so its output is parsed whatever the exit code
+                fs::write(solution_file, &r.stdout)
+                    .map_err(LpError::from)
+                    .and_then(|_| self.read_renamed_solution(solution_file, Some(problem), &names))
+                    .map(|solution| Solution {
+                        solve_time: Some(solve_time),
+                        ..solution
+                    })
+                    .map_err(|e| {
+                        if r.status.success() {
+                            e
+                        } else {
+                            LpError::Solver(format!(
+                                "{} exited with {}\n\nSTDOUT:\n{}\n\nSTDERR:\n{}\n\n",
+                                self.command_name,
+                                r.status,
+                                String::from_utf8_lossy(&r.stdout),
+                                String::from_utf8_lossy(&r.stderr),
+                            ))
+                        }
+                    })
+            }
+            Ok(None) => Err(LpError::Cancelled(Some(self.name.clone()))),
+            Err(e) => Err(process_error(&self.name, e)),
+        };
+        let _ = fs::remove_file(solution_file);
+        let _ = fs::remove_file(file_model);
+
+        result
+    }
+}
@@ -1,6 +1,11 @@
-use dsl::{LpObjective, LpProblem, LpConstraint, LpExpression, Constraint, LpExprNode, LpContinuous};
+use dsl::{LpObjective, LpProblem, LpConstraint, LpExpression, Constraint, LpExprNode, LpContinuous, ProblemUpdate};
+use dsl::problem::non_finite_coefficient;
+use error::{DecomposeError, DecomposeReason, LpError, NodeKind};
 use std::collections::HashMap;
-use solvers::{SolverTrait, Solution, Status};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use solvers::{DEFAULT_INTEGER_TOLERANCE, SolverTrait, Solution, Status, WithNbThreads, run_lowered};
+use solvers::sensitivity::sensitivity_analysis;
 use dsl::LpExprNode::LitVal;
 use dsl::LpExprOp::{Multiplication, Addition, Subtraction};
 
@@ -15,24 +20,56 @@ fn add_constraint_to_minilp(
     constraint: &LpConstraint,
     variables: &mut HashMap<String, minilp::Variable>,
     pb: &mut minilp::Problem,
-) -> Result<(), String> {
-    let LpConstraint(expr, op, constant_arena) = constraint.clone();
-    let constant = if let &LitVal(c) = constant_arena.get_root_expr_ref() { c } else {
-        return Err("not properly simplified".into());
-    };
-    let expr_variables = decompose_expression(expr)?;
+) -> Result<(), LpError> {
+    let LpConstraint(_, op, _) = constraint;
+    let (expr_variables, constant) = decompose_constraint(constraint)?;
     let mut expr = minilp::LinearExpr::empty();
-    for (name, coefficient) in expr_variables.0 {
+    for (name, coefficient) in finite_coefficients(expr_variables)?.0 {
         let var = variables.entry(name).or_insert_with(|| {
-            pb.add_var(0., (f64::NEG_INFINITY, f64::INFINITY))
+            pb.add_var(0., (coefficient.min, coefficient.max))
         }).clone();
         expr.add(var, coefficient.coefficient.into());
     }
-    let op = comparison_to_minilp(op);
+    let op = comparison_to_minilp(op.clone());
     pb.add_constraint(expr, op, f64::from(constant));
     Ok(())
 }
 
+/// Variables of `lhs - rhs` and constant moved to the right-hand side of the constraint.
+/// No arena is cloned, unless the constraint is not a sum of terms and has to be simplified first.
+fn decompose_constraint(constraint: &LpConstraint) -> Result<(VarList, f64), LpError> {
+    if let Some(decomposed) = decompose_linear_constraint(constraint) {
+        return Ok(decomposed);
+    }
+    // move the variables to the left and the constants to the right: a <= b becomes a - b <= 0
+    let LpConstraint(expr, _, constant_arena) = constraint.generalize();
+    let constant = if let &LitVal(c) = constant_arena.get_root_expr_ref() { c } else {
+        return Err(LpError::Nonlinear("not properly simplified".to_string()));
+    };
+    Ok((decompose_expression(expr)?, constant))
+}
+
+/// `decompose_constraint` for a constraint whose both sides are sums of terms, only borrowed.
+/// `None` if a side has to be simplified first.
+fn decompose_linear_constraint(constraint: &LpConstraint) -> Option<(VarList, f64)> {
+    let LpConstraint(lhs, _, rhs) = constraint;
+    let (lhs_terms, lhs_constant) = lhs.linear_term_nodes()?;
+    let (rhs_terms, rhs_constant) = rhs.linear_term_nodes()?;
+    let mut decomposed = VarList::with_capacity(lhs_terms.len() + rhs_terms.len());
+    let terms = lhs_terms
+        .into_iter()
+        .chain(rhs_terms.into_iter().map(|(name, node, coefficient)| (name, node, -coefficient)));
+    for (_, node, coefficient) in terms {
+        // integer variables are relaxed, `problem_to_minilp` checks that it is allowed
+        if let Some(var) = node.relaxed_variable() {
+            decomposed.add(var, coefficient);
+        }
+    }
+    // variables cancelling out are left out, as in a simplified expression
+    decomposed.0.retain(|_, var| var.coefficient != 0.0);
+    Some((decomposed, rhs_constant - lhs_constant))
+}
+
 fn comparison_to_minilp(op: Constraint) -> minilp::ComparisonOp {
     match op {
         Constraint::GreaterOrEqual => minilp::ComparisonOp::Ge,
@@ -58,6 +95,10 @@ impl Default for VarWithCoeff {
 struct VarList(HashMap<String, VarWithCoeff>);
 
 impl VarList {
+    fn with_capacity(capacity: usize) -> VarList {
+        VarList(HashMap::with_capacity(capacity))
+    }
+
     fn add(&mut self, var: LpContinuous, coefficient: f64) {
         let LpContinuous { name, lower_bound, upper_bound } = var;
         let prev = self.0.entry(name).or_default();
@@ -73,18 +114,31 @@ impl VarList {
 
 fn decompose_expression(
     mut expr: LpExpression,
-) -> Result<VarList, String> {
+) -> Result<VarList, DecomposeError> {
+    if expr.variable_nodes().is_empty() {
+        // constant expression, e.g. the zero objective of a feasibility problem
+        return Ok(VarList::with_capacity(0));
+    }
     expr.simplify();
-    let mut decomposed = VarList::default();
+    let mut decomposed = VarList::with_capacity(expr.variable_node_count());
     let mut idxs = vec![(1., expr.get_root_index())];
     while let Some((factor, idx)) = idxs.pop() {
         match expr.expr_ref_at(idx) {
-            LpExprNode::ConsCont(var) => { decomposed.add(var.clone(), factor) }
+            node @ LpExprNode::ConsCont(_)
+            | node @ LpExprNode::ConsInt(_)
+            | node @ LpExprNode::ConsBin(_) => {
+                decomposed.add(node.relaxed_variable().expect("variable node"), factor)
+            }
             &LpExprNode::LpCompExpr(Multiplication, lhs, rhs) => {
                 if let &LpExprNode::LitVal(lit) = expr.expr_ref_at(lhs) {
                     idxs.push((factor * lit, rhs))
                 } else {
-                    return Err(format!("Non-simplified multiplication: {:?}", expr.expr_ref_at(idx)));
+                    return Err(DecomposeError {
+                        index: idx,
+                        kind: NodeKind::of(expr.expr_ref_at(idx)),
+                        reason: DecomposeReason::Nonlinear,
+                        node: format!("{:?}", expr.expr_ref_at(idx)),
+                    });
                 }
             }
             &LpExprNode::LpCompExpr(Addition, lhs, rhs) => {
@@ -95,21 +149,38 @@ fn decompose_expression(
                 idxs.push((factor, lhs));
                 idxs.push((-factor, rhs));
             }
-            x => return Err(format!("Unsupported expression: {:?}", x))
+            node => {
+                return Err(DecomposeError {
+                    index: idx,
+                    kind: NodeKind::of(node),
+                    reason: DecomposeReason::UnsupportedNode,
+                    node: format!("{:?}", node),
+                })
+            }
         }
     }
     Ok(decomposed)
 }
 
+fn finite_coefficients(decomposed: VarList) -> Result<VarList, LpError> {
+    if let Some((name, var)) = decomposed.0.iter().find(|(_, var)| !var.coefficient.is_finite()) {
+        return Err(non_finite_coefficient(name, var.coefficient));
+    }
+    Ok(decomposed)
+}
+
 
 /// Returns a map from dsl variable name to minilp variable
+/// With `feasibility_only`, the variables are added with a zero objective coefficient
 fn add_objective_to_minilp(
     objective: LpExpression,
     pb: &mut minilp::Problem,
-) -> Result<HashMap<String, minilp::Variable>, String> {
-    let vars = decompose_expression(objective)?;
+    feasibility_only: bool,
+) -> Result<HashMap<String, minilp::Variable>, LpError> {
+    let vars = finite_coefficients(decompose_expression(objective)?)?;
     Ok(vars.0.into_iter()
         .map(|(name, VarWithCoeff { coefficient, min, max })| {
+            let coefficient = if feasibility_only { 0. } else { coefficient };
             let var = pb.add_var(
                 coefficient.into(),
                 (min.into(), max.into()),
@@ -119,11 +190,43 @@ fn add_objective_to_minilp(
     )
 }
 
-fn problem_to_minilp(pb: &LpProblem) -> Result<(minilp::Problem, Vec<Option<String>>), String> {
+/// Returns the minilp problem and a map from dsl variable name to minilp variable
+/// The integer and binary variables accepted by `relaxed` are solved as continuous ones,
+/// the others are rejected
+fn problem_to_minilp(
+    pb: &LpProblem,
+    feasibility_only: bool,
+    relaxed: impl Fn(&str) -> bool,
+) -> Result<(minilp::Problem, HashMap<String, minilp::Variable>), LpError> {
+    if !pb.sos2.is_empty() {
+        return Err(LpError::Unsupported("Special ordered sets are not supported by minilp".to_string()));
+    }
+    let integers: Vec<String> = pb.integer_variable_names().into_iter().filter(|name| !relaxed(name)).collect();
+    if !integers.is_empty() {
+        return Err(LpError::Unsupported(format!(
+            "minilp only supports continuous variables, found integer variables {}",
+            integers.join(", ")
+        )));
+    }
+    if !pb.semi_continuous_variables().is_empty() {
+        return Err(LpError::Unsupported(
+            "Semi-continuous variables have to be lowered by LpProblem::lower_semi_continuous for minilp".to_string(),
+        ));
+    }
+    pb.check_not_empty()?;
+    pb.check_bounds()?;
     let objective = direction_to_minilp(&pb.objective_type);
     let mut minilp_pb = minilp::Problem::new(objective);
-    let objective = pb.obj_expr_arena.clone().ok_or("Missing objective")?;
-    let mut minilp_variables = add_objective_to_minilp(objective, &mut minilp_pb)?;
+    let objective = pb.obj_expr_arena.clone().ok_or_else(|| LpError::MissingObjective("Missing objective".to_string()))?;
+    let mut minilp_variables = add_objective_to_minilp(objective, &mut minilp_pb, feasibility_only)?;
+    // upper bound of the number of variables found only in the constraints, counting the variables
+    // shared by several constraints once per constraint
+    minilp_variables.reserve(
+        pb.constraints
+            .iter()
+            .map(|LpConstraint(lhs, _, rhs)| lhs.variable_node_count() + rhs.variable_node_count())
+            .sum(),
+    );
     for constraint in &pb.constraints {
         add_constraint_to_minilp(
             constraint,
@@ -131,33 +234,265 @@ fn problem_to_minilp(pb: &LpProblem) -> Result<(minilp::Problem, Vec<Option<Stri
             &mut minilp_pb,
         )?;
     }
-    let mut ordered_vars = vec![None; minilp_variables.len()];
-    for (name, var) in minilp_variables {
-        ordered_vars[var.idx()] = Some(name);
+    Ok((minilp_pb, minilp_variables))
+}
+
+/// Names of the dsl variables, indexed by minilp variable
+fn variable_names(variables: &HashMap<String, minilp::Variable>) -> Vec<Option<String>> {
+    let mut ordered_vars = vec![None; variables.len()];
+    for (name, var) in variables {
+        ordered_vars[var.idx()] = Some(name.clone());
     }
-    Ok((minilp_pb, ordered_vars))
+    ordered_vars
 }
 
-pub struct MiniLpSolver;
+/// Depth-first branch and bound over the `integers` of a solved relaxation, exploring first the
+/// branch on the nearest side of a fractional value. With `first`, the first integral solution
+/// is returned, otherwise the best one.
+fn branch_and_bound(
+    relaxation: Result<minilp::Solution, minilp::Error>,
+    integers: &[minilp::Variable],
+    direction: minilp::OptimizationDirection,
+    tolerance: f64,
+    first: bool,
+) -> Result<minilp::Solution, minilp::Error> {
+    let better = |objective: f64, best: f64| match direction {
+        minilp::OptimizationDirection::Minimize => objective < best - tolerance,
+        minilp::OptimizationDirection::Maximize => objective > best + tolerance,
+    };
+    let mut best: Option<minilp::Solution> = None;
+    let mut nodes = vec![relaxation?];
+    while let Some(solution) = nodes.pop() {
+        if best.as_ref().is_some_and(|best| !better(solution.objective(), best.objective())) {
+            continue;
+        }
+        let fractional = integers.iter().find(|&&var| (solution[var] - solution[var].round()).abs() > tolerance);
+        if let Some(&var) = fractional {
+            let value = solution[var];
+            let down = solution.clone().add_constraint([(var, 1.)], minilp::ComparisonOp::Le, value.floor());
+            let up = solution.add_constraint([(var, 1.)], minilp::ComparisonOp::Ge, value.ceil());
+            // the nearest branch is pushed last, to be explored first
+            let branches = if value - value.floor() < 0.5 { vec![up, down] } else { vec![down, up] };
+            // a branch can only be infeasible: the relaxation is bounded
+            nodes.extend(branches.into_iter().filter_map(Result::ok));
+        } else {
+            best = Some(solution);
+            if first {
+                break;
+            }
+        }
+    }
+    best.ok_or(minilp::Error::Infeasible)
+}
+
+#[derive(Debug, Clone)]
+pub struct MiniLpSolver {
+    first_feasible: bool,
+    relax_integers: bool,
+    threads: Option<u32>,
+    integer_tolerance: f64,
+    sensitivity: bool,
+}
+
+impl Default for MiniLpSolver {
+    fn default() -> Self {
+        MiniLpSolver {
+            first_feasible: false,
+            relax_integers: false,
+            threads: None,
+            integer_tolerance: DEFAULT_INTEGER_TOLERANCE,
+            sensitivity: false,
+        }
+    }
+}
 
 impl MiniLpSolver {
-    pub fn new() -> Self { Self }
+    pub fn new() -> Self { Self::default() }
+
+    /// Stop at the first feasible point found, ignoring the objective.
+    /// The solution then has the `SubOptimal` status.
+    ///
+    /// Integer and binary variables are branched on until they all have an integer value,
+    /// unless they are relaxed by `relax_integers`.
+    pub fn first_feasible(self) -> Self {
+        MiniLpSolver { first_feasible: true, ..self }
+    }
+
+    /// Solve the linear relaxation of problems with integer or binary variables,
+    /// which are rejected otherwise.
+    /// The integer variables with a fractional value are listed by `Solution::fractional_integers`.
+    /// The indicators of the semi-continuous variables are still branched on.
+    pub fn relax_integers(self) -> Self {
+        MiniLpSolver { relax_integers: true, ..self }
+    }
+
+    /// Round the values of relaxed integer variables within `epsilon` of an integer,
+    /// `DEFAULT_INTEGER_TOLERANCE` by default
+    pub fn with_integer_tolerance(self, epsilon: f64) -> Self {
+        MiniLpSolver { integer_tolerance: epsilon, ..self }
+    }
+
+    /// Fill `Solution::sensitivity` with the ranges of the objective coefficients of the
+    /// variables and of the right-hand sides of the constraints, computed by
+    /// `sensitivity::sensitivity_analysis`. It stays `None` when the analysis fails, e.g. for
+    /// problems with integer variables.
+    pub fn with_sensitivity(self) -> Self {
+        MiniLpSolver { sensitivity: true, ..self }
+    }
+
+    /// Solve `problem`, branching on its `indicators` and on its integer variables when looking
+    /// for a first feasible point without `relax_integers`
+    fn solve<'a>(&self, problem: &'a LpProblem, indicators: &[String]) -> Result<Solution<'a>, LpError> {
+        let branched_name =
+            |name: &str| indicators.iter().any(|indicator| indicator == name) || (self.first_feasible && !self.relax_integers);
+        let (minilp_pb, minilp_variables) =
+            problem_to_minilp(problem, self.first_feasible, |name| self.relax_integers || branched_name(name))?;
+        let tolerance = self.integer_tolerance;
+        let branched: Vec<minilp::Variable> = problem
+            .integer_variable_names()
+            .iter()
+            .filter(|name| branched_name(name))
+            .filter_map(|name| minilp_variables.get(name).cloned())
+            .collect();
+        // `Instant::now` panics on wasm32-unknown-unknown: the solve time is not measured there
+        #[cfg(not(target_arch = "wasm32"))]
+        let start = Instant::now();
+        let minilp_result = if branched.is_empty() {
+            minilp_pb.solve()
+        } else {
+            let direction = direction_to_minilp(&problem.objective_type);
+            branch_and_bound(minilp_pb.solve(), &branched, direction, tolerance, self.first_feasible)
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let solve_time = Some(start.elapsed());
+        #[cfg(target_arch = "wasm32")]
+        let solve_time = None;
+        let solution = solution_from_minilp(minilp_result, variable_names(&minilp_variables)).map(|solution| {
+            let status = match solution.status {
+                Status::Optimal if self.first_feasible => Status::SubOptimal,
+                status => status,
+            };
+            let mut solution = Solution {
+                status,
+                related_problem: Some(problem),
+                solve_time,
+                ..solution
+            };
+            solution.round_integers(tolerance);
+            solution.add_unused_declared(problem);
+            solution
+        })?;
+        if self.sensitivity && solution.status == Status::Optimal {
+            // a failed analysis, e.g. of a problem with integer variables, leaves the solution without it
+            let sensitivity = sensitivity_analysis(problem, &solution).ok();
+            return Ok(Solution { sensitivity, ..solution });
+        }
+        Ok(solution)
+    }
+}
+
+/// minilp is single-threaded: the number of threads is stored but ignored
+impl WithNbThreads<MiniLpSolver> for MiniLpSolver {
+    fn nb_threads(&self) -> Option<u32> {
+        self.threads
+    }
+    fn with_nb_threads(&self, threads: u32) -> MiniLpSolver {
+        MiniLpSolver {
+            threads: Some(threads),
+            ..self.clone()
+        }
+    }
 }
 
 impl SolverTrait for MiniLpSolver {
     type P = LpProblem;
 
-    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
-        let (minilp_pb, variable_names) = problem_to_minilp(problem)?;
-        let minilp_result = minilp_pb.solve();
-        solution_from_minilp(minilp_result, variable_names)
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+        if problem.semi_continuous_variables().is_empty() {
+            return self.solve(problem, &[]);
+        }
+        // the indicator variables of the lowering are branched on, even with `relax_integers`
+        let indicators: Vec<String> =
+            problem.semi_continuous_indicators().into_iter().map(|(_, indicator)| indicator.name).collect();
+        run_lowered(problem, |lowered| self.solve(lowered, &indicators))
+    }
+}
+
+/// Stateful minilp solver keeping the built problem between solves,
+/// so that constraints (e.g. cutting planes) can be added one at a time
+/// without rebuilding the whole problem. It keeps its own copy of the `LpProblem`,
+/// which the changes of the original problem do not reach.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::solvers::IncrementalMiniLpSolver;
+///
+/// let ref x = LpContinuous::new("x");
+/// let mut problem = LpProblem::new("Cuts", LpObjective::Maximize);
+/// problem += x;
+/// problem += x.le(10);
+///
+/// let mut solver = IncrementalMiniLpSolver::new(&problem).unwrap();
+/// solver.add_constraint(&x.le(5)).unwrap();
+/// let solution = solver.resolve().unwrap();
+/// assert_eq!(solution.results["x"], 5.0);
+/// ```
+pub struct IncrementalMiniLpSolver {
+    source: LpProblem,
+    problem: minilp::Problem,
+    variables: HashMap<String, minilp::Variable>,
+}
+
+impl IncrementalMiniLpSolver {
+    pub fn new(problem: &LpProblem) -> Result<Self, LpError> {
+        let source = problem.clone();
+        let (problem, variables) = problem_to_minilp(&source, false, |_| false)?;
+        Ok(IncrementalMiniLpSolver { source, problem, variables })
+    }
+
+    /// Append a single row to the built problem
+    pub fn add_constraint(&mut self, constraint: &LpConstraint) -> Result<(), LpError> {
+        add_constraint_to_minilp(constraint, &mut self.variables, &mut self.problem)?;
+        self.source.constraints.push(constraint.clone());
+        Ok(())
+    }
+
+    /// Remove the constraint at `index`, counting the added ones after the constraints of the
+    /// original problem, as `LpProblem::remove_constraint` does. minilp cannot drop a row: the
+    /// problem is built again, without the constraint.
+    pub fn remove_constraint(&mut self, index: usize) -> Result<Option<LpConstraint>, LpError> {
+        let mut source = self.source.clone();
+        let removed = source.remove_constraint(index);
+        if removed.is_some() {
+            let (problem, variables) = problem_to_minilp(&source, false, |_| false)?;
+            *self = IncrementalMiniLpSolver { source, problem, variables };
+        }
+        Ok(removed)
+    }
+
+    /// Apply the objective coefficient and bound updates of `update`, as
+    /// `LpProblem::apply_updates` does. minilp cannot change a built variable: the problem is
+    /// built again, with the updates. Fails, leaving the solver unchanged, if a variable is not
+    /// part of the problem.
+    pub fn apply_updates(&mut self, update: &ProblemUpdate) -> Result<(), LpError> {
+        let mut source = self.source.clone();
+        source.apply_updates(update)?;
+        let (problem, variables) = problem_to_minilp(&source, false, |_| false)?;
+        *self = IncrementalMiniLpSolver { source, problem, variables };
+        Ok(())
+    }
+
+    pub fn resolve(&self) -> Result<Solution<'static>, LpError> {
+        solution_from_minilp(self.problem.solve(), variable_names(&self.variables))
     }
 }
 
 fn solution_from_minilp(
     result: Result<minilp::Solution, minilp::Error>,
     mut variable_names: Vec<Option<String>>,
-) -> Result<Solution<'static>, String> {
+) -> Result<Solution<'static>, LpError> {
     match result {
         Ok(solution) => {
             let results: Option<HashMap<String, f64>> = solution.iter()
@@ -170,7 +505,7 @@ fn solution_from_minilp(
             if let Some(results) = results {
                 Ok(Solution::new(Status::Optimal, results))
             } else {
-                Err("missing variable name".into())
+                Err(LpError::Solver("missing variable name".to_string()))
             }
         }
         Err(minilp::Error::Unbounded) => {
@@ -194,6 +529,30 @@ fn test_decompose() {
     assert_eq!(decomposed, Ok(expected));
 }
 
+#[test]
+fn test_decompose_product_of_variables() {
+    let a = &LpContinuous::new("a");
+    let b = &LpContinuous::new("b");
+    let expr = 2 * a + a * b;
+    let mut simplified = expr.clone();
+    simplified.simplify();
+
+    let error = decompose_expression(expr).unwrap_err();
+    assert_eq!(error.reason, DecomposeReason::Nonlinear);
+    match error.kind {
+        NodeKind::Multiplication(lhs, rhs) => {
+            assert_eq!(simplified.expr_ref_at(error.index), &LpExprNode::LpCompExpr(Multiplication, lhs, rhs));
+            assert_eq!(simplified.expr_ref_at(lhs), &LpExprNode::ConsCont(a.clone()));
+            assert_eq!(simplified.expr_ref_at(rhs), &LpExprNode::ConsCont(b.clone()));
+        }
+        kind => panic!("expected a multiplication, got {:?}", kind),
+    }
+    assert_eq!(
+        LpError::from(error.clone()).to_string(),
+        format!("Non-simplified multiplication: {:?}", simplified.expr_ref_at(error.index))
+    );
+}
+
 #[test]
 fn test_solve() {
     use dsl::operations::LpOperations;
@@ -214,6 +573,161 @@ fn test_solve() {
     assert_eq!(actual, expected);
 }
 
+#[test]
+fn test_objective_value() {
+    use dsl::operations::LpOperations;
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+
+    let mut problem = LpProblem::new("Objective value", LpObjective::Maximize);
+    problem += 3 * x + 2 * y + 10;
+    problem += (x + y).le(4);
+    problem += x.le(3);
+    problem += x.ge(0);
+    problem += y.ge(0);
+
+    let solution = MiniLpSolver::new().run(&problem).expect("could not solve");
+    let objective = solution.objective_value(&problem).expect("missing variable");
+    assert!((objective - 21.).abs() < 1e-9);
+
+    let partial = Solution::new(Status::Optimal, vec![("x".to_string(), 3.)].into_iter().collect());
+    assert!(partial.objective_value(&problem).is_err());
+}
+
+#[test]
+fn test_incremental() {
+    use dsl::operations::LpOperations;
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+
+    let mut problem = LpProblem::new("Cutting planes", LpObjective::Maximize);
+    problem += 3 * x + 2 * y;
+    problem += x.ge(0);
+    problem += y.ge(0);
+    problem += (x + y).le(10);
+
+    let mut solver = IncrementalMiniLpSolver::new(&problem).expect("could not build");
+    let cuts = vec![x.le(6), (x + 2 * y).le(12), (2 * x + y).le(13)];
+    for cut in cuts {
+        solver.add_constraint(&cut).expect("could not add constraint");
+        problem += cut;
+        let incremental = solver.resolve().expect("could not solve").results;
+        let from_scratch = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+        for (name, value) in from_scratch {
+            assert!((incremental[&name] - value).abs() < 1e-9);
+        }
+    }
+
+    // removing the cut x + 2 y <= 12 in the middle frees y: the optimum moves from (14/3, 11/3)
+    let removed = solver.remove_constraint(4).expect("could not rebuild");
+    assert_eq!(removed, problem.remove_constraint(4));
+    assert_eq!(removed, Some((x + 2 * y).le(12)));
+    let incremental = solver.resolve().expect("could not solve").results;
+    let from_scratch = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    assert!((incremental["x"] - 3.).abs() < 1e-9 && (incremental["y"] - 7.).abs() < 1e-9);
+    for (name, value) in from_scratch {
+        assert!((incremental[&name] - value).abs() < 1e-9);
+    }
+    assert_eq!(solver.remove_constraint(10).expect("could not rebuild"), None);
+
+    // the objective and the bounds change together
+    let mut update = ProblemUpdate::default();
+    update.objective_coefficients.insert("x".to_string(), 1.0);
+    update.bounds.insert("y".to_string(), (Some(0.0), Some(5.0)));
+    solver.apply_updates(&update).expect("could not rebuild");
+    problem.apply_updates(&update).expect("could not update");
+    let incremental = solver.resolve().expect("could not solve").results;
+    let from_scratch = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    assert!((incremental["x"] - 4.).abs() < 1e-9 && (incremental["y"] - 5.).abs() < 1e-9);
+    for (name, value) in from_scratch {
+        assert!((incremental[&name] - value).abs() < 1e-9);
+    }
+    update.bounds.insert("z".to_string(), (None, None));
+    assert!(solver.apply_updates(&update).is_err());
+    assert!((solver.resolve().expect("could not solve").results["y"] - 5.).abs() < 1e-9);
+}
+
+#[test]
+fn test_conflicting_bounds() {
+    use dsl::operations::LpOperations;
+    use dsl::BoundableLp;
+    let x = LpContinuous::new("x");
+
+    let mut problem = LpProblem::new("Conflicting bounds", LpObjective::Maximize);
+    problem += &x.clone().lower_bound(5.);
+    problem += x.clone().upper_bound(3.).le(10);
+
+    let expected = "Conflicting bounds for variable x: lower bound 5 exceeds upper bound 3";
+    match problem.check_bounds() {
+        Err(e @ LpError::ConflictingBounds { .. }) => assert_eq!(e.to_string(), expected),
+        result => panic!("expected conflicting bounds, got {:?}", result),
+    }
+    assert_eq!(MiniLpSolver::new().run(&problem).err().map(|e| e.to_string()), Some(expected.to_string()));
+}
+
+#[test]
+fn test_first_feasible() {
+    use dsl::operations::LpOperations;
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+
+    let mut problem = LpProblem::new("First feasible", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x + y).le(10);
+    problem += (x - y).ge(2);
+    problem += x.ge(3);
+    problem += y.ge(0);
+
+    let solution = MiniLpSolver::new().first_feasible().run(&problem).expect("could not solve");
+    assert_eq!(solution.status, Status::SubOptimal);
+    let (x, y) = (solution.results["x"], solution.results["y"]);
+    assert!(x + y <= 10. + 1e-9);
+    assert!(x - y >= 2. - 1e-9);
+    assert!(x >= 3. - 1e-9 && y >= -1e-9);
+
+    let optimal = MiniLpSolver::new().run(&problem).expect("could not solve");
+    assert_eq!(optimal.status, Status::Optimal);
+    assert!(solution.objective_value(&problem).unwrap() <= optimal.objective_value(&problem).unwrap() + 1e-9);
+}
+
+#[test]
+fn test_first_feasible_integers() {
+    use dsl::operations::LpOperations;
+    use dsl::{BoundableLp, LpInteger};
+    let x = &LpInteger::new("x").lower_bound(0.);
+    let y = &LpInteger::new("y").lower_bound(0.);
+
+    // the vertices of the polytope are fractional, (1.5, 2) and (3.5, 0) among others
+    let mut problem = LpProblem::new("First feasible integers", LpObjective::Maximize);
+    problem += x + y;
+    problem += (2 * x + 2 * y).ge(3);
+    problem += (2 * x + 2 * y).le(7);
+    problem += (2 * x).ge(3);
+
+    let solution = MiniLpSolver::new().first_feasible().run(&problem).expect("could not solve");
+    assert_eq!(solution.status, Status::SubOptimal);
+    assert!(solution.fractional_integers(&problem).is_empty());
+    assert!(problem.is_feasible(&solution.results, 1e-9).unwrap());
+
+    problem += (2 * y).ge(3);
+    problem += (x + y).le(3);
+    let solution = MiniLpSolver::new().first_feasible().run(&problem).expect("could not solve");
+    assert_eq!(solution.status, Status::Infeasible);
+}
+
+#[test]
+fn test_solve_time() {
+    use dsl::operations::LpOperations;
+    let x = &LpContinuous::new("x");
+    let mut problem = LpProblem::new("Solve time", LpObjective::Maximize);
+    problem += x;
+    problem += x.le(1);
+
+    let solution = MiniLpSolver::new().run(&problem).expect("could not solve");
+    let solve_time = solution.solve_time.expect("solve time not measured");
+    assert!(solve_time > std::time::Duration::from_secs(0));
+}
+
 #[test]
 fn decompose_large() {
     use dsl::lp_sum;
@@ -226,4 +740,120 @@ fn decompose_large() {
     let sum = lp_sum(&vars);
     let vars = decompose_expression(sum).expect("decompose failed");
     assert_eq!(vars.0.keys().len(), count);
-}
\ No newline at end of file
+}
+
+#[test]
+fn decompose_large_constraint() {
+    use dsl::{lp_sum, LpOperations};
+    let vars = LpContinuous::vec("x", 1000);
+    let constraint = lp_sum(&vars.iter().map(|v| 2 * v).collect()).le(1);
+
+    // a linear constraint is decomposed in a single pass over its borrowed arenas, without a copy
+    let (decomposed, constant) = decompose_linear_constraint(&constraint).expect("linear constraint");
+    assert_eq!(decomposed.0.len(), 1000);
+    assert!(decomposed.0.values().all(|var| var.coefficient == 2.0));
+    assert_eq!(constant, 1.0);
+    assert_eq!(decompose_constraint(&constraint).unwrap(), (decomposed, constant));
+
+    // only the other constraints are simplified on a copy
+    let product = (&vars[0] * &vars[1]).le(1);
+    assert!(decompose_linear_constraint(&product).is_none());
+    assert!(decompose_constraint(&product).is_err());
+}
+
+#[test]
+fn solve_large() {
+    use dsl::{lp_sum, BoundableLp};
+    use dsl::operations::LpOperations;
+    let count = 10_000;
+    let vars: Vec<LpContinuous> = (0..count)
+        .map(|i| LpContinuous::new(&format!("v{}", i)).lower_bound(0.).upper_bound(1.))
+        .collect();
+    let mut problem = LpProblem::new("Large", LpObjective::Maximize);
+    problem += lp_sum(&vars);
+    // blocks of 1000 variables summing up to at most 1
+    for block in vars.chunks(1000) {
+        problem += lp_sum(&block.to_vec()).le(1);
+    }
+    let (minilp_problem, variables) = problem_to_minilp(&problem, false, |_| false).unwrap();
+    assert_eq!(variables.len(), count);
+    assert!(variables.capacity() >= count);
+
+    let solution = minilp_problem.solve().unwrap();
+    assert!((solution.objective() - 10.).abs() < 1e-6);
+}
+
+#[test]
+fn test_fractional_integers() {
+    use dsl::operations::LpOperations;
+    use dsl::{LpBinary, LpInteger};
+    let x = &LpInteger::new("x");
+    let y = &LpInteger::new("y");
+    let b = &LpBinary::new("b");
+    let mut problem = LpProblem::new("Relaxation", LpObjective::Maximize);
+    problem += x + y + b;
+    problem += (2 * x).le(3);
+    problem += y.le(2);
+    problem += (2 * b).le(1);
+
+    assert!(MiniLpSolver::new().run(&problem).is_err());
+
+    let solution = MiniLpSolver::new().relax_integers().run(&problem).expect("could not solve");
+    assert_eq!(solution.results["x"], 1.5);
+    assert_eq!(solution.results["y"], 2.);
+    assert_eq!(solution.results["b"], 0.5);
+    assert_eq!(solution.fractional_integers(&problem), vec!["b", "x"]);
+}
+
+#[test]
+fn test_variables_on_both_sides() {
+    use dsl::BoundableLp;
+    let a = &LpContinuous::new("a");
+    let b = &LpContinuous::new("b").upper_bound(4.);
+    let mut problem = LpProblem::new("Both sides", LpObjective::Maximize);
+    problem += a;
+    // built without `le`, which would already move b to the left
+    problem += LpConstraint(a.into(), Constraint::LessOrEqual, b + 1);
+
+    let solution = MiniLpSolver::new().run(&problem).expect("could not solve");
+    assert_eq!(solution.results["a"], 5.);
+}
+
+#[test]
+fn test_non_finite_coefficients() {
+    use dsl::LpOperations;
+    let a = &LpContinuous::new("a");
+    let b = &LpContinuous::new("b");
+    let mut problem = LpProblem::new("NaN", LpObjective::Maximize);
+    problem += a + b;
+    problem += (f64::NAN * a + b).le(1);
+    assert_eq!(
+        MiniLpSolver::new().run(&problem).err().map(|e| e.to_string()),
+        Some("Variable a has a non-finite coefficient NaN".to_string())
+    );
+
+    let mut problem = LpProblem::new("Infinity", LpObjective::Maximize);
+    problem += f64::INFINITY * b;
+    problem += (a + b).le(1);
+    assert_eq!(
+        MiniLpSolver::new().run(&problem).err().map(|e| e.to_string()),
+        Some("Variable b has a non-finite coefficient inf".to_string())
+    );
+}
+
+#[test]
+fn test_fixed_variables() {
+    use dsl::{LpInteger, LpOperations};
+    let a = &LpContinuous::fixed("a", 3.);
+    let b = &LpInteger::fixed("b", 2.);
+    let c = &LpContinuous::new("c");
+    let mut problem = LpProblem::new("Fixed", LpObjective::Maximize);
+    problem += a + b + c;
+    problem += (a + c).le(10);
+    assert_eq!(problem.constraints.len(), 1);
+
+    let solution = MiniLpSolver::new().relax_integers().run(&problem).expect("could not solve");
+    assert_eq!(solution.results["a"], 3.);
+    assert_eq!(solution.results["b"], 2.);
+    assert_eq!(solution.results["c"], 7.);
+}
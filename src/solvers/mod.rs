@@ -7,37 +7,76 @@
 //! (note the name difference of the `native_coin_cbc` feature for the `coin_cbc` crate):
 //! ```toml
 //! [dependencies.lp_modeler]
-//! version = "4.3"
+//! version = "0.6.0"
 //! features = "native_coin_cbc"
 //! ```
 //! or:
 //! ```toml
 //! [dependencies.lp_modeler]
-//! version = "4.3"
+//! version = "0.6.0"
 //! features = "minilp"
 //! ```
 //! For `coin_cbc` to compile, the `Cbc` library files need to be available on your system.
 //! See the [`coin_cbc` project README](https://github.com/KardinalAI/coin_cbc) for more infos.
 //!
-//! The other solvers need to be installed externally on your system.
+//! The other solvers need to be installed externally on your system. They are enabled by the
+//! default `cbc`, `glpk`, `gurobi`, `scip` and `lpsolve` features, which can be turned off to slim
+//! the dependencies:
+//! ```toml
+//! [dependencies.lp_modeler]
+//! version = "0.6.0"
+//! default-features = false
+//! features = ["minilp"]
+//! ```
 //! The respective information is provided in the project's README in the section on
 //! [installing external solvers](https://github.com/jcavat/rust-lp-modeler#installing-external-solvers).
+//!
+//! The external solvers run as separate processes and exchange files with the modeler, so they
+//! are not available on `wasm32` targets. There, `minilp` is the only solver, and it does not
+//! touch the filesystem.
 
 use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use std::collections::BTreeMap;
 
-use dsl::{
-    LpBinary, LpContinuous, LpExprArenaIndex, LpExprNode, LpExprOp, LpInteger, LpProblem, Problem,
-};
+#[cfg(feature = "serde")]
+use serde::Serialize;
 
+use dsl::{LpBinary, LpContinuous, LpExpression, LpInteger, LpProblem, NamedVariable, Problem};
+use error::LpError;
+
+#[cfg(all(feature = "cbc", not(target_arch = "wasm32")))]
 pub mod cbc;
+#[cfg(all(feature = "cbc", not(target_arch = "wasm32")))]
 pub use self::cbc::*;
 
+#[cfg(all(feature = "gurobi", not(target_arch = "wasm32")))]
 pub mod gurobi;
+#[cfg(all(feature = "gurobi", not(target_arch = "wasm32")))]
 pub use self::gurobi::*;
 
+#[cfg(all(feature = "glpk", not(target_arch = "wasm32")))]
 pub mod glpk;
+#[cfg(all(feature = "glpk", not(target_arch = "wasm32")))]
 pub use self::glpk::*;
 
+#[cfg(all(feature = "scip", not(target_arch = "wasm32")))]
+pub mod scip;
+#[cfg(all(feature = "scip", not(target_arch = "wasm32")))]
+pub use self::scip::*;
+
+#[cfg(all(feature = "lpsolve", not(target_arch = "wasm32")))]
+pub mod lp_solve;
+#[cfg(all(feature = "lpsolve", not(target_arch = "wasm32")))]
+pub use self::lp_solve::*;
+
+pub mod sensitivity;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod first_of;
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::first_of::*;
+
 #[cfg(feature = "minilp")]
 pub mod minilp;
 #[cfg(feature = "minilp")]
@@ -48,24 +87,92 @@ pub mod native_cbc;
 #[cfg(feature = "native_coin_cbc")]
 pub use self::native_cbc::*;
 
+#[cfg(all(feature = "glpk-ffi", not(target_arch = "wasm32")))]
+pub mod glpk_ffi;
+#[cfg(all(feature = "glpk-ffi", not(target_arch = "wasm32")))]
+pub use self::glpk_ffi::*;
+
+#[cfg(not(target_arch = "wasm32"))]
 use std::fs;
+#[cfg(not(target_arch = "wasm32"))]
 use std::fs::File;
+use std::io;
+use std::io::Write;
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+use std::io::Read;
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+use std::process::{Child, Output};
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+use std::env;
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+use std::sync::atomic::Ordering;
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+use std::thread;
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+use std::time::Instant;
+use std::time::Duration;
 use util::is_zero;
+#[cfg(not(target_arch = "wasm32"))]
+use format::lp_format::sanitized_names;
+
+/// Default distance to an integer under which the value of an integer variable is rounded
+pub const DEFAULT_INTEGER_TOLERANCE: f64 = 1e-6;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum Status {
     Optimal,
     SubOptimal,
+    /// Stopped at the time limit, with a feasible incumbent solution
+    TimeLimit,
     Infeasible,
     Unbounded,
     NotSolved,
 }
 
+/// Sensitivity analysis of a single constraint or variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ranging {
+    pub status: String,
+    pub activity: f64,
+    /// Slack of a constraint, or objective coefficient of a variable
+    pub slack_or_cost: f64,
+    pub marginal: f64,
+    pub bounds: (f64, f64),
+    pub activity_range: (f64, f64),
+    pub objective_coefficient_range: (f64, f64),
+    /// Objective value at both ends of the activity range
+    pub objective_value_range: (f64, f64),
+    /// Variables or constraints limiting the activity range at both ends
+    pub limiting: (Option<String>, Option<String>),
+}
+
+/// Sensitivity analysis of a solution, by constraint and variable names
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sensitivity {
+    pub constraints: HashMap<String, Ranging>,
+    pub variables: HashMap<String, Ranging>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Solution<'a> {
     pub status: Status,
     pub results: HashMap<String, f64>,
     pub related_problem: Option<&'a LpProblem>,
+    pub sensitivity: Option<Sensitivity>,
+    /// Wall-clock time spent by the solver itself, excluding the writing of the model
+    pub solve_time: Option<Duration>,
+    /// Relative gap between the objective value and `objective_bound` (e.g. `0.01` for 1%),
+    /// for MIP solvers reporting it
+    pub mip_gap: Option<f64>,
+    /// Best bound on the objective value proven by a MIP solver
+    pub objective_bound: Option<f64>,
+    /// Objective value reported by the solver, for solvers reporting it.
+    /// `objective_value` computes it from the values of the variables instead.
+    pub objective: Option<f64>,
 }
 impl Solution<'_> {
     pub fn new<'a>(status: Status, results: HashMap<String, f64>) -> Solution<'a> {
@@ -73,6 +180,11 @@ impl Solution<'_> {
             status,
             results,
             related_problem: None,
+            sensitivity: None,
+            solve_time: None,
+            mip_gap: None,
+            objective_bound: None,
+            objective: None,
         }
     }
     pub fn with_problem(
@@ -84,8 +196,53 @@ impl Solution<'_> {
             status,
             results,
             related_problem: Some(problem),
+            sensitivity: None,
+            solve_time: None,
+            mip_gap: None,
+            objective_bound: None,
+            objective: None,
         }
     }
+    /// Same solution, related to `problem`
+    pub fn with_related_problem(self, problem: &LpProblem) -> Solution<'_> {
+        Solution {
+            status: self.status,
+            results: self.results,
+            related_problem: Some(problem),
+            sensitivity: self.sensitivity,
+            solve_time: self.solve_time,
+            mip_gap: self.mip_gap,
+            objective_bound: self.objective_bound,
+            objective: self.objective,
+        }
+    }
+    /// Combine the solutions of the components of `problem`, as split by
+    /// `LpProblem::connected_components`, into a solution of the whole problem.
+    ///
+    /// The status is the worst status of the components, and the solve time their total.
+    pub fn combine<'a>(problem: &'a LpProblem, solutions: Vec<Solution>) -> Solution<'a> {
+        fn rank(status: &Status) -> u8 {
+            match status {
+                Status::Optimal => 0,
+                Status::SubOptimal => 1,
+                Status::TimeLimit => 2,
+                Status::NotSolved => 3,
+                Status::Unbounded => 4,
+                Status::Infeasible => 5,
+            }
+        }
+        let mut combined = Solution::with_problem(Status::Optimal, HashMap::new(), problem);
+        for solution in solutions {
+            if rank(&solution.status) > rank(&combined.status) {
+                combined.status = solution.status;
+            }
+            combined.results.extend(solution.results);
+            if let Some(time) = solution.solve_time {
+                combined.solve_time = Some(combined.solve_time.unwrap_or_default() + time);
+            }
+        }
+        combined
+    }
     fn check_possible_solution(&self) {
         match &self.status {
             Status::Unbounded | Status::NotSolved | Status::Infeasible => {
@@ -128,66 +285,490 @@ impl Solution<'_> {
         );
         i
     }
+    pub fn get_metadata(&self, key: &str) -> Option<&str> {
+        self.related_problem.and_then(|problem| problem.get_metadata(key))
+    }
+    /// Value of the objective of the related problem (including its constant term), the variables
+    /// without a value counting as zero
     pub fn eval(&self) -> Option<f64> {
         self.related_problem.and_then(|problem| match &problem.obj_expr_arena {
-            Some(obj_expr_arena) => {
-                Some(self.eval_with(&obj_expr_arena.get_root_index(), &self.results))
-            }
+            Some(obj_expr_arena) => obj_expr_arena
+                .evaluate(&|name| Ok(*self.results.get(name).unwrap_or(&0f64)))
+                .ok()
+                .map(|value| value + problem.objective_constant),
             None => None,
         })
     }
-    fn eval_with(&self, index: &LpExprArenaIndex, values: &HashMap<String, f64>) -> f64 {
-        match self
-            .related_problem
-            .unwrap()
-            .obj_expr_arena
-            .as_ref()
-            .unwrap()
-            .expr_ref_at(*index)
-        {
-            LpExprNode::LpCompExpr(operation, left, right) => match operation {
-                LpExprOp::Addition => self.eval_with(left, values) + self.eval_with(right, values),
-                LpExprOp::Multiplication => {
-                    self.eval_with(left, values) * self.eval_with(right, values)
-                }
-                LpExprOp::Subtraction => {
-                    self.eval_with(left, values) - self.eval_with(right, values)
+    /// Value of a variable of the problem, `None` if the solution has no value for it
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::solvers::{Solution, Status};
+    ///
+    /// let ref a = LpInteger::new("a");
+    /// let mut results = HashMap::new();
+    /// results.insert("a".to_string(), 3.0);
+    /// let solution = Solution::new(Status::Optimal, results);
+    /// assert_eq!(solution.value(a), Some(3.0));
+    /// assert_eq!(solution.value(&LpBinary::new("b")), None);
+    /// ```
+    pub fn value<V: NamedVariable>(&self, var: &V) -> Option<f64> {
+        self.results.get(var.name()).cloned()
+    }
+    /// Value of `expr` at this solution, failing if a variable of `expr` has no value
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::solvers::{Solution, Status};
+    ///
+    /// let ref a = LpInteger::new("a");
+    /// let ref b = LpInteger::new("b");
+    /// let mut results = HashMap::new();
+    /// results.insert("a".to_string(), 3.0);
+    /// results.insert("b".to_string(), 1.0);
+    /// let solution = Solution::new(Status::Optimal, results);
+    /// assert_eq!(solution.evaluate(&(a + b)).unwrap(), 4.0);
+    /// ```
+    pub fn evaluate(&self, expr: &LpExpression) -> Result<f64, LpError> {
+        expr.evaluate(&|name| {
+            self.results
+                .get(name)
+                .cloned()
+                .ok_or_else(|| LpError::MissingValue(name.to_string()))
+        })
+    }
+    /// Value of the objective of `problem` (including its constant term) at this solution
+    pub fn objective_value(&self, problem: &LpProblem) -> Result<f64, LpError> {
+        let value = match &problem.obj_expr_arena {
+            Some(obj_expr_arena) => self.evaluate(obj_expr_arena)?,
+            None => 0.0,
+        };
+        Ok(value + problem.objective_constant)
+    }
+    /// Names of the integer and binary variables of `problem` with a fractional value,
+    /// e.g. in the solution of a linear relaxation
+    pub fn fractional_integers(&self, problem: &LpProblem) -> Vec<String> {
+        problem
+            .integer_variable_names()
+            .into_iter()
+            .filter(|name| match self.results.get(name) {
+                Some(value) => !is_zero(value - value.round()),
+                None => false,
+            })
+            .collect()
+    }
+    /// Round the values of the integer and binary variables of the related problem lying within
+    /// `epsilon` of an integer, removing the noise left by the solvers. Other values are untouched,
+    /// and listed by `fractional_integers`.
+    pub fn round_integers(&mut self, epsilon: f64) {
+        if let Some(problem) = self.related_problem {
+            for name in problem.integer_variable_names() {
+                if let Some(value) = self.results.get_mut(&name) {
+                    if (*value - value.round()).abs() <= epsilon {
+                        *value = value.round();
+                    }
                 }
-            },
-            LpExprNode::ConsBin(LpBinary { name })
-            | LpExprNode::ConsCont(LpContinuous { name, .. })
-            | LpExprNode::ConsInt(LpInteger { name, .. }) => *values.get(name).unwrap_or(&0f64),
-            LpExprNode::LitVal(n) => *n,
-            LpExprNode::EmptyExpr => 0.0,
+            }
+        }
+    }
+    /// Give the variables declared on `problem` but absent from its model the value closest to
+    /// zero within their bounds, as the solvers never see them
+    pub(crate) fn add_unused_declared(&mut self, problem: &LpProblem) {
+        if let Status::Optimal | Status::SubOptimal | Status::TimeLimit = self.status {
+            for (name, value) in problem.unused_declared_values() {
+                self.results.entry(name).or_insert(value);
+            }
+        }
+    }
+    /// Sum of the absolute values of the variables
+    pub fn l1_norm(&self) -> f64 {
+        self.results.values().map(|v| v.abs()).sum()
+    }
+    /// Euclidean norm of the vector of the variable values
+    pub fn l2_norm(&self) -> f64 {
+        self.results.values().map(|v| v * v).sum::<f64>().sqrt()
+    }
+    /// Write the solution in the Gurobi solution (`.sol`) format, readable by `GurobiSolver`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_gurobi_format(&self, path: &str) -> io::Result<()> {
+        let mut buffer = File::create(path)?;
+        match self.related_problem {
+            Some(problem) => writeln!(buffer, "# Solution for model {}", problem.name)?,
+            None => writeln!(buffer, "# Solution")?,
+        }
+        if let Some(objective) = self.eval() {
+            writeln!(buffer, "# Objective value = {}", objective)?;
         }
+        let mut results: Vec<_> = self.results.iter().collect();
+        results.sort_by_key(|(name, _)| *name);
+        for (name, value) in results {
+            writeln!(buffer, "{} {}", name, value)?;
+        }
+        Ok(())
+    }
+    /// Write the values of the variables as a `variable,value` CSV, sorted by name.
+    /// The values of the integer and binary variables of the related problem are written
+    /// without a decimal fraction.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::solvers::{Solution, Status};
+    ///
+    /// let mut results = HashMap::new();
+    /// results.insert("b".to_string(), 2.5);
+    /// results.insert("a".to_string(), 1.0);
+    /// let solution = Solution::new(Status::Optimal, results);
+    ///
+    /// let mut csv = Vec::new();
+    /// solution.to_csv(&mut csv).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "variable,value\na,1\nb,2.5\n");
+    /// ```
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        fn escaped(field: &str) -> String {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        }
+        let integers = self.related_problem.map(|problem| problem.integer_variable_names()).unwrap_or_default();
+        let mut results: Vec<_> = self.results.iter().collect();
+        results.sort_by_key(|(name, _)| *name);
+        writeln!(writer, "variable,value")?;
+        for (name, value) in results {
+            if integers.binary_search(name).is_ok() {
+                // `+ 0.0` writes -0 as 0
+                writeln!(writer, "{},{}", escaped(name), value.round() + 0.0)?;
+            } else {
+                writeln!(writer, "{},{}", escaped(name), value)?;
+            }
+        }
+        Ok(())
+    }
+    /// Status, objective value and values of the variables as a JSON object, with sorted keys.
+    /// The objective is the one reported by the solver, or else computed for the related
+    /// problem, and `null` if neither is known.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lp_modeler::solvers::{Solution, Status};
+    ///
+    /// let mut results = HashMap::new();
+    /// results.insert("b".to_string(), 2.5);
+    /// results.insert("a".to_string(), 1.0);
+    /// let solution = Solution::new(Status::Optimal, results);
+    /// assert_eq!(
+    ///     solution.to_json(),
+    ///     r#"{"objective":null,"status":"Optimal","variables":{"a":1.0,"b":2.5}}"#
+    /// );
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        let objective = self
+            .objective
+            .or_else(|| self.related_problem.and_then(|problem| self.objective_value(problem).ok()));
+        let variables: BTreeMap<&String, &f64> = self.results.iter().collect();
+        serde_json::json!({
+            "status": self.status,
+            "objective": objective,
+            "variables": variables,
+        })
+        .to_string()
     }
 }
 
 pub trait SolverTrait {
     type P: Problem;
-    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String>;
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError>;
+    /// Same as `run`, but gives up as soon as `cancel` is set.
+    /// Solvers that cannot be interrupted ignore the flag and run to completion.
+    fn run_cancellable<'a>(
+        &self,
+        problem: &'a Self::P,
+        _cancel: &AtomicBool,
+    ) -> Result<Solution<'a>, LpError> {
+        self.run(problem)
+    }
 }
 
+/// Irreducible infeasible set of an infeasible problem: names of constraints (as in the LP file)
+/// which are infeasible together, but feasible as soon as any of them is removed.
+///
+/// Computed with a deletion filter, solving the problem once per constraint with `solver`,
+/// so it works with any solver able to detect infeasibility. Variable bounds are kept.
+/// Fails if the problem is not infeasible, or if the solver neither finds a solution nor proves
+/// the infeasibility of a subset of the constraints, e.g. with the status `NotSolved`.
+///
+/// # Examples:
+///
+/// ```
+/// # #[cfg(feature = "glpk")]
+/// # fn main() {
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::solvers::{compute_iis, GlpkSolver};
+///
+/// let ref x = LpContinuous::new("x");
+/// let mut problem = LpProblem::new("Infeasible", LpObjective::Maximize);
+/// problem += x;
+/// problem += x.ge(3);
+/// problem += x.le(2);
+///
+/// if let Ok(iis) = compute_iis(&GlpkSolver::new(), &problem) {
+///     println!("Conflicting constraints: {}", iis.join(", "));
+/// }
+/// # }
+/// # #[cfg(not(feature = "glpk"))]
+/// # fn main() {}
+/// ```
+pub fn compute_iis<S: SolverTrait<P = LpProblem>>(solver: &S, problem: &LpProblem) -> Result<Vec<String>, LpError> {
+    let infeasible = |constraints: &[usize]| -> Result<bool, LpError> {
+        let mut stage = problem.clone();
+        stage.constraints = constraints.iter().map(|&index| problem.constraints[index].clone()).collect();
+        match solver.run(&stage)?.status {
+            Status::Infeasible => Ok(true),
+            // an unbounded problem, or one stopped with a feasible incumbent, is feasible
+            Status::Optimal | Status::SubOptimal | Status::TimeLimit | Status::Unbounded => Ok(false),
+            status => Err(LpError::Solver(format!(
+                "Cannot tell whether {} constraints of {} are feasible, the solver ended with the status {:?}",
+                constraints.len(),
+                problem.name,
+                status
+            ))),
+        }
+    };
+    let mut kept: Vec<usize> = (0..problem.constraints.len()).collect();
+    if !infeasible(&kept)? {
+        return Err(LpError::Solver(format!("The problem {} is not infeasible", problem.name)));
+    }
+    let mut position = 0;
+    while position < kept.len() {
+        let index = kept.remove(position);
+        if !infeasible(&kept)? {
+            // needed for the infeasibility
+            kept.insert(position, index);
+            position += 1;
+        }
+    }
+    Ok(kept.into_iter().map(|index| format!("c{}", index + 1)).collect())
+}
+
+/// Solve `problem` with `run` once its semi-continuous variables have been lowered by
+/// `LpProblem::lower_semi_continuous`, for the solvers without native support.
+/// The indicator variables and the constraints added by the lowering are left out of the solution.
+pub(crate) fn run_lowered<'a, F>(problem: &'a LpProblem, run: F) -> Result<Solution<'a>, LpError>
+where
+    F: for<'b> FnOnce(&'b LpProblem) -> Result<Solution<'b>, LpError>,
+{
+    let lowered = problem.lower_semi_continuous()?;
+    let mut solution = run(&lowered)?.with_related_problem(problem);
+    for (_, indicator) in problem.semi_continuous_indicators() {
+        solution.results.remove(&indicator.name);
+        if let Some(sensitivity) = solution.sensitivity.as_mut() {
+            sensitivity.variables.remove(&indicator.name);
+        }
+    }
+    if let Some(sensitivity) = solution.sensitivity.as_mut() {
+        for index in problem.constraints.len()..lowered.constraints.len() {
+            sensitivity.constraints.remove(&format!("c{}", index + 1));
+        }
+    }
+    Ok(solution)
+}
+
+/// Whether `command` is an executable file, given by its path or found in the `PATH`
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+pub(crate) fn command_available(command: &str) -> bool {
+    fn is_executable(path: &Path) -> bool {
+        match path.metadata() {
+            #[cfg(unix)]
+            Ok(metadata) => {
+                use std::os::unix::fs::PermissionsExt;
+                metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+            }
+            #[cfg(not(unix))]
+            Ok(metadata) => metadata.is_file(),
+            Err(_) => false,
+        }
+    }
+    let path = Path::new(command);
+    if path.components().count() > 1 {
+        return is_executable(path);
+    }
+    match env::var_os("PATH") {
+        Some(paths) => env::split_paths(&paths).any(|dir| {
+            let candidate = dir.join(command);
+            is_executable(&candidate) || (cfg!(windows) && is_executable(&candidate.with_extension("exe")))
+        }),
+        None => false,
+    }
+}
+
+/// Time given to a solver past its own time limit to write its solution, before it is killed
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+const TIME_LIMIT_GRACE: Duration = Duration::from_secs(2);
+
+/// Spawned solver process, killed when dropped before it finished,
+/// e.g. when the thread waiting for it panics
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+struct ChildGuard(Child);
+
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        if let Ok(None) = self.0.try_wait() {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+}
+
+/// Wait for a spawned solver process to finish while polling `cancel`.
+/// Returns `Ok(None)` if the process was killed because `cancel` was set.
+///
+/// With a `time_limit`, the process is killed if it is still running `TIME_LIMIT_GRACE` after
+/// it, which is reported as an error of kind `TimedOut`.
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+pub(crate) fn wait_cancellable(child: Child, cancel: &AtomicBool, time_limit: Option<Duration>) -> io::Result<Option<Output>> {
+    // Drain the pipes in the background so that a verbose solver cannot block on a full pipe
+    fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> Option<thread::JoinHandle<Vec<u8>>> {
+        pipe.map(|mut pipe| {
+            thread::spawn(move || {
+                let mut buffer = Vec::new();
+                let _ = pipe.read_to_end(&mut buffer);
+                buffer
+            })
+        })
+    }
+    fn collect(handle: Option<thread::JoinHandle<Vec<u8>>>) -> Vec<u8> {
+        handle.and_then(|h| h.join().ok()).unwrap_or_default()
+    }
+    let start = Instant::now();
+    let mut child = ChildGuard(child);
+    let stdout = drain(child.0.stdout.take());
+    let stderr = drain(child.0.stderr.take());
+    loop {
+        if let Some(status) = child.0.try_wait()? {
+            return Ok(Some(Output {
+                status,
+                stdout: collect(stdout),
+                stderr: collect(stderr),
+            }));
+        }
+        if cancel.load(Ordering::SeqCst) {
+            let _ = child.0.kill();
+            let _ = child.0.wait();
+            return Ok(None);
+        }
+        if let Some(limit) = time_limit {
+            if start.elapsed() > limit + TIME_LIMIT_GRACE {
+                let _ = child.0.kill();
+                let _ = child.0.wait();
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "time limit exceeded"));
+            }
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Error of a solver process that could not be run to its end: killed after exceeding its time
+/// limit, or that could not be started
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+pub(crate) fn process_error(name: &str, error: io::Error) -> LpError {
+    if error.kind() == io::ErrorKind::TimedOut {
+        LpError::Solver(format!("The {} solver has been killed after exceeding its time limit", name))
+    } else {
+        LpError::SolverNotFound(name.to_string())
+    }
+}
+
+/// Path of the temporary file `file_name` in the working directory of a solver.
+/// An absolute `file_name` is kept as is.
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+pub(crate) fn work_file(work_dir: &Path, file_name: &str) -> String {
+    work_dir.join(file_name).to_string_lossy().into_owned()
+}
+
+/// Path of the solution file of the run writing the model `file_model`: `temp_solution_file`
+/// in the working directory if one was set, or else the model file with the `.sol` extension,
+/// so that runs sharing a solver do not share a solution file
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+pub(crate) fn solution_file(work_dir: &Path, temp_solution_file: Option<&String>, file_model: &str) -> String {
+    match temp_solution_file {
+        Some(file_name) => work_file(work_dir, file_name),
+        None => Path::new(file_model).with_extension("sol").to_string_lossy().into_owned(),
+    }
+}
+
+/// Stem of the files written for a single run on `problem`, e.g. the model `{stem}.lp`.
+/// It differs between runs, so that solvers racing on the same problem in `FirstOf`
+/// do not overwrite or remove the files of each other.
+#[cfg(all(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"), not(target_arch = "wasm32")))]
+pub(crate) fn run_file_stem(problem: &LpProblem) -> String {
+    format!("{}_{}", problem.unique_name, ::uuid::Uuid::new_v4())
+}
+
+/// Rename the values of the variables written under their sanitized name in `names`,
+/// see `sanitized_names`
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn restore_names<V>(values: &mut HashMap<String, V>, names: &HashMap<String, String>) {
+    for (name, sanitized) in names {
+        if let Some(value) = values.remove(sanitized) {
+            values.insert(name.clone(), value);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 pub trait SolverWithSolutionParsing {
     fn read_solution<'a>(
         &self,
         temp_solution_file: &String,
         problem: Option<&'a LpProblem>,
-    ) -> Result<Solution<'a>, String> {
+    ) -> Result<Solution<'a>, LpError> {
+        let names = problem.map(sanitized_names).unwrap_or_default();
+        self.read_renamed_solution(temp_solution_file, problem, &names)
+    }
+    /// `read_solution` for a problem written with the variable names `names`, see `sanitized_names`
+    fn read_renamed_solution<'a>(
+        &self,
+        temp_solution_file: &String,
+        problem: Option<&'a LpProblem>,
+        names: &HashMap<String, String>,
+    ) -> Result<Solution<'a>, LpError> {
         match File::open(temp_solution_file) {
             Ok(f) => {
-                let res = self.read_specific_solution(&f, problem)?;
+                let mut res = self.read_specific_solution(&f, problem)?;
                 let _ = fs::remove_file(temp_solution_file);
+                restore_names(&mut res.results, names);
+                if let Some(problem) = problem {
+                    res.add_unused_declared(problem);
+                }
+                res.round_integers(self.integer_tolerance());
                 Ok(res)
             }
-            Err(_) => return Err("Cannot open file".to_string()),
+            Err(e) => return Err(io::Error::new(e.kind(), "Cannot open file").into()),
         }
     }
     fn read_specific_solution<'a>(
         &self,
         f: &File,
         problem: Option<&'a LpProblem>,
-    ) -> Result<Solution<'a>, String>;
+    ) -> Result<Solution<'a>, LpError>;
+    /// Values of integer variables within this distance of an integer are rounded by `read_solution`
+    fn integer_tolerance(&self) -> f64 {
+        DEFAULT_INTEGER_TOLERANCE
+    }
 }
 
 pub trait WithMaxSeconds<T> {
@@ -199,3 +780,22 @@ pub trait WithNbThreads<T> {
     fn nb_threads(&self) -> Option<u32>;
     fn with_nb_threads(&self, threads: u32) -> T;
 }
+
+/// Solvers accepting an initial (possibly partial) assignment of the variables,
+/// used to seed the branch-and-bound of a MIP.
+pub trait WithWarmStart<T> {
+    fn warm_start(&self) -> Option<&HashMap<String, f64>>;
+    fn with_warm_start(&self, values: HashMap<String, f64>) -> T;
+}
+
+/// Warm start values with the variable names written in the LP file, sanitized as in `names`,
+/// sorted by name so that start files are reproducible
+#[cfg(all(any(feature = "cbc", feature = "gurobi"), not(target_arch = "wasm32")))]
+pub(crate) fn sorted_warm_start<'a>(
+    values: &'a HashMap<String, f64>,
+    names: &'a HashMap<String, String>,
+) -> Vec<(&'a String, &'a f64)> {
+    let mut values: Vec<_> = values.iter().map(|(name, value)| (names.get(name).unwrap_or(name), value)).collect();
+    values.sort_by_key(|(name, _)| *name);
+    values
+}
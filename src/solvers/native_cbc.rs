@@ -1,10 +1,11 @@
-extern crate uuid;
 use coin_cbc;
 
 use dsl::LpExprNode::*;
 use dsl::*;
-use solvers::{Solution, SolverTrait, Status, WithMaxSeconds, WithNbThreads};
+use error::LpError;
+use solvers::{Solution, SolverTrait, Status, WithMaxSeconds, WithNbThreads, run_lowered};
 use std::collections::HashMap;
+use std::time::Instant;
 
 /// Solver that calls cbc through [rust bindings](https://github.com/KardinalAI/coin_cbc)
 #[derive(Debug, Clone, Default)]
@@ -141,7 +142,16 @@ fn add_variable(m: &mut coin_cbc::Model, expr: &LpExprNode) -> coin_cbc::Col {
 impl SolverTrait for NativeCbcSolver {
     type P = LpProblem;
 
-    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+        problem.check_not_empty()?;
+        if !problem.semi_continuous_variables().is_empty() {
+            return run_lowered(problem, |lowered| self.run(lowered));
+        }
+        if !problem.sos2.is_empty() {
+            return Err(LpError::Unsupported(
+                "Special ordered sets are not supported by the native Cbc solver".to_string(),
+            ));
+        }
         let mut m = coin_cbc::Model::default();
         // columns (variables)
         let mut cols: HashMap<String, coin_cbc::Col> = HashMap::new();
@@ -179,9 +189,11 @@ impl SolverTrait for NativeCbcSolver {
             LpObjective::Minimize => coin_cbc::Sense::Minimize,
         });
 
+        let start = Instant::now();
         let sol = m.solve();
+        let solve_time = start.elapsed();
 
-        Ok(Solution {
+        let mut solution = Solution {
             status: match sol.raw().status() {
                 coin_cbc::raw::Status::Finished => Status::Optimal,
                 coin_cbc::raw::Status::Abandoned => Status::Infeasible,
@@ -192,6 +204,13 @@ impl SolverTrait for NativeCbcSolver {
                 .map(|(name, col)| (name.to_owned(), sol.col(*col) as f64))
                 .collect(),
             related_problem: Some(problem),
-        })
+            sensitivity: None,
+            solve_time: Some(solve_time),
+            mip_gap: None,
+            objective_bound: None,
+            objective: None,
+        };
+        solution.add_unused_declared(problem);
+        Ok(solution)
     }
 }
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::fs;
+use std::env;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+use dsl::LpProblem;
+use error::LpError;
+use format::lp_format::*;
+use solvers::{DEFAULT_INTEGER_TOLERANCE, Solution, SolverTrait, SolverWithSolutionParsing, Status, WithMaxSeconds, command_available, process_error, wait_cancellable, work_file, solution_file, run_file_stem};
+
+#[derive(Debug, Clone)]
+pub struct ScipSolver {
+    name: String,
+    command_name: String,
+    temp_solution_file: Option<String>,
+    work_dir: PathBuf,
+    seconds: Option<u32>,
+    integer_tolerance: f64,
+}
+
+impl ScipSolver {
+    pub fn new() -> ScipSolver {
+        ScipSolver {
+            name: "Scip".to_string(),
+            command_name: "scip".to_string(),
+            temp_solution_file: None,
+            work_dir: env::temp_dir(),
+            seconds: None,
+            integer_tolerance: DEFAULT_INTEGER_TOLERANCE,
+        }
+    }
+    pub fn command_name(&self, command_name: String) -> ScipSolver {
+        ScipSolver {
+            command_name,
+            ..self.clone()
+        }
+    }
+    pub fn with_temp_solution_file(&self, temp_solution_file: String) -> ScipSolver {
+        ScipSolver {
+            temp_solution_file: Some(temp_solution_file),
+            ..self.clone()
+        }
+    }
+    /// Create the model and solution files in `work_dir`, the temporary directory of the system
+    /// by default
+    pub fn with_work_dir<P: Into<PathBuf>>(&self, work_dir: P) -> ScipSolver {
+        ScipSolver {
+            work_dir: work_dir.into(),
+            ..self.clone()
+        }
+    }
+    /// Round the values of integer variables within `epsilon` of an integer,
+    /// `DEFAULT_INTEGER_TOLERANCE` by default
+    pub fn with_integer_tolerance(&self, epsilon: f64) -> ScipSolver {
+        ScipSolver {
+            integer_tolerance: epsilon,
+            ..self.clone()
+        }
+    }
+
+    /// Arguments given to scip to solve `file_model`: the commands of its interactive shell,
+    /// run in batch mode. The paths are quoted, as the shell splits its commands on spaces.
+    pub fn arguments(&self, file_model: &str) -> Vec<String> {
+        let mut commands = vec![format!("read \"{}\"", file_model)];
+        if let Some(seconds) = self.seconds {
+            commands.push(format!("set limits time {}", seconds));
+        }
+        commands.push("optimize".to_string());
+        commands.push(format!("write solution \"{}\"", solution_file(&self.work_dir, self.temp_solution_file.as_ref(), file_model)));
+        commands.push("quit".to_string());
+        vec!["-c".to_string(), commands.join(" ")]
+    }
+
+    /// Whether the solver executable can be found, checked by `run` before writing any file
+    pub fn is_available(&self) -> bool {
+        command_available(&self.command_name)
+    }
+}
+
+impl Default for ScipSolver {
+    fn default() -> ScipSolver {
+        ScipSolver::new()
+    }
+}
+
+/// Status from the `solution status:` line of a solution written by scip.
+/// `solution_available` tells whether the file holds a solution, when scip stopped at a limit.
+pub fn parse_scip_status(status: &str, solution_available: bool) -> Status {
+    match status.trim() {
+        "optimal solution found" => Status::Optimal,
+        "infeasible" => Status::Infeasible,
+        "unbounded" => Status::Unbounded,
+        "time limit reached" if solution_available => Status::TimeLimit,
+        _ if solution_available => Status::SubOptimal,
+        _ => Status::NotSolved,
+    }
+}
+
+impl WithMaxSeconds<ScipSolver> for ScipSolver {
+    fn max_seconds(&self) -> Option<u32> {
+        self.seconds
+    }
+    fn with_max_seconds(&self, seconds: u32) -> ScipSolver {
+        ScipSolver {
+            seconds: Some(seconds),
+            ..self.clone()
+        }
+    }
+}
+
+impl SolverWithSolutionParsing for ScipSolver {
+    fn integer_tolerance(&self) -> f64 {
+        self.integer_tolerance
+    }
+    fn read_specific_solution<'a>(&self, f: &File, problem: Option<&'a LpProblem>) -> Result<Solution<'a>, LpError> {
+        let mut lines = BufReader::new(f).lines();
+        let status = match lines.next() {
+            Some(Ok(line)) if line.starts_with("solution status:") => line["solution status:".len()..].to_string(),
+            _ => {
+                return Err(LpError::ParseSolution(
+                    "Incorrect solution format: No solution status found".to_string(),
+                ))
+            }
+        };
+
+        let mut vars_value: HashMap<_, _> = HashMap::new();
+        let mut solution_available = false;
+        for line in lines {
+            let line = line?;
+            if line.starts_with("objective value:") {
+                solution_available = true;
+                continue;
+            } else if line.trim().is_empty() || line.starts_with("no solution available") {
+                continue;
+            }
+            // Each line holds a name, a value and the objective coefficient, e.g. `x  1  (obj:5)`
+            let result_line: Vec<_> = line.split_whitespace().collect();
+            if result_line.len() < 2 {
+                return Err(LpError::ParseSolution(
+                    "Incorrect solution format: Variable line has too few fields".to_string(),
+                ));
+            }
+            match result_line[1].parse::<f64>() {
+                Ok(n) => {
+                    vars_value.insert(result_line[0].to_string(), n);
+                }
+                Err(e) => return Err(LpError::ParseSolution(e.to_string())),
+            }
+        }
+        let status = parse_scip_status(&status, solution_available);
+
+        if let Some(p) = problem {
+            // scip only writes the variables with a nonzero value
+            // under their original names, replaced by the values restored from the sanitized names
+            if solution_available {
+                for name in p.variable_nodes().into_keys() {
+                    vars_value.entry(name).or_insert(0.0);
+                }
+            }
+            Ok(Solution::with_problem(status, vars_value, p))
+        } else {
+            Ok(Solution::new(status, vars_value))
+        }
+    }
+}
+
+impl SolverTrait for ScipSolver {
+    type P = LpProblem;
+    fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, LpError> {
+        self.run_cancellable(problem, &AtomicBool::new(false))
+    }
+
+    fn run_cancellable<'a>(&self, problem: &'a Self::P, cancel: &AtomicBool) -> Result<Solution<'a>, LpError> {
+        problem.check_not_empty()?;
+        if !self.is_available() {
+            return Err(LpError::SolverNotFound(self.name.clone()));
+        }
+        let file_model = &work_file(&self.work_dir, &format!("{}.lp", run_file_stem(problem)));
+        let solution_file = &solution_file(&self.work_dir, self.temp_solution_file.as_ref(), file_model);
+        let names = sanitized_names(problem);
+        write_renamed_lp(problem, &names, file_model)?;
+
+        let start = Instant::now();
+        let output = Command::new(&self.command_name)
+            .args(self.arguments(file_model))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .and_then(|child| wait_cancellable(child, cancel, self.seconds.map(|s| Duration::from_secs(s.into()))));
+        let solve_time = start.elapsed();
+        let result = match output {
+            Ok(Some(r)) => {
+                if r.status.success() {
+                    self.read_renamed_solution(solution_file, Some(problem), &names).map(|solution| Solution {
+                        solve_time: Some(solve_time),
+                        ..solution
+                    })
+                } else {
+                    Err(LpError::Solver(format!(
+                        "{} exited with {}\n\nSTDOUT:\n{}\n\nSTDERR:\n{}\n\n",
+                        self.command_name,
+                        r.status,
+                        String::from_utf8_lossy(&r.stdout),
+                        String::from_utf8_lossy(&r.stderr),
+                    )))
+                }
+            }
+            Ok(None) => {
+                let _ = fs::remove_file(solution_file);
+                Err(LpError::Cancelled(Some(self.name.clone())))
+            }
+            Err(e) => {
+                let _ = fs::remove_file(solution_file);
+                Err(process_error(&self.name, e))
+            }
+        };
+        let _ = fs::remove_file(file_model);
+
+        result
+    }
+}
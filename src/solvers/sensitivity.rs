@@ -0,0 +1,424 @@
+//! Sensitivity analysis of an optimal solution of a linear problem, for the solvers that do not
+//! report it, such as `MiniLpSolver`.
+//!
+//! The optimal basis is rebuilt from the values of the variables in `to_standard_form`, with a
+//! slack variable per constraint: the variables strictly between their bounds are basic. In a
+//! degenerate solution, the basis is completed with the slack variables first, then with the
+//! variables at a bound. The analysis fails if the rebuilt basis is not optimal.
+
+use dsl::{Constraint, LpObjective, LpProblem};
+use error::LpError;
+use solvers::{Ranging, Sensitivity, Solution, Status};
+
+/// Tolerance on the distance of a value to a bound and on the signs of the reduced costs
+const TOLERANCE: f64 = 1e-7;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnStatus {
+    Basic,
+    AtLower,
+    AtUpper,
+    Fixed,
+}
+
+impl ColumnStatus {
+    /// Name of the status, as in the sensitivity report of GLPK
+    fn name(self) -> &'static str {
+        match self {
+            ColumnStatus::Basic => "BS",
+            ColumnStatus::AtLower => "NL",
+            ColumnStatus::AtUpper => "NU",
+            ColumnStatus::Fixed => "NS",
+        }
+    }
+}
+
+/// Optimal basis of a linear problem, with the objective written for minimization.
+/// The columns are the variables of the standard form, followed by the slack variable `s_i` of
+/// every constraint `a_i x + s_i = b_i`.
+struct Basis {
+    names: Vec<String>,
+    nb_variables: usize,
+    /// 1 to minimize, -1 to maximize
+    sign: f64,
+    objective: f64,
+    /// Right-hand side of every constraint
+    rhs: Vec<f64>,
+    costs: Vec<f64>,
+    columns: Vec<Vec<f64>>,
+    values: Vec<f64>,
+    bounds: Vec<(f64, f64)>,
+    statuses: Vec<ColumnStatus>,
+    /// Column of every position of the basis
+    basic: Vec<usize>,
+    /// Inverse of the basis matrix, by position of the basis
+    inverse: Vec<Vec<f64>>,
+    reduced_costs: Vec<f64>,
+}
+
+fn near(value: f64, bound: f64) -> bool {
+    bound.is_finite() && (value - bound).abs() <= TOLERANCE * bound.abs().max(1.0)
+}
+
+fn dot(left: &[f64], right: &[f64]) -> f64 {
+    left.iter().zip(right).map(|(l, r)| l * r).sum()
+}
+
+/// Inverse of the square matrix given by its `columns`, `None` if it is singular
+fn inverse(columns: &[&Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let size = columns.len();
+    // rows of the matrix augmented with the identity
+    let mut rows: Vec<Vec<f64>> = (0..size)
+        .map(|i| {
+            let mut row: Vec<f64> = columns.iter().map(|column| column[i]).collect();
+            row.extend((0..size).map(|j| if i == j { 1.0 } else { 0.0 }));
+            row
+        })
+        .collect();
+    for pivot in 0..size {
+        let best = (pivot..size).max_by(|&i, &j| rows[i][pivot].abs().total_cmp(&rows[j][pivot].abs()))?;
+        if rows[best][pivot].abs() <= TOLERANCE {
+            return None;
+        }
+        rows.swap(pivot, best);
+        let scale = rows[pivot][pivot];
+        rows[pivot].iter_mut().for_each(|value| *value /= scale);
+        let pivot_row = rows[pivot].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            let factor = row[pivot];
+            if i != pivot && factor != 0.0 {
+                row.iter_mut().zip(&pivot_row).for_each(|(value, p)| *value -= factor * p);
+            }
+        }
+    }
+    Some(rows.into_iter().map(|row| row[size..].to_vec()).collect())
+}
+
+impl Basis {
+    fn new(problem: &LpProblem, solution: &Solution) -> Result<Basis, LpError> {
+        if solution.status != Status::Optimal {
+            return Err(LpError::Unsupported(format!(
+                "The sensitivity analysis needs an optimal solution, found {:?}",
+                solution.status
+            )));
+        }
+        let integers = problem.integer_variable_names();
+        if !integers.is_empty() {
+            return Err(LpError::Unsupported(format!(
+                "The sensitivity analysis is only defined for linear problems, found integer variables {}",
+                integers.join(", ")
+            )));
+        }
+        if !problem.semi_continuous_variables().is_empty() || !problem.sos2.is_empty() {
+            return Err(LpError::Unsupported(
+                "The sensitivity analysis is only defined for linear problems, without semi-continuous variables \
+                 or special ordered sets"
+                    .to_string(),
+            ));
+        }
+
+        let form = problem.to_standard_form()?;
+        let sign = match problem.objective_type {
+            LpObjective::Minimize => 1.0,
+            LpObjective::Maximize => -1.0,
+        };
+        let (nb_rows, nb_variables) = (form.a.len(), form.var_names.len());
+        let structural: Vec<f64> = form.var_names.iter().map(|name| solution.results.get(name).cloned().unwrap_or(0.0)).collect();
+        let objective = dot(&form.c, &structural) + form.objective_constant;
+
+        let mut names = form.var_names.clone();
+        names.extend((1..=nb_rows).map(|index| format!("c{}", index)));
+        let mut costs: Vec<f64> = form.c.iter().map(|c| sign * c).collect();
+        costs.resize(nb_variables + nb_rows, 0.0);
+        let mut columns: Vec<Vec<f64>> = (0..nb_variables).map(|j| form.a.iter().map(|row| row[j]).collect()).collect();
+        columns.extend((0..nb_rows).map(|i| (0..nb_rows).map(|k| if i == k { 1.0 } else { 0.0 }).collect()));
+        let mut values = structural.clone();
+        values.extend(form.a.iter().zip(&form.b).map(|(row, b)| b - dot(row, &structural)));
+        let mut bounds: Vec<(f64, f64)> = form
+            .bounds
+            .iter()
+            .map(|(lower, upper)| (lower.unwrap_or(f64::NEG_INFINITY), upper.unwrap_or(f64::INFINITY)))
+            .collect();
+        bounds.extend(form.senses.iter().map(|sense| match sense {
+            Constraint::LessOrEqual => (0.0, f64::INFINITY),
+            Constraint::GreaterOrEqual => (f64::NEG_INFINITY, 0.0),
+            Constraint::Equal => (0.0, 0.0),
+        }));
+        let mut statuses: Vec<ColumnStatus> = values
+            .iter()
+            .zip(&bounds)
+            .map(|(&value, &(lower, upper))| {
+                if lower == upper {
+                    ColumnStatus::Fixed
+                } else if near(value, lower) {
+                    ColumnStatus::AtLower
+                } else if near(value, upper) {
+                    ColumnStatus::AtUpper
+                } else {
+                    ColumnStatus::Basic
+                }
+            })
+            .collect();
+
+        // columns strictly between their bounds, then the slack and structural columns at a bound,
+        // kept while they are linearly independent of the columns already in the basis
+        let between: Vec<usize> = (0..statuses.len()).filter(|&j| statuses[j] == ColumnStatus::Basic).collect();
+        let candidates = between
+            .iter()
+            .cloned()
+            .chain((nb_variables..nb_variables + nb_rows).filter(|&j| statuses[j] != ColumnStatus::Basic))
+            .chain((0..nb_variables).filter(|&j| statuses[j] != ColumnStatus::Basic));
+        let mut basic = Vec::with_capacity(nb_rows);
+        let mut eliminated: Vec<(usize, Vec<f64>)> = Vec::with_capacity(nb_rows);
+        for j in candidates {
+            if basic.len() == nb_rows && statuses[j] != ColumnStatus::Basic {
+                break;
+            }
+            let mut column = columns[j].clone();
+            for (pivot, reduced) in &eliminated {
+                let factor = column[*pivot];
+                column.iter_mut().zip(reduced).for_each(|(value, r)| *value -= factor * r);
+            }
+            let pivot = (0..nb_rows).max_by(|&i, &k| column[i].abs().total_cmp(&column[k].abs()));
+            match pivot {
+                Some(pivot) if column[pivot].abs() > TOLERANCE => {
+                    let scale = column[pivot];
+                    column.iter_mut().for_each(|value| *value /= scale);
+                    eliminated.push((pivot, column));
+                    basic.push(j);
+                }
+                _ if statuses[j] == ColumnStatus::Basic => {
+                    return Err(LpError::Unsupported(
+                        "The sensitivity analysis needs a basic solution, with at most one variable strictly \
+                         between its bounds per constraint"
+                            .to_string(),
+                    ));
+                }
+                _ => (),
+            }
+        }
+        for &j in &basic {
+            statuses[j] = ColumnStatus::Basic;
+        }
+        let inverse = inverse(&basic.iter().map(|&j| &columns[j]).collect::<Vec<_>>())
+            .ok_or_else(|| LpError::Solver("Singular basis matrix".to_string()))?;
+
+        let duals: Vec<f64> = (0..nb_rows)
+            .map(|i| basic.iter().zip(&inverse).map(|(&j, row)| costs[j] * row[i]).sum())
+            .collect();
+        let reduced_costs: Vec<f64> = costs.iter().zip(&columns).map(|(cost, column)| cost - dot(&duals, column)).collect();
+        let optimal = statuses.iter().zip(&reduced_costs).all(|(status, &reduced_cost)| match status {
+            ColumnStatus::AtLower => reduced_cost >= -TOLERANCE,
+            ColumnStatus::AtUpper => reduced_cost <= TOLERANCE,
+            _ => true,
+        });
+        if !optimal {
+            return Err(LpError::Unsupported(
+                "The basis rebuilt from the degenerate solution is not optimal".to_string(),
+            ));
+        }
+
+        Ok(Basis {
+            names,
+            nb_variables,
+            sign,
+            objective,
+            rhs: form.b,
+            costs,
+            columns,
+            values,
+            bounds,
+            statuses,
+            basic,
+            inverse,
+            reduced_costs,
+        })
+    }
+
+    /// Interval of the changes of the minimized cost of column `j` keeping the basis optimal
+    fn cost_change_range(&self, j: usize) -> (f64, f64) {
+        let reduced_cost = self.reduced_costs[j];
+        let position = match self.statuses[j] {
+            ColumnStatus::Fixed => return (f64::NEG_INFINITY, f64::INFINITY),
+            ColumnStatus::AtLower => return (-reduced_cost, f64::INFINITY),
+            ColumnStatus::AtUpper => return (f64::NEG_INFINITY, -reduced_cost),
+            ColumnStatus::Basic => self.basic.iter().position(|&b| b == j).expect("basic column in the basis"),
+        };
+        // a change `delta` of the cost changes the reduced cost of every nonbasic column `k` by
+        // `-delta alpha_k`, with `alpha_k` the coefficient of `k` in the row of `j` in the tableau
+        let (mut lower, mut upper) = (f64::NEG_INFINITY, f64::INFINITY);
+        for (k, status) in self.statuses.iter().enumerate() {
+            let alpha = dot(&self.inverse[position], &self.columns[k]);
+            if alpha.abs() <= TOLERANCE {
+                continue;
+            }
+            let ratio = self.reduced_costs[k] / alpha;
+            match (status, alpha > 0.0) {
+                (ColumnStatus::AtLower, true) | (ColumnStatus::AtUpper, false) => upper = upper.min(ratio),
+                (ColumnStatus::AtLower, false) | (ColumnStatus::AtUpper, true) => lower = lower.max(ratio),
+                _ => (),
+            }
+        }
+        (lower, upper)
+    }
+
+    /// Objective value once the objective coefficient of column `j` changed by `delta`,
+    /// if the basis stays optimal
+    fn shifted_objective(&self, j: usize, delta: f64) -> f64 {
+        if self.values[j] == 0.0 {
+            self.objective
+        } else {
+            self.objective + delta * self.values[j]
+        }
+    }
+
+    fn variable_ranging(&self, j: usize) -> Ranging {
+        let coefficient = self.sign * self.costs[j];
+        let (lower, upper) = self.cost_change_range(j);
+        // the changes of the maximized objective are the opposite of the minimized one
+        let (lower, upper) = if self.sign > 0.0 { (lower, upper) } else { (-upper, -lower) };
+        Ranging {
+            status: self.statuses[j].name().to_string(),
+            activity: self.values[j],
+            slack_or_cost: coefficient,
+            marginal: if self.statuses[j] == ColumnStatus::Basic { 0.0 } else { self.sign * self.reduced_costs[j] },
+            bounds: self.bounds[j],
+            activity_range: (self.values[j], self.values[j]),
+            objective_coefficient_range: (coefficient + lower, coefficient + upper),
+            objective_value_range: (self.shifted_objective(j, lower), self.shifted_objective(j, upper)),
+            limiting: (None, None),
+        }
+    }
+
+    /// Interval of the changes of the right-hand side of constraint `i` keeping the basis
+    /// feasible, with the basic columns reaching a bound at both ends
+    fn rhs_change_range(&self, i: usize) -> ((f64, Option<usize>), (f64, Option<usize>)) {
+        // a change `delta` of the right-hand side moves every basic column by `delta g`,
+        // with `g` the column `i` of the inverse of the basis
+        let (mut lower, mut upper) = ((f64::NEG_INFINITY, None), (f64::INFINITY, None));
+        for (position, &j) in self.basic.iter().enumerate() {
+            let g = self.inverse[position][i];
+            if g.abs() <= TOLERANCE {
+                continue;
+            }
+            let (low, high) = self.bounds[j];
+            let (to_low, to_high) = ((low - self.values[j]) / g, (high - self.values[j]) / g);
+            let (down, up) = if g > 0.0 { (to_low, to_high) } else { (to_high, to_low) };
+            if down > lower.0 {
+                lower = (down, Some(j));
+            }
+            if up < upper.0 {
+                upper = (up, Some(j));
+            }
+        }
+        (lower, upper)
+    }
+
+    /// Ranging of constraint `i`, whose activity is `a_i x = b_i - s_i`
+    fn constraint_ranging(&self, i: usize) -> Ranging {
+        let j = self.nb_variables + i;
+        let rhs = self.rhs[i];
+        let (slack_lower, slack_upper) = self.bounds[j];
+        let status = match self.statuses[j] {
+            // the activity is at its upper bound when the slack is at its lower bound
+            ColumnStatus::AtLower => "NU",
+            ColumnStatus::AtUpper => "NL",
+            status => status.name(),
+        };
+        // the dual value of the constraint is the opposite of the reduced cost of its slack
+        let marginal = if self.statuses[j] == ColumnStatus::Basic { 0.0 } else { -self.sign * self.reduced_costs[j] };
+        let ((lower, lower_limit), (upper, upper_limit)) = self.rhs_change_range(i);
+        let objective_at = |delta: f64| if marginal == 0.0 { self.objective } else { self.objective + marginal * delta };
+        // a coefficient on the activity is the opposite coefficient on the slack
+        let (cost_lower, cost_upper) = self.cost_change_range(j);
+        let coefficient_range = if self.sign > 0.0 { (-cost_upper, -cost_lower) } else { (cost_lower, cost_upper) };
+        Ranging {
+            status: status.to_string(),
+            activity: rhs - self.values[j],
+            slack_or_cost: self.values[j].abs(),
+            marginal,
+            bounds: (rhs - slack_upper, rhs - slack_lower),
+            activity_range: (rhs + lower, rhs + upper),
+            objective_coefficient_range: coefficient_range,
+            objective_value_range: (objective_at(lower), objective_at(upper)),
+            limiting: (lower_limit.map(|k| self.names[k].clone()), upper_limit.map(|k| self.names[k].clone())),
+        }
+    }
+}
+
+/// Ranges of the objective coefficients of the variables of a linear problem over which the
+/// optimal basis of `solution` stays optimal, by variable name.
+///
+/// The `Ranging` of every variable holds its value, its objective coefficient and reduced cost,
+/// its bounds, and its objective coefficient range with the objective values at both ends.
+/// Its activity range is reduced to its value. Fails for problems with integer variables and for
+/// solutions that are not optimal.
+///
+/// # Examples:
+///
+/// ```
+/// use std::collections::HashMap;
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::solvers::{Solution, Status};
+/// use lp_modeler::solvers::sensitivity::objective_ranging;
+///
+/// let ref x = LpContinuous::new("x").lower_bound(0.0);
+/// let ref y = LpContinuous::new("y").lower_bound(0.0);
+/// let mut problem = LpProblem::new("Ranging", LpObjective::Maximize);
+/// problem += 3 * x + 2 * y;
+/// problem += (x + y).le(4);
+/// problem += (x + 3 * y).le(9);
+/// problem += x.le(3);
+///
+/// // x = 3 and y = 1 stay optimal while the coefficient of x is at least the one of y
+/// let results: HashMap<String, f64> = vec![("x".to_string(), 3.0), ("y".to_string(), 1.0)].into_iter().collect();
+/// let solution = Solution::new(Status::Optimal, results);
+/// let sensitivity = objective_ranging(&problem, &solution).unwrap();
+/// assert_eq!(sensitivity.variables["x"].objective_coefficient_range, (2.0, f64::INFINITY));
+/// ```
+pub fn objective_ranging(problem: &LpProblem, solution: &Solution) -> Result<Sensitivity, LpError> {
+    let variables = sensitivity_analysis(problem, solution)?.variables;
+    Ok(Sensitivity { variables, ..Sensitivity::default() })
+}
+
+/// Ranges of the right-hand sides of the constraints of a linear problem over which the optimal
+/// basis of `solution` stays feasible, and thus the dual values valid, by constraint name.
+///
+/// The `Ranging` of every constraint holds its activity and slack, its dual value, the range of
+/// its right-hand side as activity range with the objective values at both ends, and the
+/// variables or constraints leaving the basis at both ends. Fails for problems with integer
+/// variables, whose dual values are not defined, and for solutions that are not optimal.
+///
+/// # Examples:
+///
+/// ```
+/// use std::collections::HashMap;
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::solvers::{Solution, Status};
+/// use lp_modeler::solvers::sensitivity::rhs_ranging;
+///
+/// let ref x = LpContinuous::new("x").lower_bound(0.0);
+/// let mut problem = LpProblem::new("Ranging", LpObjective::Maximize);
+/// problem += 3 * x;
+/// problem += x.le(4);
+///
+/// // the capacity of c1 is worth 3 whatever its value, as long as it is nonnegative
+/// let results: HashMap<String, f64> = vec![("x".to_string(), 4.0)].into_iter().collect();
+/// let solution = Solution::new(Status::Optimal, results);
+/// let sensitivity = rhs_ranging(&problem, &solution).unwrap();
+/// assert_eq!(sensitivity.constraints["c1"].marginal, 3.0);
+/// assert_eq!(sensitivity.constraints["c1"].activity_range, (0.0, f64::INFINITY));
+/// ```
+pub fn rhs_ranging(problem: &LpProblem, solution: &Solution) -> Result<Sensitivity, LpError> {
+    let constraints = sensitivity_analysis(problem, solution)?.constraints;
+    Ok(Sensitivity { constraints, ..Sensitivity::default() })
+}
+
+/// Both `objective_ranging` and `rhs_ranging`, from the same basis
+pub fn sensitivity_analysis(problem: &LpProblem, solution: &Solution) -> Result<Sensitivity, LpError> {
+    let basis = Basis::new(problem, solution)?;
+    Ok(Sensitivity {
+        constraints: (0..basis.rhs.len()).map(|i| (format!("c{}", i + 1), basis.constraint_ranging(i))).collect(),
+        variables: (0..basis.nb_variables).map(|j| (basis.names[j].clone(), basis.variable_ranging(j))).collect(),
+    })
+}
@@ -2,6 +2,20 @@ pub fn is_zero(n: f64) -> bool {
     n.abs() < 0.00001
 }
 
+/// `value` rounded to `decimals` decimals, unchanged if it is too large to be scaled
+pub fn round_to(value: f64, decimals: usize) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    let rounded = (value * factor).round() / factor;
+    if !rounded.is_finite() {
+        value
+    } else if rounded == 0.0 {
+        // no negative zero
+        0.0
+    } else {
+        rounded
+    }
+}
+
 /// This macro allows defining constraints using 'expression1 <= expression2'
 /// instead of `expression1.le(expression2)`. 
 /// 
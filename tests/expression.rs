@@ -193,3 +193,112 @@ fn macros(){
         "2 a + b - c >= -20"
     );
 }
+
+#[test]
+fn variable_collections() {
+    let x = LpContinuous::vec("x", 3);
+    assert_eq!(lp_sum(&x).to_lp_file_format(), "x_0 + x_1 + x_2");
+
+    let assign = LpBinary::matrix("assign", 3, 3);
+    assert_eq!(assign.len(), 3);
+    assert!(assign.iter().all(|row| row.len() == 3));
+    assert_eq!(
+        lp_sum(&assign[1]).to_lp_file_format(),
+        "assign_1_0 + assign_1_1 + assign_1_2"
+    );
+    let column: Vec<&LpBinary> = assign.iter().map(|row| &row[2]).collect();
+    assert_eq!(
+        lp_sum(&column).to_lp_file_format(),
+        "assign_0_2 + assign_1_2 + assign_2_2"
+    );
+}
+
+#[test]
+fn sum_of_iterator() {
+    let x = LpContinuous::vec("x", 7);
+    let odd = || x.iter().enumerate().filter(|(i, _)| i % 2 == 1).map(|(_, v)| v.into());
+    assert_eq!(lp_sum_iter(odd()).to_lp_file_format(), "x_1 + x_3 + x_5");
+    assert_eq!(odd().sum::<LpExpression>().to_lp_file_format(), "x_1 + x_3 + x_5");
+    assert_eq!(
+        lp_sum_iter(x.iter().map(|v| v.into())).to_lp_file_format(),
+        lp_sum(&x).to_lp_file_format()
+    );
+    assert_eq!(lp_sum_iter(Vec::new()).to_lp_file_format(), "0");
+}
+
+#[test]
+fn sum_of_expressions() {
+    let x = LpContinuous::vec("x", 5);
+    let terms: Vec<LpExpression> = x.iter().enumerate().map(|(i, v)| (i as i32 + 1) * v).collect();
+    let expected = lp_sum(&terms).to_lp_file_format();
+    assert_eq!(expected, "x_0 + 2 x_1 + 3 x_2 + 4 x_3 + 5 x_4");
+    assert_eq!(terms.iter().sum::<LpExpression>().to_lp_file_format(), expected);
+    assert_eq!(terms.clone().into_iter().sum::<LpExpression>().to_lp_file_format(), expected);
+    assert_eq!(Vec::<LpExpression>::new().iter().sum::<LpExpression>().to_lp_file_format(), "0");
+}
+
+#[test]
+fn collect_expressions() {
+    let x = LpContinuous::vec("x", 4);
+    let coefficients = [2.0, -1.0, 0.5, 3.0];
+    let terms: Vec<LpExpression> = coefficients.iter().zip(x.iter()).map(|(&c, v)| c * v).collect();
+
+    let collected: LpExpression = terms.clone().into_iter().collect();
+    assert_eq!(collected, lp_sum(&terms));
+    assert_eq!(collected.to_lp_file_format(), "2 x_0 - x_1 + 0.5 x_2 + 3 x_3");
+    let empty: LpExpression = Vec::new().into_iter().collect();
+    assert_eq!(empty.to_lp_file_format(), "0");
+}
+
+#[test]
+fn linear_coefficients() {
+    let a = &LpContinuous::new("a");
+    let b = &LpContinuous::new("b");
+    let c = &LpInteger::new("c");
+
+    // as in the decomposition used by the minilp solver
+    let (coefficients, constant) = ((4 * (3 * a - b * 2 + a)) * 1 + b).linear_coefficients().unwrap();
+    assert_eq!(coefficients.len(), 2);
+    assert_eq!(coefficients["a"], 4. * 3. + 4.);
+    assert_eq!(coefficients["b"], 4. * (-2.) + 1.);
+    assert_eq!(constant, 0.);
+
+    let (coefficients, constant) = (2 * (c + 3) - c - c + a).linear_coefficients().unwrap();
+    assert_eq!(coefficients.keys().collect::<Vec<_>>(), vec!["a"]);
+    assert_eq!(constant, 6.);
+
+    let error = (2 * a + a * b).linear_coefficients().unwrap_err();
+    assert_eq!(error.to_string(), "Expected a linear expression, found a product of variables");
+}
+
+#[test]
+fn numeric_types() {
+    let x = &LpContinuous::new("x");
+    let (a, b, c, d, e): (f32, i64, u32, usize, i32) = (0.5, 2, 3, 4, 5);
+
+    assert_eq!((x * a).to_lp_file_format(), "0.5 x");
+    assert_eq!((x * b - c).to_lp_file_format(), "2 x - 3");
+    assert_eq!((x + d).to_lp_file_format(), "x + 4");
+    assert_eq!((x * e + x * a).to_lp_file_format(), "5 x + 0.5 x");
+    assert_eq!((LpExpression::from(a) * x + LpExpression::from(b) * x).to_lp_file_format(), "0.5 x + 2 x");
+
+    let mut expr = LpExpression::from(a);
+    expr += x;
+    expr *= b;
+    expr -= d;
+    assert_eq!(expr.to_lp_file_format(), "2 x - 3");
+    assert_eq!(x.le(c).to_lp_file_format(), "x <= 3");
+}
+
+#[test]
+fn display() {
+    let a = &LpContinuous::new("a");
+    let b = &LpInteger::new("b");
+
+    assert_eq!(format!("{}", 2 * a + 3 * b), "2 a + 3 b");
+    assert_eq!(format!("{}", 3 * b - 2 * (a + 2) - b + a), "2 b - a - 4");
+    assert_eq!(format!("{}", -1 * a + b * 0.5), "-a + 0.5 b");
+    assert_eq!(format!("{}", a - a + 1.5), "1.5");
+    assert_eq!(format!("{}", LpExpression::from(-2)), "-2");
+    assert_eq!(format!("{}", a * b + 1), (a * b + 1).to_lp_file_format());
+}
@@ -1,13 +1,27 @@
 extern crate lp_modeler;
 
 use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Write;
 
-use lp_modeler::solvers::{CbcSolver, SolverTrait, Solution};
+#[cfg(feature = "cbc")]
+use lp_modeler::solvers::CbcSolver;
+use lp_modeler::solvers::{SolverTrait, Solution, Status};
 #[cfg(feature = "native_coin_cbc")]
 use lp_modeler::solvers::NativeCbcSolver;
+#[cfg(feature = "minilp")]
+use lp_modeler::solvers::{compute_iis, MiniLpSolver};
+#[cfg(feature = "minilp")]
+use lp_modeler::solvers::sensitivity;
 use lp_modeler::dsl::*;
+use lp_modeler::error::LpError;
 use lp_modeler::format::lp_format::LpFileFormat;
+use lp_modeler::format::lp_reader::parse_lp;
+use lp_modeler::format::mps_format::{MpsFileFormat, MpsFlavor};
+use lp_modeler::format::mps_reader::parse_mps;
 
+#[cfg(feature = "cbc")]
 #[test]
 fn test_readme_example_1() {
     let ref a = LpInteger::new("a");
@@ -49,6 +63,7 @@ Subject To
     assert_eq!(output1, output2);
 }
 
+#[cfg(feature = "cbc")]
 #[test]
 fn test_full_example() {
     let ref a = LpInteger::new("a").lower_bound(1.0);
@@ -80,6 +95,30 @@ fn test_full_example() {
     }
 }
 
+#[test]
+fn test_sections_order() {
+    let bin = &LpBinary::new("bin");
+    let int = &LpInteger::new("int").upper_bound(5.0);
+    let cont = &LpContinuous::new("cont").lower_bound(1.0);
+
+    let mut problem = LpProblem::new("Sections", LpObjective::Minimize);
+    problem += bin + int + cont;
+    // binaries first, continuous last: the written order must not depend on it
+    problem += (bin + int + cont).ge(2);
+
+    let output = problem.to_lp_file_format();
+    let position = |section: &str| {
+        output
+            .find(section)
+            .unwrap_or_else(|| panic!("{} section is missing", section))
+    };
+    assert!(position("Subject To") < position("Bounds"));
+    assert!(position("Bounds") < position("Generals"));
+    assert!(position("Generals") < position("Binary"));
+    assert!(position("Binary") < position("End"));
+}
+
+#[cfg(feature = "cbc")]
 #[test]
 fn test_readme_example_2() {
     // Problem Data
@@ -152,7 +191,7 @@ fn test_readme_example_2() {
 
     // Terminate if error, or assign status & variable values
     assert!(result.is_ok(), result.unwrap_err());
-    let Solution { status: solver_status, results: var_values, related_problem: _ } = result.unwrap();
+    let Solution { status: solver_status, results: var_values, .. } = result.unwrap();
 
     // Compute final objective function value
     let mut obj_value = 0f64;
@@ -174,7 +213,7 @@ fn test_readme_example_2() {
         }
     }
 
-    assert_eq!(solver_status, lp_modeler::solvers::Status::Optimal);
+    assert_eq!(solver_status, Status::Optimal);
     assert_eq!(obj_value, 230f64);
     assert_eq!(*var_values.get("A_F").unwrap(), 1f64);
     assert_eq!(*var_values.get("B_E").unwrap(), 1f64);
@@ -222,4 +261,1554 @@ fn cbc_native_optimal() {
         }
         Err(msg) => panic!("Native Cbc Solver panicked at run: {}", msg),
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_metadata() {
+    let a = &LpContinuous::new("a");
+
+    let mut problem = LpProblem::new("Metadata", LpObjective::Maximize);
+    problem += a;
+    problem += a.le(10);
+    problem.set_metadata("scenario", "42");
+    problem.set_metadata("created", "2020-01-01");
+
+    assert_eq!(problem.get_metadata("scenario"), Some("42"));
+    assert_eq!(problem.get_metadata("unknown"), None);
+    assert!(problem
+        .to_lp_file_format()
+        .starts_with("\\ Metadata\n\\ created: 2020-01-01\n\\ scenario: 42\n\n"));
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert_eq!(solution.get_metadata("scenario"), Some("42"));
+
+    // a line break cannot end the comment and inject a constraint
+    problem.set_metadata("note", "first\nSubject To\r\n c9: a >= 11");
+    assert_eq!(problem.get_metadata("note"), Some("first\nSubject To\r\n c9: a >= 11"));
+    let written = problem.to_lp_file_format();
+    assert!(written.contains("\\ note: first\\nSubject To\\r\\n c9: a >= 11\n"), "{}", written);
+    assert_eq!(parse_lp(&written, "Metadata").unwrap().constraints.len(), 1);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_minimize_max_of() {
+    let x = &LpContinuous::new("x").lower_bound(0.0).upper_bound(10.0);
+
+    let mut problem = LpProblem::new("Minimax", LpObjective::Minimize);
+    let (t, constraints) = LpExpression::max_of(&[x.into(), 10 - x]);
+    problem += &t;
+    for constraint in constraints {
+        problem += constraint;
+    }
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert!((solution.get_float(x) - 5.0).abs() < 1e-9);
+    assert!((solution.get_float(&t) - 5.0).abs() < 1e-9);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_minimize_abs() {
+    let x = &LpContinuous::new("x");
+
+    let mut problem = LpProblem::new("Distance", LpObjective::Minimize);
+    let (t, constraints) = (x - 5).abs();
+    problem += &t;
+    for constraint in constraints {
+        problem += constraint;
+    }
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert!(solution.objective_value(&problem).unwrap().abs() < 1e-9);
+    assert!((solution.get_float(x) - 5.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_with_capacity() {
+    let x = &LpContinuous::new("x");
+    let mut problem = LpProblem::with_capacity("Reserved", LpObjective::Minimize, 100);
+    let capacity = problem.constraints.capacity();
+    assert!(capacity >= 100);
+    for i in 0..100 {
+        problem += x.ge(i);
+    }
+    assert_eq!(problem.constraints.len(), 100);
+    assert_eq!(problem.constraints.capacity(), capacity);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_piecewise_linear() {
+    let x = &LpContinuous::new("x");
+    // slopes 1 then 2: convex, so minimizing the value is exact without the SOS2 set
+    let (value, constraints, sos) = piecewise_linear(x, &[(0.0, 0.0), (1.0, 1.0), (3.0, 5.0)]);
+
+    let mut problem = LpProblem::new("Piecewise", LpObjective::Minimize);
+    problem += &value;
+    for constraint in constraints {
+        problem += constraint;
+    }
+    problem += x.equal(2);
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert!((solution.get_float(&value) - 3.0).abs() < 1e-9);
+
+    let LpSos2(ref set) = sos;
+    let weights: Vec<f64> = set.iter().map(|&(_, w)| w).collect();
+    assert_eq!(weights, vec![0.0, 1.0, 3.0]);
+    problem += sos.clone();
+    assert!(problem
+        .to_lp_file_format()
+        .contains(&format!("SOS\n  s1: S2:: {}:0 {}:1 {}:3\n", set[0].0.name, set[1].0.name, set[2].0.name)));
+    assert!(MiniLpSolver::new().run(&problem).is_err());
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_split_free_variables() {
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+
+    let mut problem = LpProblem::new("Split", LpObjective::Minimize);
+    problem += x + 2 * y;
+    problem += (x + y).ge(-3);
+    problem += x.ge(-5);
+
+    let split = problem.split_free_variables();
+    assert_eq!(split.len(), 1);
+    let (x_pos, x_neg) = &split["x"];
+    assert!(!problem.variables().contains_key("x"));
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    let value = solution.get_float(x_pos) - solution.get_float(x_neg);
+    assert!((value + 3.0).abs() < 1e-9);
+    assert!((solution.objective_value(&problem).unwrap() + 3.0).abs() < 1e-9);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_apply_updates() {
+    let x = &LpContinuous::new("x").lower_bound(0.0).upper_bound(3.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+
+    let mut problem = LpProblem::new("Updates", LpObjective::Maximize);
+    problem += 3 * x + 2 * y;
+    problem += (x + y).le(4);
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert!((solution.objective_value(&problem).unwrap() - 11.0).abs() < 1e-9);
+
+    let mut update = ProblemUpdate::default();
+    update.objective_coefficients.insert("y".to_string(), 1.0);
+    update.bounds.insert("x".to_string(), (Some(0.0), Some(2.0)));
+    problem.apply_updates(&update).unwrap();
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert!((solution.get_float(x) - 2.0).abs() < 1e-9);
+    assert!((solution.get_float(y) - 2.0).abs() < 1e-9);
+    assert!((solution.objective_value(&problem).unwrap() - 8.0).abs() < 1e-9);
+
+    update.bounds.insert("z".to_string(), (None, None));
+    assert!(problem.apply_updates(&update).is_err());
+}
+
+#[test]
+fn test_validate_unbounded_variable() {
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").upper_bound(5.0);
+
+    let mut problem = LpProblem::new("Unbounded", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x - y).ge(1);
+    assert_eq!(problem.validate().unwrap(), vec![ModelWarning::UnboundedVariable("x".to_string())]);
+
+    problem.objective_type = LpObjective::Minimize;
+    assert_eq!(problem.validate().unwrap(), vec![ModelWarning::UnboundedVariable("y".to_string())]);
+}
+
+#[test]
+fn test_validate_empty_constraint() {
+    let x = &LpContinuous::new("x").lower_bound(0.0).upper_bound(1.0);
+
+    let mut problem = LpProblem::new("Empty", LpObjective::Maximize);
+    problem += x;
+    problem += x.le(1);
+    problem += (x + 2).le(x + 3);
+    assert_eq!(problem.validate().unwrap(), vec![ModelWarning::EmptyConstraint("c2".to_string())]);
+}
+
+#[test]
+fn test_validate_violated_constraint() {
+    let x = &LpContinuous::new("x").lower_bound(0.0).upper_bound(1.0);
+
+    let mut problem = LpProblem::new("Violated", LpObjective::Maximize);
+    problem += x;
+    problem += x.le(1);
+    problem += (x + 3).ge(x + 10);
+    problem += (2 * x - x - x).equal(0);
+    assert_eq!(
+        problem.validate().unwrap(),
+        vec![
+            ModelWarning::ViolatedConstraint("c2".to_string()),
+            ModelWarning::EmptyConstraint("c3".to_string()),
+        ]
+    );
+    assert_eq!(
+        problem.validate().unwrap()[0].to_string(),
+        "Constraint c2 has no variable and can never be satisfied"
+    );
+}
+
+#[test]
+fn test_is_feasible() {
+    let x = &LpContinuous::new("x").lower_bound(0.0).upper_bound(4.0);
+    let n = &LpInteger::new("n").lower_bound(0.0);
+    let b = &LpBinary::new("b");
+
+    let mut problem = LpProblem::new("Check", LpObjective::Maximize);
+    problem += x + n + b;
+    problem += (x + 2 * n).le(10);
+    problem += (x - b).ge(0.5);
+
+    let assignment = |x: f64, n: f64, b: f64| -> HashMap<String, f64> {
+        vec![("x".to_string(), x), ("n".to_string(), n), ("b".to_string(), b)].into_iter().collect()
+    };
+    assert_eq!(problem.is_feasible(&assignment(4.0, 3.0, 1.0), 1e-6).ok(), Some(true));
+    // within tolerance
+    assert_eq!(problem.is_feasible(&assignment(4.0000001, 3.0, 1.0), 1e-6).ok(), Some(true));
+    // x + 2 n <= 10
+    assert_eq!(problem.is_feasible(&assignment(4.0, 4.0, 1.0), 1e-6).ok(), Some(false));
+    // n integer
+    assert_eq!(problem.is_feasible(&assignment(2.0, 2.5, 1.0), 1e-6).ok(), Some(false));
+    // b binary
+    assert_eq!(problem.is_feasible(&assignment(2.0, 2.0, 0.5), 1e-6).ok(), Some(false));
+    // x <= 4
+    assert_eq!(problem.is_feasible(&assignment(4.5, 0.0, 0.0), 1e-6).ok(), Some(false));
+
+    let mut missing = assignment(4.0, 3.0, 1.0);
+    missing.remove("n");
+    match problem.is_feasible(&missing, 1e-6) {
+        Err(LpError::MissingValue(name)) => assert_eq!(name, "n"),
+        result => panic!("expected a missing value, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_validate_unused_variable() {
+    let x = &LpContinuous::new("x").lower_bound(0.0).upper_bound(1.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0).upper_bound(1.0);
+
+    let mut problem = LpProblem::new("Unused", LpObjective::Maximize);
+    problem += x + 0 * y;
+    problem += (x + y - y).le(1);
+    assert_eq!(problem.validate().unwrap(), vec![ModelWarning::UnusedVariable("y".to_string())]);
+}
+
+#[test]
+fn test_nonlinear_problem() {
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+
+    let mut problem = LpProblem::new("Nonlinear", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x * y).le(1);
+    assert!(matches!(problem.validate(), Err(LpError::Nonlinear(_))));
+    assert!(matches!(problem.check_coefficients(), Err(LpError::Nonlinear(_))));
+    assert!(matches!(problem.connected_components(), Err(LpError::Nonlinear(_))));
+    assert!(matches!(problem.to_standard_form(), Err(LpError::Nonlinear(_))));
+}
+
+#[cfg(feature = "cbc")]
+#[test]
+fn test_empty_problem() {
+    let problem = LpProblem::new("Empty", LpObjective::Minimize);
+    assert_eq!(problem.validate().unwrap(), vec![ModelWarning::EmptyProblem]);
+    match CbcSolver::new().run(&problem) {
+        Err(e @ LpError::EmptyProblem(_)) => {
+            assert_eq!(e.to_string(), "Problem Empty has no constraint and no variable in its objective")
+        }
+        result => panic!("expected an empty problem, got {:?}", result.map(|s| s.status)),
+    }
+    #[cfg(feature = "minilp")]
+    assert!(matches!(MiniLpSolver::new().run(&problem), Err(LpError::EmptyProblem(_))));
+
+    // an objective without any variable
+    let mut problem = LpProblem::new("Constant", LpObjective::Maximize);
+    problem += LpExpression::literal(2.0) * 3;
+    assert_eq!(problem.validate().unwrap(), vec![ModelWarning::EmptyProblem]);
+    assert!(matches!(CbcSolver::new().run(&problem), Err(LpError::EmptyProblem(_))));
+    #[cfg(feature = "minilp")]
+    assert!(matches!(MiniLpSolver::new().run(&problem), Err(LpError::EmptyProblem(_))));
+
+    // a constraint is enough to solve the problem
+    problem += LpContinuous::new("x").le(1);
+    assert!(problem.check_not_empty().is_ok());
+    assert_eq!(problem.validate().unwrap(), vec![]);
+}
+
+#[test]
+fn test_feasibility_lp_format() {
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+    let mut problem = LpProblem::feasibility("Feasibility");
+    problem += (x + y).ge(2);
+    let lp = problem.to_lp_file_format();
+    assert!(lp.contains("Minimize\n  obj: 0 x\n"), "{}", lp);
+    assert!(lp.contains("c1: x + y >= 2"), "{}", lp);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_feasibility() {
+    let x = &LpContinuous::new("x").lower_bound(0.0).upper_bound(1.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+
+    let mut problem = LpProblem::feasibility("Satisfiable");
+    problem += (x + y).ge(3);
+    problem += (y - x).le(2.5);
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    // the bounds of the variables, only found in the constraints, are kept
+    let (x_value, y_value) = (solution.results["x"], solution.results["y"]);
+    assert!((0.0..=1.0).contains(&x_value) && y_value >= 0.0);
+    assert!(x_value + y_value >= 3.0 - 1e-9 && y_value - x_value <= 2.5 + 1e-9);
+    assert_eq!(solution.objective_value(&problem).unwrap(), 0.0);
+
+    let mut problem = LpProblem::feasibility("Unsatisfiable");
+    problem += (x + y).ge(3);
+    problem += (y - x).le(0.5);
+    assert_eq!(MiniLpSolver::new().run(&problem).unwrap().status, Status::Infeasible);
+}
+
+#[test]
+#[cfg(feature = "minilp")]
+fn test_to_le_form() {
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+    let z = &LpContinuous::new("z").lower_bound(0.0);
+
+    let mut problem = LpProblem::new("Senses", LpObjective::Minimize);
+    problem += 3 * x + 2 * y + z;
+    problem += (x + y).ge(4);
+    problem += (x - z).equal(1);
+    problem += (y + 2).le(z + 5);
+    problem.add_tagged(&["balance"], (2 * y + z).ge(x));
+
+    let normalized = problem.to_le_form();
+    assert_eq!(normalized.constraints.len(), 5);
+    assert!(normalized.constraints.iter().all(|LpConstraint(_, op, _)| *op == Constraint::LessOrEqual));
+    let constraints = normalized.to_lp_file_format();
+    let constraints = constraints.split("Subject To").nth(1).unwrap();
+    assert!(!constraints.contains(">=") && !constraints.contains(" = "));
+    // with the variables on the left and the constant on the right
+    assert!(constraints.contains("c1: -x - y <= -4\n"));
+    assert!(constraints.contains("c2: x - z <= 1\n"));
+    assert!(constraints.contains("c3: -x + z <= -1\n"));
+    assert!(constraints.contains("c4: y - z <= 3\n"));
+    assert_eq!(normalized.constraints_with_tag("balance").iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![4]);
+
+    let solver = MiniLpSolver::new();
+    let original = solver.run(&problem).unwrap();
+    let solution = solver.run(&normalized).unwrap();
+    assert_eq!(original.status, Status::Optimal);
+    assert_eq!(solution.status, Status::Optimal);
+    let expected = original.objective_value(&problem).unwrap();
+    assert!((solution.objective_value(&normalized).unwrap() - expected).abs() < 1e-9);
+}
+
+#[test]
+#[cfg(feature = "minilp")]
+fn test_flipped_constraint() {
+    let a = &LpContinuous::new("a").lower_bound(0.0);
+    let b = &LpContinuous::new("b").lower_bound(0.0);
+
+    let constraint = (a + b).le(5);
+    let flipped = constraint.flipped();
+    let expected = (-1 * a - b).ge(-5);
+    assert_eq!(flipped.1, expected.1);
+    assert_eq!(flipped.0.linear_coefficients().unwrap(), expected.0.linear_coefficients().unwrap());
+    assert_eq!(flipped.2.linear_coefficients().unwrap(), expected.2.linear_coefficients().unwrap());
+    assert_eq!(flipped.flipped().1, Constraint::LessOrEqual);
+    assert_eq!((a - 2 * b).equal(1).flipped().1, Constraint::GreaterOrEqual);
+
+    // both constraints bound the same half-plane, reaching the edge a + b = 5
+    let solve = |constraint: LpConstraint| {
+        let mut problem = LpProblem::new("Edge", LpObjective::Maximize);
+        problem += a + 2 * b;
+        problem += constraint;
+        let solution = MiniLpSolver::new().run(&problem).unwrap();
+        assert_eq!(solution.status, Status::Optimal);
+        (solution.results["a"], solution.results["b"])
+    };
+    assert_eq!(solve(constraint), (0.0, 5.0));
+    assert_eq!(solve(flipped), (0.0, 5.0));
+}
+
+#[test]
+#[cfg(feature = "minilp")]
+fn test_bounds_set_after_creation() {
+    let x = LpContinuous::new("x");
+    let y = LpContinuous::new("y");
+    let (lower, upper) = (1.5, 10.0);
+    let x = &x.bounded(lower, upper);
+    let y = &y.with_lower_bound(0.0).with_upper_bound(upper / 4.0);
+
+    let mut problem = LpProblem::new("Bounds", LpObjective::Maximize);
+    problem += x + 3 * y;
+    problem += (x - y).ge(0);
+    let lp = problem.to_lp_file_format();
+    assert!(lp.contains("  1.5 <= x <= 10\n"));
+    assert!(lp.contains("  0 <= y <= 2.5\n"));
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results["x"], 10.0);
+    assert_eq!(solution.results["y"], 2.5);
+
+    problem.objective_type = LpObjective::Minimize;
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert_eq!(solution.results["x"], 1.5);
+}
+
+#[test]
+fn test_semi_continuous() {
+    let size = &LpSemiContinuous::new("size", 20.0, 100.0);
+    let other = &LpContinuous::new("other").lower_bound(0.0);
+
+    let mut problem = LpProblem::new("Facility", LpObjective::Minimize);
+    problem += 3 * size + 4 * other;
+    problem += (size + other).ge(10);
+    let lp = problem.to_lp_file_format();
+    assert!(lp.contains("  20 <= size <= 100\n"));
+    assert!(lp.contains("\nSemi-Continuous\n  size"));
+    assert!(!lp.contains("other\nSemi"));
+
+    let point = |size_value: f64, other_value: f64| -> HashMap<String, f64> {
+        vec![("size".to_string(), size_value), ("other".to_string(), other_value)].into_iter().collect()
+    };
+    assert!(problem.is_feasible(&point(0.0, 10.0), 1e-9).unwrap());
+    assert!(problem.is_feasible(&point(20.0, 0.0), 1e-9).unwrap());
+    assert!(!problem.is_feasible(&point(10.0, 0.0), 1e-9).unwrap());
+
+    // either zero with the indicator off, or between the bounds with the indicator on
+    let lowered = problem.lower_semi_continuous().unwrap();
+    assert!(!lowered.to_lp_file_format().contains("Semi-Continuous"));
+    let point = |size_value: f64, on: f64| -> HashMap<String, f64> {
+        let mut values = point(size_value, 10.0);
+        values.insert("size_on".to_string(), on);
+        values
+    };
+    assert!(lowered.is_feasible(&point(0.0, 0.0), 1e-9).unwrap());
+    assert!(lowered.is_feasible(&point(50.0, 1.0), 1e-9).unwrap());
+    assert!(!lowered.is_feasible(&point(50.0, 0.0), 1e-9).unwrap());
+    assert!(!lowered.is_feasible(&point(10.0, 0.0), 1e-9).unwrap());
+    assert!(!lowered.is_feasible(&point(10.0, 1.0), 1e-9).unwrap());
+    assert!(!lowered.is_feasible(&point(0.0, 1.0), 1e-9).unwrap());
+}
+
+#[test]
+fn test_lower_semi_continuous_preconditions() {
+    let size = &LpSemiContinuous::new("size", 20.0, f64::INFINITY);
+    let mut problem = LpProblem::new("Unbounded", LpObjective::Minimize);
+    problem += size;
+    problem += size.ge(10);
+    assert!(matches!(problem.lower_semi_continuous(), Err(LpError::Unsupported(_))));
+
+    // the indicator does not reuse the name of a variable of the problem
+    let size = &LpSemiContinuous::new("size", 20.0, 100.0);
+    let size_on = &LpContinuous::new("size_on");
+    let mut problem = LpProblem::new("Clash", LpObjective::Minimize);
+    problem += size + size_on;
+    problem += size.ge(10);
+    let lp = problem.lower_semi_continuous().unwrap().to_lp_file_format();
+    assert!(lp.contains("size - 20 size_on1 >= "));
+    assert!(lp.contains("Binary\n  size_on1 \n"));
+}
+
+#[test]
+#[cfg(feature = "minilp")]
+fn test_semi_continuous_minilp() {
+    let size = &LpSemiContinuous::new("size", 20.0, 100.0);
+    let mut problem = LpProblem::new("Facility", LpObjective::Maximize);
+    problem += size;
+    problem += size.le(60);
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results["size"], 60.0);
+    assert!(!solution.results.contains_key("size_on"));
+
+    // the relaxation gives size = 10 with size_on = 0.5, neither zero nor within the bounds
+    let mut problem = LpProblem::new("Facility", LpObjective::Minimize);
+    problem += size;
+    problem += size.ge(10);
+    for solver in [MiniLpSolver::new(), MiniLpSolver::new().relax_integers()] {
+        let solution = solver.run(&problem).unwrap();
+        assert_eq!(solution.status, Status::Optimal);
+        assert_eq!(solution.results["size"], 20.0);
+    }
+}
+
+#[test]
+#[cfg(feature = "minilp")]
+fn test_objective_ranging_minilp() {
+    // Wyndor Glass with an extra product x3: the optimum x1 = 2, x2 = 6 stays optimal for
+    // 1.5 <= c1 <= 7.5, below which x3 becomes profitable, and c2 >= 2
+    let x1 = &LpContinuous::new("x1").lower_bound(0.0);
+    let x2 = &LpContinuous::new("x2").lower_bound(0.0);
+    let x3 = &LpContinuous::new("x3").lower_bound(0.0);
+    let mut problem = LpProblem::new("Wyndor", LpObjective::Maximize);
+    problem += 3 * x1 + 5 * x2 + x3;
+    problem += x1.le(4);
+    problem += (2 * x2).le(12);
+    problem += (3 * x1 + 2 * x2 + 2 * x3).le(18);
+
+    let solution = MiniLpSolver::new().with_sensitivity().run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    let variables = &solution.sensitivity.as_ref().unwrap().variables;
+    let close = |(l1, u1): (f64, f64), (l2, u2): (f64, f64)| {
+        (l1 == l2 || (l1 - l2).abs() < 1e-9) && (u1 == u2 || (u1 - u2).abs() < 1e-9)
+    };
+    assert_eq!(variables["x1"].status, "BS");
+    assert!(close(variables["x1"].objective_coefficient_range, (1.5, 7.5)));
+    assert!(close(variables["x1"].objective_value_range, (33.0, 45.0)));
+    assert!(close(variables["x2"].objective_coefficient_range, (2.0, f64::INFINITY)));
+    // x3 only pays off once its coefficient exceeds twice the dual value 1 of the third constraint
+    assert_eq!(variables["x3"].status, "NL");
+    assert!((variables["x3"].marginal + 1.0).abs() < 1e-9);
+    assert!(close(variables["x3"].objective_coefficient_range, (f64::NEG_INFINITY, 2.0)));
+
+    let n = &LpInteger::new("n");
+    problem += n.le(1);
+    // the solution of a MIP is kept without its sensitivity analysis
+    let solution = MiniLpSolver::new().relax_integers().with_sensitivity().run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert!(solution.sensitivity.is_none());
+    match sensitivity::sensitivity_analysis(&problem, &solution) {
+        Err(LpError::Unsupported(message)) => assert!(message.contains("integer variables n")),
+        other => panic!("expected an unsupported MIP, got {:?}", other.is_ok()),
+    }
+}
+
+#[test]
+#[cfg(feature = "minilp")]
+fn test_rhs_ranging_minilp() {
+    // both constraints are active at x = 3, y = 1, with x = (3 b1 - b2) / 2 and y = (b2 - b1) / 2:
+    // the basis stays feasible for 2 <= b1 <= 6 and 4 <= b2 <= 12
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+    let mut problem = LpProblem::new("Ranging", LpObjective::Maximize);
+    problem += 2 * x + 3 * y;
+    problem += (x + y).le(4);
+    problem += (x + 3 * y).le(6);
+
+    let solution = MiniLpSolver::new().with_sensitivity().run(&problem).unwrap();
+    let constraints = &solution.sensitivity.as_ref().unwrap().constraints;
+    let close = |(l1, u1): (f64, f64), (l2, u2): (f64, f64)| (l1 - l2).abs() < 1e-9 && (u1 - u2).abs() < 1e-9;
+    let c1 = &constraints["c1"];
+    assert_eq!(c1.status, "NU");
+    assert!((c1.marginal - 1.5).abs() < 1e-9);
+    assert!(close(c1.activity_range, (2.0, 6.0)));
+    assert!(close(c1.objective_value_range, (6.0, 12.0)));
+    assert_eq!(c1.limiting, (Some("x".to_string()), Some("y".to_string())));
+    let c2 = &constraints["c2"];
+    assert!((c2.marginal - 0.5).abs() < 1e-9);
+    assert!(close(c2.activity_range, (4.0, 12.0)));
+    assert_eq!(c2.limiting, (Some("y".to_string()), Some("x".to_string())));
+
+    let n = &LpInteger::new("n");
+    problem += n.le(1);
+    // the dual values of a MIP are not defined, even at the optimum of its relaxation
+    let relaxed = MiniLpSolver::new().relax_integers().run(&problem).unwrap();
+    assert!(matches!(sensitivity::rhs_ranging(&problem, &relaxed), Err(LpError::Unsupported(_))));
+}
+
+#[test]
+fn test_fluent_builder() {
+    let a = &LpInteger::new("a").lower_bound(0.0);
+    let b = &LpContinuous::new("b").upper_bound(4.0);
+
+    let mut assigned = LpProblem::new("Fluent", LpObjective::Maximize);
+    assigned += 10 * a + 20 * b;
+    assigned += (500 * a + 1200 * b).le(10000);
+    assigned += a.le(b);
+
+    let fluent = LpProblem::new("Fluent", LpObjective::Maximize)
+        .with_objective(10 * a + 20 * b)
+        .with_constraint((500 * a + 1200 * b).le(10000))
+        .with_constraint(a.le(b));
+    assert_eq!(fluent.to_lp_file_format(), assigned.to_lp_file_format());
+}
+
+#[test]
+fn test_incidence() {
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+    let z = &LpContinuous::new("z");
+
+    let mut problem = LpProblem::new("Incidence", LpObjective::Maximize);
+    problem += x + y + z;
+    problem += (z + x).le(1);
+    problem += (y + 2 * z).le(3);
+
+    let incidence = problem.incidence().unwrap();
+    assert_eq!(incidence.len(), 2);
+    assert_eq!(incidence["c1"], vec!["x", "z"]);
+    assert_eq!(incidence["c2"], vec!["y", "z"]);
+}
+
+#[test]
+fn test_standard_form() {
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").upper_bound(4.0);
+
+    let mut problem = LpProblem::new("Standard form", LpObjective::Maximize);
+    problem += 3 * y + 2 * x + 1;
+    problem += (x + 2 * y).le(14);
+    problem += (3 * x - y).ge(y - 2);
+    problem += (x - y).equal(1);
+
+    let form = problem.to_standard_form().unwrap();
+    assert_eq!(form.var_names, vec!["x", "y"]);
+    assert_eq!(form.c, vec![2.0, 3.0]);
+    assert_eq!(form.objective_constant, 1.0);
+    assert_eq!(form.a, vec![vec![1.0, 2.0], vec![3.0, -2.0], vec![1.0, -1.0]]);
+    assert_eq!(form.b, vec![14.0, -2.0, 1.0]);
+    assert_eq!(form.senses, vec![Constraint::LessOrEqual, Constraint::GreaterOrEqual, Constraint::Equal]);
+    assert_eq!(form.bounds, vec![(Some(0.0), None), (None, Some(4.0))]);
+}
+
+#[test]
+fn test_sparse_matrix() {
+    let x = LpContinuous::vec("x", 1000);
+
+    let mut problem = LpProblem::new("Sparse", LpObjective::Minimize);
+    problem += lp_sum(&x);
+    for i in 0..10 {
+        problem += (&x[i] + 2 * &x[i + 100] - &x[999]).ge(1);
+    }
+
+    let matrix = problem.to_sparse().unwrap();
+    assert_eq!(matrix.var_names.len(), 1000);
+    assert_eq!(matrix.col_ptr.len(), 1001);
+    assert_eq!(matrix.values.len(), 30);
+    assert_eq!(matrix.row_names.len(), 10);
+
+    // x_999 appears in every row
+    let column = matrix.var_names.iter().position(|name| name == "x_999").unwrap();
+    let (start, end) = (matrix.col_ptr[column], matrix.col_ptr[column + 1]);
+    assert_eq!(matrix.row_indices[start..end].to_vec(), (0..10).collect::<Vec<_>>());
+    assert!(matrix.values[start..end].iter().all(|&v| v == -1.0));
+}
+
+#[test]
+fn test_disjunction_formulation() {
+    let x = &LpContinuous::new("x");
+    let (selectors, constraints) = disjunction(vec![x.le(2), x.ge(12)], 100.0);
+    assert_eq!(selectors.len(), 2);
+    assert_eq!(constraints.len(), 3);
+
+    let mut problem = LpProblem::new("Disjunction", LpObjective::Maximize);
+    problem += x;
+    for constraint in constraints {
+        problem += constraint;
+    }
+    let output = problem.to_lp_file_format();
+    let (a, b) = (&selectors[0].name, &selectors[1].name);
+    assert!(output.contains(&format!("c1: x + 100 {} <= 102", a)));
+    assert!(output.contains(&format!("c2: x - 100 {} >= -88", b)));
+    assert!(output.contains(&format!("c3: {} + {} = 1", a, b)));
+}
+
+#[test]
+fn test_step_function_formulation() {
+    let x = &LpContinuous::new("x");
+    let (cost, steps, constraints) = step_function(x, &[0.0, 10.0, 20.0, 30.0], &[5.0, 3.0, 8.0]);
+    assert_eq!(steps.len(), 3);
+    assert_eq!(constraints.len(), 3);
+
+    let mut problem = LpProblem::new("Steps", LpObjective::Minimize);
+    problem += cost;
+    for constraint in constraints {
+        problem += constraint;
+    }
+    let output = problem.to_lp_file_format();
+    let (a, b, c) = (&steps[0].name, &steps[1].name, &steps[2].name);
+    assert!(output.contains(&format!("obj: 5 {} + 3 {} + 8 {}", a, b, c)));
+    assert!(output.contains(&format!("c2: x - 10 {} - 20 {} >= ", b, c)));
+    assert!(output.contains(&format!("c3: x - 10 {} - 20 {} - 30 {} <= ", a, b, c)));
+}
+
+/// Optimal values of the variables of `problem`, as minilp does not branch on its binaries
+/// `selectors`: the relaxation is solved for every value of the selectors, fixed by equalities
+#[cfg(feature = "minilp")]
+fn solve_over_selectors(problem: &LpProblem, selectors: &[LpBinary]) -> Option<HashMap<String, f64>> {
+    let mut best: Option<(f64, HashMap<String, f64>)> = None;
+    for values in 0..1u32 << selectors.len() {
+        let mut fixed = problem.clone();
+        for (index, selector) in selectors.iter().enumerate() {
+            fixed += selector.equal((values >> index & 1) as f64);
+        }
+        let solution = MiniLpSolver::new().relax_integers().run(&fixed).unwrap();
+        if solution.status != Status::Optimal {
+            continue;
+        }
+        let objective = solution.objective_value(&fixed).unwrap();
+        let better = match &best {
+            Some((best, _)) if problem.objective_type == LpObjective::Maximize => objective > *best,
+            Some((best, _)) => objective < *best,
+            None => true,
+        };
+        if better {
+            best = Some((objective, solution.results.clone()));
+        }
+    }
+    best.map(|(_, results)| results)
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_step_function_minilp() {
+    let x = &LpContinuous::new("x");
+    let (cost, steps, constraints) = step_function(x, &[0.0, 10.0, 20.0, 30.0], &[5.0, 3.0, 8.0]);
+
+    let mut problem = LpProblem::new("Steps", LpObjective::Minimize);
+    problem += cost;
+    for constraint in constraints {
+        problem += constraint;
+    }
+    problem += x.ge(12);
+
+    let results = solve_over_selectors(&problem, &steps).unwrap();
+    assert_eq!(results[&steps[1].name], 1.0);
+    let solution = Solution::with_problem(Status::Optimal, results, &problem);
+    assert_eq!(solution.objective_value(&problem).unwrap(), 3.0);
+}
+
+#[test]
+fn test_either_or_formulation() {
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+    let (z, constraints) = either_or((x + y).le(4), (x - y).ge(6), 50.0);
+    assert_eq!(constraints.len(), 2);
+
+    let mut problem = LpProblem::new("Either or", LpObjective::Maximize);
+    problem += x + y;
+    for constraint in constraints {
+        problem += constraint;
+    }
+    let output = problem.to_lp_file_format();
+    assert!(output.contains(&format!("c1: x + y + 50 {} <= 54", z.name)));
+    assert!(output.contains(&format!("c2: x - y + 50 {} >= 6", z.name)));
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_either_or_minilp() {
+    let x = &LpContinuous::new("x").lower_bound(0.0).upper_bound(10.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0).upper_bound(10.0);
+    let mut problem = LpProblem::new("Either or", LpObjective::Maximize);
+    problem += x + y;
+    let (z, constraints) = either_or((x + y).le(4), (x - y).ge(6), 50.0);
+    for constraint in constraints {
+        problem += constraint;
+    }
+
+    let results = solve_over_selectors(&problem, std::slice::from_ref(&z)).unwrap();
+    // the second branch is enforced, the first one is violated
+    assert_eq!(results[&z.name], 0.0);
+    assert_eq!(results["x"], 10.0);
+    assert_eq!(results["y"], 4.0);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_disjunction_minilp() {
+    let x = &LpContinuous::new("x");
+    let mut problem = LpProblem::new("Disjunction", LpObjective::Maximize);
+    problem += x;
+    problem += x.le(9);
+    // x <= 2 or x >= 12: only the first alternative is compatible with x <= 9
+    let (selectors, constraints) = disjunction(vec![x.le(2), x.ge(12)], 100.0);
+    for constraint in constraints {
+        problem += constraint;
+    }
+
+    let results = solve_over_selectors(&problem, &selectors).unwrap();
+    assert_eq!(results["x"], 2.0);
+    assert_eq!(results[&selectors[0].name], 1.0);
+    assert_eq!(results[&selectors[1].name], 0.0);
+}
+
+#[test]
+fn test_read_objective_constant_and_name() {
+    let content = "Minimize\n  cost: 2 x + 3 y + 10\nSubject To\n  c1: x + y >= 1\nEnd\n";
+    let problem = parse_lp(content, "Read").unwrap();
+    assert_eq!(problem.objective_type, LpObjective::Minimize);
+    assert_eq!(problem.objective_constant, 10.0);
+    assert_eq!(problem.objective_name, Some("cost".to_string()));
+    assert_eq!(problem.constraints.len(), 1);
+
+    let written = problem.to_lp_file_format();
+    assert!(written.contains("cost: 2 x + 3 y + 10"));
+}
+
+#[test]
+fn test_read_written_problem() {
+    let x = &LpContinuous::new("x").upper_bound(4.0);
+    let n = &LpInteger::new("n");
+    let b = &LpBinary::new("b");
+    let mut problem = LpProblem::new("Round trip", LpObjective::Maximize);
+    problem += 3 * x + 2 * n - b;
+    problem += (x + n).le(10);
+    problem += (n - 5 * b).ge(-2);
+    problem.set_objective_constant(-1.5);
+
+    let read = parse_lp(&problem.to_lp_file_format(), "Round trip").unwrap();
+    assert_eq!(read.objective_type, LpObjective::Maximize);
+    assert_eq!(read.objective_constant, -1.5);
+    assert_eq!(read.constraints.len(), 2);
+    let written = read.to_lp_file_format();
+    assert!(written.contains("obj: 3 x + 2 n - b - 1.5"));
+    assert!(written.contains("c1: x + n <= 10"));
+    // variables without a lower bound in the LP file are nonnegative
+    assert!(written.contains("0 <= x <= 4"));
+    assert!(written.contains("0 <= n\n"));
+    assert!(written.contains("Generals\n  n \n"));
+    assert!(written.contains("Binary\n  b \n"));
+}
+
+#[test]
+fn test_free_mps_long_names() {
+    let production = &LpContinuous::new("production_of_the_northern_plant").upper_bound(40.0);
+    let trucks = &LpInteger::new("number_of_delivery_trucks");
+    let open = &LpBinary::new("northern_plant_is_open");
+    let free = &LpContinuous::new("inventory_adjustment_free");
+    let mut problem = LpProblem::new("Plants", LpObjective::Maximize);
+    problem += 3 * production - 5 * trucks - 100 * open + free;
+    problem += (production - 10 * trucks).le(0);
+    problem += (production - 40 * open).le(0);
+    problem += (free + production).equal(12.5);
+    problem.set_objective_constant(7.0);
+
+    // the names overflow the 8 columns of the fixed flavor
+    assert!(problem.to_mps(MpsFlavor::Fixed).is_err());
+
+    let mps = problem.to_mps(MpsFlavor::Free).unwrap();
+    assert!(mps.starts_with("NAME Plants\nOBJSENSE\n    MAX\nROWS\n N  obj\n L  c1\n L  c2\n E  c3\n"));
+    assert!(mps.contains("    production_of_the_northern_plant obj 3\n"));
+    assert!(mps.contains("    MARKER 'MARKER' 'INTORG'\n    northern_plant_is_open obj -100\n"));
+    assert!(mps.contains("    RHS obj -7\n    RHS c3 12.5\n"));
+    assert!(mps.contains(" FR BND inventory_adjustment_free\n"));
+    assert!(mps.contains(" UP BND production_of_the_northern_plant 40\n"));
+    assert!(mps.contains(" BV BND northern_plant_is_open\n"));
+    assert!(mps.ends_with("ENDATA\n"));
+
+    let read = parse_mps(&mps, "Plants").unwrap();
+    assert_eq!(read.objective_type, LpObjective::Maximize);
+    assert_eq!(read.objective_constant, 7.0);
+    assert_eq!(read.constraints.len(), 3);
+    assert_eq!(read.to_mps(MpsFlavor::Free).unwrap(), mps);
+    let written = read.to_lp_file_format();
+    assert!(written.contains("inventory_adjustment_free free"));
+    assert!(written.contains("0 <= production_of_the_northern_plant <= 40"));
+    assert!(written.contains("Generals\n  number_of_delivery_trucks \n"));
+    assert!(written.contains("Binary\n  northern_plant_is_open \n"));
+}
+
+#[test]
+fn test_fixed_mps_columns() {
+    let x = &LpContinuous::new("x").bounded(-2.0, 4.0);
+    let n = &LpInteger::new("n");
+    let mut problem = LpProblem::new("Fixed", LpObjective::Minimize);
+    problem += x + 0.1234567890123 * n;
+    problem += (x + n).ge(1);
+
+    let mps = problem.to_mps(MpsFlavor::Fixed).unwrap();
+    assert!(mps.contains("    n         obj       1.2345679e-1\n"));
+    assert!(mps.contains(" LO BND       x         -2\n"));
+    let read = parse_mps(&mps, "Fixed").unwrap();
+    assert_eq!(read.objective_type, LpObjective::Minimize);
+    assert!(read.to_lp_file_format().contains("-2 <= x <= 4"));
+}
+
+#[test]
+fn test_connected_components() {
+    let a = &LpContinuous::new("a");
+    let b = &LpContinuous::new("b");
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+
+    let mut problem = LpProblem::new("Blocks", LpObjective::Maximize);
+    problem += a + 2 * b + 3 * x + y + 5;
+    problem += (a + b).le(4);
+    problem += (x + y).le(2);
+    problem += (x - y).ge(1);
+    problem += b.le(3);
+
+    let components = problem.connected_components().unwrap();
+    assert_eq!(components.len(), 2);
+    assert_eq!(components[0].constraints.len(), 2);
+    assert_eq!(components[1].constraints.len(), 2);
+    assert_eq!(components[0].objective_constant, 5.0);
+    assert_eq!(components[1].objective_constant, 0.0);
+    assert!(components[0].to_lp_file_format().contains("obj: a + 2 b + 5\n"));
+    assert!(components[1].to_lp_file_format().contains("obj: 3 x + y\n"));
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_solve_connected_components() {
+    let a = &LpContinuous::new("a").lower_bound(0.0);
+    let b = &LpContinuous::new("b").lower_bound(0.0);
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+
+    let mut problem = LpProblem::new("Blocks", LpObjective::Maximize);
+    problem += a + 2 * b + 3 * x + y;
+    problem += (a + b).le(4);
+    problem += b.le(3);
+    problem += (x + y).le(2);
+
+    let solver = MiniLpSolver::new();
+    let components = problem.connected_components().unwrap();
+    let solutions = components.iter().map(|c| solver.run(c).unwrap()).collect();
+    let solution = Solution::combine(&problem, solutions);
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results.len(), 4);
+    assert_eq!(solution.objective_value(&problem).unwrap(), 13.0);
+}
+
+#[test]
+fn test_auto_scale_objective() {
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+
+    let mut problem = LpProblem::new("Scaled", LpObjective::Maximize);
+    problem += 4000000 * x + 1000000 * y + 2000000;
+    problem += (x + y).le(10);
+    assert!(problem.to_lp_file_format().contains("obj: 4000000 x + 1000000 y + 2000000\n"));
+
+    problem.auto_scale_objective = true;
+    assert_eq!(problem.objective_scale(), 4000000.0);
+    assert!(problem.to_lp_file_format().contains("obj: x + 0.25 y + 0.5\n"));
+
+    // the values found by the solver do not depend on the scale of the objective
+    let results: HashMap<String, f64> = vec![("x".to_string(), 10.0), ("y".to_string(), 0.0)].into_iter().collect();
+    let solution = Solution::new(Status::Optimal, results);
+    assert_eq!(solution.objective_value(&problem).unwrap(), 42000000.0);
+}
+
+#[test]
+fn test_assignment_formulation() {
+    let costs = vec![vec![9.0, 2.0, 7.0], vec![6.0, 4.0, 3.0], vec![5.0, 8.0, 1.0]];
+    let (problem, assign) = assignment(&costs);
+    assert_eq!(problem.objective_type, LpObjective::Minimize);
+    assert_eq!(assign.len(), 3);
+    assert_eq!(problem.constraints.len(), 6);
+
+    let lp = problem.to_lp_file_format();
+    assert!(lp.contains("c1: assign_0_0 + assign_0_1 + assign_0_2 = 1"));
+    assert!(lp.contains("c4: assign_0_0 + assign_1_0 + assign_2_0 = 1"));
+    assert!(lp.contains("Binary\n"));
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_assignment_minilp() {
+    let costs = vec![vec![9.0, 2.0, 7.0], vec![6.0, 4.0, 3.0], vec![5.0, 8.0, 1.0]];
+    let (problem, assign) = assignment(&costs);
+
+    // the relaxation of an assignment problem has an integral optimum
+    let solution = MiniLpSolver::new().relax_integers().run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.objective_value(&problem).unwrap(), 9.0);
+    assert!(solution.get_bool(&assign[0][1]));
+    assert!(solution.get_bool(&assign[1][0]));
+    assert!(solution.get_bool(&assign[2][2]));
+}
+
+#[test]
+fn test_write_variable_categories() {
+    let n = &LpInteger::new("n");
+    let m = &LpInteger::new("m").upper_bound(7.0);
+    let b = &LpBinary::new("b");
+    let c = &LpBinary::new("c");
+    let x = &LpContinuous::new("x");
+
+    let mut problem = LpProblem::new("Categories", LpObjective::Maximize);
+    // m and c only appear in the objective
+    problem += n + m + b + c + x;
+    problem += (n + b + x).le(10);
+
+    let lp = problem.to_lp_file_format();
+    let section = |header: &str| -> Vec<String> {
+        let start = lp.find(&format!("\n{}\n", header)).expect("missing section") + header.len() + 2;
+        let end = lp[start..].find("\n\n").map_or(lp.len(), |end| start + end);
+        let mut names: Vec<String> = lp[start..end].split_whitespace().map(|s| s.to_string()).collect();
+        names.sort();
+        names
+    };
+    assert_eq!(section("Generals"), vec!["m", "n"]);
+    assert_eq!(section("Binary"), vec!["b", "c"]);
+    assert!(lp.contains("  m <= 7\n"));
+    assert!(lp.find("\nGenerals\n") < lp.find("\nBinary\n"));
+}
+
+#[test]
+fn test_freeze() {
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").lower_bound(3.0).upper_bound(2.0);
+    let mut problem = LpProblem::new("Conflicting", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x + y).le(10);
+    problem += (x - x).le(1);
+    let errors = problem.freeze().unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].contains("Conflicting bounds for variable y"));
+    assert_eq!(errors[1], "Constraint c2 has no variable and is always satisfied");
+
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let mut problem = LpProblem::new("Frozen", LpObjective::Maximize);
+    problem += x;
+    problem += (2 * x).le(5);
+    let frozen = problem.freeze().unwrap();
+    assert_eq!(frozen.warnings(), &[ModelWarning::UnboundedVariable("x".to_string())]);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_solve_frozen() {
+    let build = || {
+        let x = &LpContinuous::new("x").lower_bound(0.0);
+        let y = &LpContinuous::new("y").lower_bound(0.0);
+        let mut problem = LpProblem::new("Frozen", LpObjective::Maximize);
+        problem += 3 * x + 2 * y;
+        problem += (x + y).le(4);
+        problem += (x + 3 * y).le(6);
+        problem += x.le(3);
+        problem
+    };
+    let solver = MiniLpSolver::new();
+    let problem = build();
+    let expected = solver.run(&problem).unwrap();
+    let frozen = build().freeze().unwrap();
+    let solution = solver.run(&frozen).unwrap();
+    assert_eq!(solution.status, expected.status);
+    assert_eq!(solution.results, expected.results);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_solution_value() {
+    let a = &LpContinuous::new("a");
+    let b = &LpContinuous::new("b");
+    let mut problem = LpProblem::new("Values", LpObjective::Maximize);
+    problem += a + 2 * b;
+    problem += (a + b).le(4);
+    problem += b.le(1);
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert_eq!(solution.value(a), Some(solution.results["a"]));
+    assert_eq!(solution.value(b), Some(solution.results["b"]));
+    assert_eq!(solution.value(&LpContinuous::new("c")), None);
+}
+
+#[test]
+fn test_solution_evaluate() {
+    let a = &LpContinuous::new("a");
+    let b = &LpContinuous::new("b");
+    let mut results = HashMap::new();
+    results.insert("a".to_string(), 1.5);
+    results.insert("b".to_string(), -2.0);
+    let solution = Solution::new(Status::Optimal, results);
+
+    assert_eq!(solution.evaluate(&(2 * a + 3 * b)).unwrap(), -3.0);
+    assert_eq!(solution.evaluate(&(4 * (a - b) + 1)).unwrap(), 15.0);
+    match solution.evaluate(&(a + LpContinuous::new("c"))) {
+        Err(LpError::MissingValue(name)) => assert_eq!(name, "c"),
+        other => panic!("expected a missing value, got {:?}", other),
+    }
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_relax() {
+    let x = &LpBinary::new("x");
+    let y = &LpBinary::new("y");
+    let z = &LpInteger::new("z").lower_bound(0.0).upper_bound(1.0);
+    let mut problem = LpProblem::new("Knapsack", LpObjective::Maximize);
+    problem += 5 * x + 4 * y + 3 * z;
+    problem += (2 * x + 3 * y + z).le(5);
+
+    // the integer optimum is x = y = 1, z = 0
+    let integer_optimum = 9.0;
+    assert!(MiniLpSolver::new().run(&problem).is_err());
+
+    let relaxed = problem.relax();
+    let solution = MiniLpSolver::new().run(&relaxed).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    let bound = solution.objective_value(&relaxed).unwrap();
+    assert!(bound >= integer_optimum - 1e-6);
+    assert!((bound - 32.0 / 3.0).abs() < 1e-5);
+    assert!((solution.results["y"] - 2.0 / 3.0).abs() < 1e-5);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_dual() {
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").lower_bound(1.0).upper_bound(10.0);
+    let z = &LpContinuous::new("z").upper_bound(5.0);
+    let w = &LpContinuous::new("w").upper_bound(0.0);
+
+    // both optima are at x = 3, y = 1, z = 2, w = 0
+    for &(objective, expected) in &[(LpObjective::Minimize, 8.0), (LpObjective::Maximize, -6.0)] {
+        let mut problem = LpProblem::new("Primal", objective);
+        if objective == LpObjective::Minimize {
+            problem += 2 * x + 3 * y - z - w + 1;
+        } else {
+            problem += -2 * x - 3 * y + z + w + 1;
+        }
+        problem += (x + y).ge(4);
+        problem += (x - z + w).le(2);
+        problem += (y + z).equal(3);
+        problem += w.ge(-1);
+
+        let primal = MiniLpSolver::new().run(&problem).unwrap();
+        let dual_problem = problem.dual().unwrap();
+        let dual = MiniLpSolver::new().run(&dual_problem).unwrap();
+        assert_eq!(primal.status, Status::Optimal);
+        assert_eq!(dual.status, Status::Optimal);
+        let optimum = primal.objective_value(&problem).unwrap();
+        assert!((optimum - expected).abs() < 1e-5);
+        assert!((dual.objective_value(&dual_problem).unwrap() - optimum).abs() < 1e-5);
+    }
+
+    let mut problem = LpProblem::new("Integer", LpObjective::Maximize);
+    problem += LpInteger::new("n");
+    problem += LpInteger::new("n").le(3);
+    assert!(matches!(problem.dual(), Err(LpError::Unsupported(_))));
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_solve_lexicographic() {
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+    let mut problem = LpProblem::new("Lexicographic", LpObjective::Maximize);
+    problem += (x + y).le(4);
+    problem += x.le(3);
+    problem += y.le(3);
+    // the secondary objective alone would be optimal at x = 3, y = 0
+    problem.add_objective(1, x - y);
+    problem.add_objective(2, x + y);
+
+    let solution = problem.solve_lexicographic(&MiniLpSolver::new()).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.results["x"] + solution.results["y"] - 4.0).abs() < 1e-5);
+    assert!((solution.results["x"] - 3.0).abs() < 1e-5);
+    assert!((solution.results["y"] - 1.0).abs() < 1e-5);
+
+    let empty = LpProblem::new("Empty", LpObjective::Maximize);
+    assert!(empty.solve_lexicographic(&MiniLpSolver::new()).is_err());
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_compute_iis() {
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+    let mut problem = LpProblem::new("Infeasible", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x + y).le(10);
+    problem += (x - y).ge(3);
+    problem += y.le(5);
+    problem += (x + 2).le(y);
+
+    assert_eq!(compute_iis(&MiniLpSolver::new(), &problem).unwrap(), vec!["c2", "c4"]);
+
+    problem.constraints.pop();
+    assert!(compute_iis(&MiniLpSolver::new(), &problem).is_err());
+
+    // a run without an answer is not taken for a feasible one
+    struct Unsolved;
+    impl SolverTrait for Unsolved {
+        type P = LpProblem;
+        fn run<'a>(&self, problem: &'a LpProblem) -> Result<Solution<'a>, LpError> {
+            Ok(Solution::with_problem(Status::NotSolved, HashMap::new(), problem))
+        }
+    }
+    assert!(matches!(compute_iis(&Unsolved, &problem), Err(LpError::Solver(_))));
+}
+
+#[test]
+fn test_display_problem() {
+    let a = &LpInteger::new("a").lower_bound(0.0).upper_bound(10.0);
+    let b = &LpBinary::new("b");
+    let x = &LpContinuous::new("x");
+    let mut problem = LpProblem::new("Displayed", LpObjective::Minimize);
+    problem += 3 * a + 2 * x + 1;
+    problem += (a + 4 * b + x).ge(2);
+
+    let text = problem.to_string();
+    assert!(text.starts_with("Displayed\nMinimize\n  3 a + 2 x + 1\n"));
+    assert!(text.contains("Subject to\n  c1: a + 4 b + x >= 2\n"));
+    assert!(text.contains("  a: integer, 0 <= a <= 10\n"));
+    assert!(text.contains("  b: binary, 0 <= b <= 1\n"));
+    assert!(text.contains("  x: continuous, x free\n"));
+}
+
+#[test]
+fn test_wrap_long_lines() {
+    let vars = LpInteger::vec("a_rather_long_variable_name", 200);
+    let mut problem = LpProblem::new("Long lines", LpObjective::Minimize);
+    problem += lp_sum(&vars);
+    problem += vars.iter().enumerate().map(|(i, v)| (i as i32 + 1) * v).sum::<LpExpression>().ge(100);
+
+    let written = problem.to_lp_file_format();
+    assert!(written.lines().all(|line| line.len() <= 255));
+    // lines are broken before an operator, never inside a term
+    let rows = written.split("\nGenerals").next().unwrap();
+    let continuations: Vec<&str> = rows.lines().filter(|line| line.starts_with("   ")).collect();
+    assert!(!continuations.is_empty());
+    assert!(continuations.iter().all(|line| line.starts_with("   + ") || line.starts_with("   >= ")));
+    let read = parse_lp(&written, "Long lines").unwrap();
+    assert_eq!(read.constraints.len(), 1);
+    assert_eq!(read.variables().len(), 200);
+}
+
+#[test]
+fn test_write_lp_to() {
+    let a = &LpInteger::new("a");
+    let b = &LpContinuous::new("b").upper_bound(4.0);
+    let mut problem = LpProblem::new("Buffer", LpObjective::Maximize);
+    problem += 2 * a + b;
+    problem += (a + b).le(10);
+
+    let mut buffer: Vec<u8> = Vec::new();
+    problem.write_lp_to(&mut buffer).unwrap();
+    problem.write_lp("write_lp_to.lp").unwrap();
+    let file_content = fs::read("write_lp_to.lp").unwrap();
+    let _ = fs::remove_file("write_lp_to.lp");
+    assert_eq!(buffer, file_content);
+    assert_eq!(String::from_utf8(buffer).unwrap(), problem.to_lp_file_format());
+
+    problem += (f64::NAN * a).ge(0);
+    let mut buffer: Vec<u8> = Vec::new();
+    assert!(problem.write_lp_to(&mut buffer).is_err());
+    assert!(buffer.is_empty());
+}
+
+/// Writer keeping track of the number of bytes and of the largest single write
+#[derive(Default)]
+struct CountingWriter {
+    bytes: usize,
+    largest_write: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes += buf.len();
+        self.largest_write = self.largest_write.max(buf.len());
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_write_lp_to_is_buffered() {
+    let vars = LpContinuous::vec("x", 100);
+    let mut problem = LpProblem::new("Large", LpObjective::Maximize);
+    problem += lp_sum(&vars);
+    for i in 0..100_000 {
+        problem += (&vars[i % 100] + &vars[(i + 1) % 100]).le(i as i32);
+    }
+
+    let mut writer = CountingWriter::default();
+    problem.write_lp_to(&mut writer).unwrap();
+    assert_eq!(writer.bytes, problem.to_lp_file_format().len());
+    // the file reaches the writer in chunks of the default capacity of a BufWriter
+    assert!(writer.bytes > 1_000_000);
+    assert!(writer.largest_write <= 8 * 1024);
+}
+
+#[test]
+fn test_write_non_finite_coefficients() {
+    let x = &LpContinuous::new("x").upper_bound(f64::INFINITY);
+    let y = &LpContinuous::new("y");
+    let mut problem = LpProblem::new("Non-finite", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x + f64::NAN * y).le(1);
+    let error = problem.write_lp("non_finite.lp").unwrap_err();
+    assert_eq!(error.to_string(), "Variable y has a non-finite coefficient NaN");
+    assert!(fs::metadata("non_finite.lp").is_err());
+
+    let mut problem = LpProblem::new("Infinite", LpObjective::Maximize);
+    problem += f64::NEG_INFINITY * x + y;
+    assert_eq!(
+        problem.check_coefficients().unwrap_err().to_string(),
+        "Variable x has a non-finite coefficient -inf"
+    );
+
+    // infinite bounds are allowed
+    let mut problem = LpProblem::new("Infinite bound", LpObjective::Maximize);
+    problem += x + y;
+    assert!(problem.check_coefficients().is_ok());
+}
+
+#[test]
+fn test_fixed_variable_bound() {
+    let a = &LpContinuous::fixed("a", 2.5);
+    let b = &LpInteger::fixed("b", -1.0);
+    let mut problem = LpProblem::new("Fixed", LpObjective::Minimize);
+    problem += a + b;
+
+    let lp = problem.to_lp_file_format();
+    assert!(lp.contains("\n  a = 2.5\n"));
+    assert!(lp.contains("\n  b = -1\n"));
+    assert!(problem.constraints.is_empty());
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_merge() {
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+    let mut production = LpProblem::new("Production", LpObjective::Maximize);
+    production += 3 * x + 1;
+    production += (x + y).le(10);
+
+    // x is only bounded above in the second sub-model
+    let mut transport = LpProblem::new("Transport", LpObjective::Maximize);
+    transport += 2 * y + 2;
+    transport += LpContinuous::new("x").upper_bound(4.0).ge(1);
+
+    production.merge(&transport).unwrap();
+    assert_eq!(production.constraints.len(), 2);
+    assert_eq!(production.objective_constant, 3.0);
+    let lp = production.to_lp_file_format();
+    assert!(lp.contains("0 <= x <= 4"));
+
+    let solution = MiniLpSolver::new().run(&production).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results["x"], 4.0);
+    assert_eq!(solution.results["y"], 6.0);
+    assert_eq!(solution.objective_value(&production).unwrap(), 27.0);
+}
+
+#[test]
+fn test_merge_errors() {
+    let x = &LpContinuous::new("x").lower_bound(5.0);
+    let mut problem = LpProblem::new("Max", LpObjective::Maximize);
+    problem += x;
+
+    let mut minimize = LpProblem::new("Min", LpObjective::Minimize);
+    minimize += x;
+    let error = problem.merge(&minimize).unwrap_err();
+    assert_eq!(error.to_string(), "Cannot merge the objective of Min into Max: their senses differ");
+
+    // without objective, the sense does not matter
+    let mut constraints_only = LpProblem::new("Constraints", LpObjective::Minimize);
+    constraints_only += x.le(10);
+    problem.merge(&constraints_only).unwrap();
+    assert_eq!(problem.objective_type, LpObjective::Maximize);
+
+    let mut conflicting = LpProblem::new("Conflicting", LpObjective::Maximize);
+    conflicting += LpContinuous::new("x").upper_bound(2.0).le(10);
+    match problem.merge(&conflicting) {
+        Err(LpError::ConflictingBounds { name, .. }) => assert_eq!(name, "x"),
+        other => panic!("Unexpected result {:?}", other),
+    }
+    assert_eq!(problem.constraints.len(), 1);
+}
+
+#[test]
+fn test_remove_constraint() {
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+    let mut problem = LpProblem::new("Cuts", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x + y).le(10);
+    problem += (x - y).le(3);
+    problem += x.le(4);
+
+    assert!(problem.remove_constraint_by_name("c2"));
+    problem.write_lp("remove_constraint.lp").unwrap();
+    let lp = fs::read_to_string("remove_constraint.lp").unwrap();
+    let _ = fs::remove_file("remove_constraint.lp");
+    assert!(lp.contains("  c1: x + y <= 10\n"));
+    assert!(lp.contains("  c2: x <= 4\n"));
+    assert!(!lp.contains("x - y"));
+    assert!(!lp.contains("c3"));
+
+    assert!(!problem.remove_constraint_by_name("c3"));
+    assert!(!problem.remove_constraint_by_name("c0"));
+    assert!(!problem.remove_constraint_by_name("x"));
+    assert_eq!(problem.remove_constraint(1).map(|c| c.to_lp_file_format()), Some("x <= 4".to_string()));
+    assert!(problem.remove_constraint(1).is_none());
+    assert_eq!(problem.constraints.len(), 1);
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_set_objective_coefficient() {
+    let x = &LpContinuous::new("x").lower_bound(0.0).upper_bound(3.0);
+    let y = &LpContinuous::new("y").lower_bound(0.0);
+    let mut problem = LpProblem::new("Reoptimize", LpObjective::Maximize);
+    problem += 3 * x + y + 1;
+    problem += (x + y).le(4);
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert_eq!(solution.results["x"], 3.0);
+    assert_eq!(solution.objective_value(&problem).unwrap(), 11.0);
+
+    problem.set_objective_coefficient(x, 0.5).unwrap();
+    assert!(problem.to_lp_file_format().contains("obj: 0.5 x + y + 1"));
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert_eq!(solution.results["x"], 0.0);
+    assert_eq!(solution.results["y"], 4.0);
+    assert_eq!(solution.objective_value(&problem).unwrap(), 5.0);
+}
+
+#[test]
+fn test_set_objective_coefficient_of_nonlinear_objective() {
+    let x = &LpContinuous::new("x");
+    let mut problem = LpProblem::new("Quadratic", LpObjective::Minimize);
+    problem += x * x;
+    let error = problem.set_objective_coefficient(x, 2.0).unwrap_err();
+    assert_eq!(error.to_string(), "Expected a linear objective, found a product of variables");
+}
+
+#[test]
+fn test_lp_precision() {
+    let x = &LpContinuous::new("x").lower_bound(0.123456).upper_bound(2.0 / 3.0);
+    let y = &LpContinuous::new("y");
+    let mut problem = LpProblem::new("Precision", LpObjective::Minimize);
+    problem += 0.3333333333 * x + 0.0001 * y;
+    problem += (1e-7 * x + 2.5e6 * y).ge(1.0 / 7.0);
+
+    let exact = problem.to_lp_file_format();
+    assert!(exact.contains("0.3333333333 x"));
+
+    let problem = problem.with_precision(3);
+    let lp = problem.to_lp_file_format();
+    assert!(lp.contains("  obj: 0.333 x\n"), "{}", lp);
+    assert!(lp.contains("  c1: 2500000 y >= 0.143\n"), "{}", lp);
+    assert!(lp.contains("  0.123 <= x <= 0.667\n"), "{}", lp);
+    // negligible coefficients are dropped
+    assert!(!lp.contains("e-"));
+}
+
+#[test]
+fn test_deterministic_lp_output() {
+    fn build() -> LpProblem {
+        let mut problem = LpProblem::new("Deterministic", LpObjective::Maximize);
+        let xs: Vec<LpContinuous> = (0..20).map(|i| LpContinuous::new(&format!("x{}", i)).upper_bound(i as f64)).collect();
+        let ys = LpInteger::vec("y", 20);
+        let zs = LpBinary::vec("z", 20);
+        problem += lp_sum(&xs) + lp_sum(&ys) + lp_sum(&zs);
+        for ((x, y), z) in xs.iter().zip(ys.iter()).zip(zs.iter()) {
+            problem += (x + 2 * y + 3 * z).le(10);
+        }
+        problem
+    }
+
+    let (first, second) = (build(), build());
+    first.write_lp("deterministic_1.lp").unwrap();
+    second.write_lp("deterministic_2.lp").unwrap();
+    let first_content = fs::read("deterministic_1.lp").unwrap();
+    let second_content = fs::read("deterministic_2.lp").unwrap();
+    let _ = fs::remove_file("deterministic_1.lp");
+    let _ = fs::remove_file("deterministic_2.lp");
+    assert_eq!(first_content, second_content);
+
+    // the sections list the variables by name
+    let lp = String::from_utf8(first_content).unwrap();
+    let bounds: Vec<&str> = lp.lines().filter(|l| l.starts_with("  x") && l.contains("<=")).collect();
+    assert_eq!(bounds[..3], ["  x0 <= 0", "  x1 <= 1", "  x10 <= 10"]);
+    assert!(lp.contains("Generals\n  y_0 y_1 y_10 y_11"));
+    assert!(lp.contains("Binary\n  z_0 z_1 z_10 z_11"));
+}
+
+#[test]
+fn test_constraint_tags() {
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+    let mut problem = LpProblem::new("Tags", LpObjective::Maximize);
+    problem += x + y;
+    problem.add_tagged(&["capacity"], x.le(10));
+    problem += (x + y).le(15);
+    problem.add_tagged(&["demand"], (x + y).ge(2));
+    problem.add_tagged(&["capacity", "plant"], y.le(5));
+
+    let tagged = |problem: &LpProblem, tag: &str| -> Vec<(usize, String)> {
+        problem.constraints_with_tag(tag).into_iter().map(|(i, c)| (i, c.to_lp_file_format())).collect()
+    };
+    assert_eq!(tagged(&problem, "capacity"), vec![(0, "x <= 10".to_string()), (3, "y <= 5".to_string())]);
+    assert_eq!(tagged(&problem, "demand"), vec![(2, "x + y >= 2".to_string())]);
+    assert_eq!(tagged(&problem, "plant"), vec![(3, "y <= 5".to_string())]);
+    assert!(tagged(&problem, "flow").is_empty());
+
+    // the tags are not written
+    let mut untagged = LpProblem::new("Tags", LpObjective::Maximize);
+    untagged += x + y;
+    untagged += x.le(10);
+    untagged += (x + y).le(15);
+    untagged += (x + y).ge(2);
+    untagged += y.le(5);
+    assert_eq!(problem.to_lp_file_format(), untagged.to_lp_file_format());
+
+    // the tags follow the constraints when one is removed
+    problem.remove_constraint(0);
+    assert_eq!(tagged(&problem, "capacity"), vec![(2, "y <= 5".to_string())]);
+    assert_eq!(tagged(&problem, "demand"), vec![(1, "x + y >= 2".to_string())]);
+
+    // and when the constraints are changed directly, or by the transformations of the problem
+    problem.constraints.swap(0, 2);
+    problem.constraints.insert(0, x.ge(1));
+    assert_eq!(tagged(&problem, "capacity"), vec![(1, "y <= 5".to_string())]);
+    assert_eq!(tagged(&problem, "demand"), vec![(2, "x + y >= 2".to_string())]);
+    assert_eq!(tagged(&problem.relax(), "demand"), vec![(2, "x + y >= 2".to_string())]);
+    assert_eq!(tagged(&problem.to_le_form(), "capacity"), vec![(1, "y <= 5".to_string())]);
+    problem.constraints.pop();
+    problem.constraints.pop();
+    assert!(tagged(&problem, "demand").is_empty());
+}
+
+#[cfg(feature = "minilp")]
+#[test]
+fn test_declared_variables() {
+    let x = &LpContinuous::new("x").upper_bound(3.0);
+    let spare = &LpContinuous::new("spare").lower_bound(1.0).upper_bound(2.0);
+    let unbounded = &LpContinuous::new("unbounded");
+    let mut problem = LpProblem::new("Declared", LpObjective::Maximize);
+    problem += x;
+    problem += x.ge(1);
+    problem.declare(x);
+    problem.declare(spare);
+    problem.declare(unbounded);
+
+    assert_eq!(
+        problem.validate().unwrap(),
+        vec![
+            ModelWarning::UnusedDeclaredVariable("spare".to_string()),
+            ModelWarning::UnusedDeclaredVariable("unbounded".to_string()),
+        ]
+    );
+    // the declarations are not written
+    assert!(!problem.to_lp_file_format().contains("spare"));
+
+    let solution = MiniLpSolver::new().run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results["x"], 3.0);
+    assert_eq!(solution.results["spare"], 1.0);
+    assert_eq!(solution.results["unbounded"], 0.0);
+
+    // once used, a declared variable is not reported anymore
+    problem += (x + spare).le(4);
+    assert_eq!(problem.validate().unwrap(), vec![ModelWarning::UnusedDeclaredVariable("unbounded".to_string())]);
+}
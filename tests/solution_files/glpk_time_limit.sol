@@ -0,0 +1,30 @@
+Problem:    
+Rows:       3
+Columns:    3 (3 integer, 0 binary)
+Non-zeros:  8
+Status:     INTEGER NON-OPTIMAL
+Objective:  obj = 90 (MAXimum)
+
+   No.   Row name        Activity     Lower bound   Upper bound
+------ ------------    ------------- ------------- -------------
+     1 c1                       6000                       10000 
+     2 c2                         10                          10 
+     3 c3                         -5                           0 
+
+   No. Column name       Activity     Lower bound   Upper bound
+------ ------------    ------------- ------------- -------------
+     1 a            *              0             0               
+     2 b            *              5             0               
+     3 c            *              0             0               
+
+Integer feasibility conditions:
+
+KKT.PE: max.abs.err = 0.00e+00 on row 0
+        max.rel.err = 0.00e+00 on row 0
+        High quality
+
+KKT.PB: max.abs.err = 0.00e+00 on row 0
+        max.rel.err = 0.00e+00 on row 0
+        High quality
+
+End of output
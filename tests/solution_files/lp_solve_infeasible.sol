@@ -0,0 +1,2 @@
+
+This problem is infeasible
@@ -0,0 +1,10 @@
+
+Value of objective function: 9.00000000
+
+Actual values of the variables:
+x                               1
+y                               1
+z                               0
+
+Actual values of the constraints:
+c1                              5
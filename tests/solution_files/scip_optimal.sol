@@ -0,0 +1,4 @@
+solution status: optimal solution found
+objective value:                                    9
+x                                                   1 	(obj:5)
+y                                                   1 	(obj:4)
@@ -0,0 +1,3 @@
+solution status: time limit reached
+objective value:                                 12.5
+a                                                 2.5 	(obj:5)
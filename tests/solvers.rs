@@ -1,19 +1,69 @@
 extern crate lp_modeler;
 
 use lp_modeler::solvers::*;
+use lp_modeler::dsl::*;
+#[cfg(feature = "gurobi")]
+use lp_modeler::format::lp_format::LpFileFormat;
+#[cfg(any(feature = "cbc", feature = "gurobi"))]
+use lp_modeler::format::lp_format::sanitized_names;
+use std::collections::HashMap;
+#[cfg(any(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"))]
 use std::fs;
+#[cfg(feature = "glpk")]
+use std::process::Command;
+#[cfg(any(feature = "glpk", feature = "gurobi"))]
+use std::time::Duration;
 
+/// Files of `dir` written for runs on `problem`, named after its unique name
+#[cfg(feature = "cbc")]
+fn run_files(dir: &std::path::Path, problem: &LpProblem) -> Vec<String> {
+    fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with(&problem.unique_name))
+        .collect()
+}
+
+#[cfg(feature = "glpk")]
+fn available(command: &str) -> bool {
+    Command::new(command).arg("--version").output().is_ok()
+}
+
+#[cfg(feature = "cbc")]
 #[test]
 fn cbc_optimal() {
     let _ = fs::copy("tests/solution_files/cbc_optimal.sol", "cbc_optimal.sol");
     let solver = CbcSolver::new().with_temp_solution_file("cbc_optimal.sol".to_string());
-    let Solution { status, results: mut variables, related_problem: _ } = solver.read_solution(&"cbc_optimal.sol".to_string(), None).unwrap();
+    let Solution { status, results: mut variables, .. } = solver.read_solution(&"cbc_optimal.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Optimal);
     assert_eq!(variables.remove("a"), Some(5f64));
     assert_eq!(variables.remove("b"), Some(6f64));
     assert_eq!(variables.remove("c"), Some(0f64));
 }
 
+#[cfg(feature = "cbc")]
+#[test]
+fn cbc_declared_variables() {
+    let a = &LpInteger::new("a");
+    let b = &LpInteger::new("b");
+    let c = &LpInteger::new("c");
+    let mut problem = LpProblem::new("Declared", LpObjective::Minimize);
+    problem += -10 * a - 20 * b;
+    problem += (a + b + c).le(11);
+    problem.declare(a);
+    problem.declare(&LpInteger::new("d").lower_bound(1.5).upper_bound(4.0));
+    problem.declare(&LpContinuous::new("e").upper_bound(-2.5));
+
+    let _ = fs::copy("tests/solution_files/cbc_optimal.sol", "cbc_declared.sol");
+    let solver = CbcSolver::new();
+    let solution = solver.read_solution(&"cbc_declared.sol".to_string(), Some(&problem)).unwrap();
+    assert_eq!(solution.results["a"], 5.0);
+    assert_eq!(solution.results["d"], 2.0);
+    assert_eq!(solution.results["e"], -2.5);
+}
+
+#[cfg(feature = "cbc")]
 #[test]
 fn cbc_infeasible() {
     let _ = fs::copy(
@@ -21,7 +71,7 @@ fn cbc_infeasible() {
         "cbc_infeasible.sol",
     );
     let solver = CbcSolver::new().with_temp_solution_file("cbc_infeasible.sol".to_string());
-    let Solution { status, results: _, related_problem: _ } = solver.read_solution(&"cbc_infeasible.sol".to_string(), None).unwrap();
+    let Solution { status, results: _, .. } = solver.read_solution(&"cbc_infeasible.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Infeasible);
 }
 
@@ -35,12 +85,13 @@ fn cbc_infeasible() {
 // binaries
 //   a b
 // end
+#[cfg(feature = "cbc")]
 fn cbc_infeasible_alternative_format() {
     let _ = fs::copy(
         "tests/solution_files/cbc_infeasible_alternative_format.sol",
         "cbc_infeasible_alternative_format.sol",
     );
-    let Solution { status, results: mut variables, related_problem: _ } = CbcSolver::new()
+    let Solution { status, results: mut variables, .. } = CbcSolver::new()
         .with_temp_solution_file("cbc_infeasible_alternative_format.sol".to_string())
         .read_solution(&"cbc_infeasible_alternative_format.sol".to_string(), None)
         .unwrap();
@@ -49,6 +100,7 @@ fn cbc_infeasible_alternative_format() {
     assert_eq!(variables.remove("b"), Some(0f64));
 }
 
+#[cfg(feature = "cbc")]
 #[test]
 fn cbc_unbounded() {
     let _ = fs::copy(
@@ -56,21 +108,24 @@ fn cbc_unbounded() {
         "cbc_unbounded.sol",
     );
     let solver = CbcSolver::new().with_temp_solution_file("cbc_unbounded.sol".to_string());
-    let Solution { status, results: _, related_problem: _ } = solver.read_solution(&"cbc_unbounded.sol".to_string(), None).unwrap();
+    let Solution { status, results: _, .. } = solver.read_solution(&"cbc_unbounded.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Unbounded);
 }
 
+#[cfg(feature = "glpk")]
 #[test]
 fn glpk_optimal() {
     let _ = fs::copy("tests/solution_files/glpk_optimal.sol", "glpk_optimal.sol");
     let solver = GlpkSolver::new().with_temp_solution_file("glpk_optimal.sol".to_string());
-    let Solution { status, results: mut variables, related_problem: _ } = solver.read_solution(&"glpk_optimal.sol".to_string(), None).unwrap();
+    let Solution { status, results: mut variables, objective, .. } = solver.read_solution(&"glpk_optimal.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Optimal);
+    assert_eq!(objective, Some(100.0));
     assert_eq!(variables.remove("a"), Some(0f64));
     assert_eq!(variables.remove("b"), Some(5f64));
     assert_eq!(variables.remove("c"), Some(0f64));
 }
 
+#[cfg(feature = "glpk")]
 #[test]
 fn glpk_infeasible() {
     let _ = fs::copy(
@@ -78,10 +133,22 @@ fn glpk_infeasible() {
         "glpk_infeasible.sol",
     );
     let solver = GlpkSolver::new().with_temp_solution_file("glpk_infeasible.sol".to_string());
-    let Solution { status, results: _, related_problem: _ } = solver.read_solution(&"glpk_infeasible.sol".to_string(), None).unwrap();
+    let Solution { status, results: _, objective, .. } = solver.read_solution(&"glpk_infeasible.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Infeasible);
+    assert_eq!(objective, None);
+}
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_objective() {
+    assert_eq!(parse_glpk_objective("Objective:  obj = 100 (MAXimum)"), Some(100.0));
+    assert_eq!(parse_glpk_objective("Objective:  obj = -2.5e+10 (MINimum)"), Some(-2.5e10));
+    // objective without a name
+    assert_eq!(parse_glpk_objective("Objective:  12.5 (MINimum)"), Some(12.5));
+    assert_eq!(parse_glpk_objective("Status:     OPTIMAL"), None);
 }
 
+#[cfg(feature = "glpk")]
 #[test]
 fn glpk_unbounded() {
     let _ = fs::copy(
@@ -89,10 +156,11 @@ fn glpk_unbounded() {
         "glpk_unbounded.sol",
     );
     let solver = GlpkSolver::new().with_temp_solution_file("glpk_unbounded.sol".to_string());
-    let Solution { status, results: _, related_problem: _ } = solver.read_solution(&"glpk_unbounded.sol".to_string(), None).unwrap();
+    let Solution { status, results: _, .. } = solver.read_solution(&"glpk_unbounded.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Unbounded);
 }
 
+#[cfg(feature = "glpk")]
 #[test]
 fn glpk_empty_col_bounds() {
     let _ = fs::copy(
@@ -100,8 +168,691 @@ fn glpk_empty_col_bounds() {
         "glpk_empty_col_bounds.sol",
     );
     let solver = GlpkSolver::new().with_temp_solution_file("glpk_empty_col_bounds.sol".to_string());
-    let Solution { status, results: solution, related_problem: _} = solver.read_solution(&"glpk_empty_col_bounds.sol".to_string(), None).unwrap();
+    let Solution { status, results: solution, objective, .. } = solver.read_solution(&"glpk_empty_col_bounds.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Optimal);
+    assert_eq!(objective, Some(1.0));
     assert_eq!(1.0, *solution.get("a").unwrap());
     assert_eq!(0.0, *solution.get("b").unwrap());
 }
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_ranges_of_mip() {
+    let x = &LpInteger::new("x");
+    let y = &LpContinuous::new("y");
+    let mut problem = LpProblem::new("ranges", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x + y).le(4);
+
+    let solver = GlpkSolver::new().with_ranges("glpk_ranges_of_mip.txt".to_string());
+    match solver.run(&problem) {
+        Err(lp_modeler::error::LpError::Unsupported(message)) => {
+            assert_eq!(message, "GLPK cannot compute the sensitivity ranges of a MIP, found integer variables x")
+        }
+        other => panic!("Unexpected result {:?}", other.map(|s| s.status)),
+    }
+    // the relaxed problem is analysed
+    let relaxed = solver.with_lp_relaxation().run(&problem);
+    let _ = fs::remove_file("glpk_ranges_of_mip.txt");
+    assert!(!matches!(relaxed, Err(lp_modeler::error::LpError::Unsupported(_))));
+}
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_ranges() {
+    let solver = GlpkSolver::new();
+    let sensitivity = solver.read_ranges("tests/solution_files/glpk_ranges.txt").unwrap();
+    assert_eq!(sensitivity.constraints.len(), 2);
+    assert_eq!(sensitivity.variables.len(), 2);
+
+    let c1 = &sensitivity.constraints["c1"];
+    assert_eq!(c1.status, "NU");
+    assert_eq!(c1.activity, 4.0);
+    assert_eq!(c1.marginal, 2.0);
+    assert_eq!(c1.bounds, (f64::NEG_INFINITY, 4.0));
+    assert_eq!(c1.activity_range, (3.0, 4.33333));
+    assert_eq!(c1.objective_value_range, (9.0, 11.66667));
+    assert_eq!(c1.limiting, (Some("y".to_string()), Some("c2".to_string())));
+
+    let x = &sensitivity.variables["x"];
+    assert_eq!(x.slack_or_cost, 3.0);
+    assert_eq!(x.objective_coefficient_range, (2.0, f64::INFINITY));
+
+    let long = &sensitivity.variables["a_very_long_variable_name"];
+    assert_eq!(long.status, "BS");
+    assert_eq!(long.objective_coefficient_range, (0.0, 3.0));
+    assert_eq!(long.limiting, (Some("c1".to_string()), None));
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn gurobi_warm_start_file() {
+    let start: HashMap<String, f64> = vec![("y".to_string(), 0.0), ("x".to_string(), 1.0)]
+        .into_iter()
+        .collect();
+    let solver = GurobiSolver::new().with_warm_start(start);
+    solver.write_warm_start("gurobi_warm_start.mst", &HashMap::new()).unwrap();
+    let content = fs::read_to_string("gurobi_warm_start.mst").unwrap();
+    let _ = fs::remove_file("gurobi_warm_start.mst");
+    assert_eq!(content, "# MIP start\nx 1\ny 0\n");
+
+    // the names are those written in the LP file
+    let mut problem = LpProblem::new("Start", LpObjective::Maximize);
+    problem += &LpContinuous::new("my var") + &LpContinuous::new("x");
+    let solver = solver.with_warm_start(vec![("my var".to_string(), 2.0)].into_iter().collect());
+    solver.write_warm_start("gurobi_sanitized_start.mst", &sanitized_names(&problem)).unwrap();
+    let content = fs::read_to_string("gurobi_sanitized_start.mst").unwrap();
+    let _ = fs::remove_file("gurobi_sanitized_start.mst");
+    assert_eq!(content, "# MIP start\nmy_var 2\n");
+}
+
+#[cfg(feature = "cbc")]
+#[test]
+fn cbc_warm_start_file() {
+    let start: HashMap<String, f64> = vec![("a".to_string(), 5.0), ("b".to_string(), 2.5)]
+        .into_iter()
+        .collect();
+    let solver = CbcSolver::new().with_warm_start(start);
+    solver.write_warm_start("cbc_warm_start.sol", &HashMap::new()).unwrap();
+    let content = fs::read_to_string("cbc_warm_start.sol").unwrap();
+    let _ = fs::remove_file("cbc_warm_start.sol");
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[1].split_whitespace().collect::<Vec<_>>(), vec!["0", "a", "5"]);
+    assert_eq!(lines[2].split_whitespace().collect::<Vec<_>>(), vec!["1", "b", "2.5"]);
+
+    let mut problem = LpProblem::new("Start", LpObjective::Maximize);
+    problem += &LpContinuous::new("a+b") + &LpContinuous::new("a");
+    let solver = solver.with_warm_start(vec![("a+b".to_string(), 1.0)].into_iter().collect());
+    solver.write_warm_start("cbc_sanitized_start.sol", &sanitized_names(&problem)).unwrap();
+    let content = fs::read_to_string("cbc_sanitized_start.sol").unwrap();
+    let _ = fs::remove_file("cbc_sanitized_start.sol");
+    assert_eq!(content.lines().nth(1).unwrap().split_whitespace().collect::<Vec<_>>(), vec!["0", "a_b", "1"]);
+}
+
+#[test]
+fn solution_to_csv() {
+    let n = &LpInteger::new("n");
+    let x = &LpContinuous::new("x");
+    let mut problem = LpProblem::new("Csv", LpObjective::Maximize);
+    problem += n + x;
+    problem += (n + x).le(3.5);
+
+    let results: HashMap<String, f64> = vec![("x".to_string(), 0.5), ("n".to_string(), 2.9999999)].into_iter().collect();
+    let solution = Solution::with_problem(Status::Optimal, results, &problem);
+    let mut buffer = Vec::new();
+    solution.to_csv(&mut buffer).unwrap();
+    let csv = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines, vec!["variable,value", "n,3", "x,0.5"]);
+
+    // the category is unknown without the problem
+    let solution = Solution::new(Status::Optimal, vec![("n,m".to_string(), 2.9999999)].into_iter().collect());
+    let mut buffer = Vec::new();
+    solution.to_csv(&mut buffer).unwrap();
+    assert_eq!(String::from_utf8(buffer).unwrap(), "variable,value\n\"n,m\",2.9999999\n");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn solution_to_json() {
+    let x = &LpContinuous::new("x");
+    let y = &LpContinuous::new("y");
+    let mut problem = LpProblem::new("Json", LpObjective::Maximize);
+    problem += 2 * x + y + 1;
+    problem += (x + y).le(4);
+
+    let results: HashMap<String, f64> = vec![("y".to_string(), 0.0), ("x".to_string(), 4.0)].into_iter().collect();
+    let solution = Solution::with_problem(Status::Optimal, results, &problem);
+    assert_eq!(solution.to_json(), r#"{"objective":9.0,"status":"Optimal","variables":{"x":4.0,"y":0.0}}"#);
+
+    // the objective reported by the solver is preferred
+    let solution = Solution { objective: Some(8.5), status: Status::SubOptimal, ..solution };
+    let json = solution.to_json();
+    assert!(json.contains(r#""status":"SubOptimal""#), "{}", json);
+    assert!(json.contains(r#""objective":8.5"#), "{}", json);
+    assert!(json.contains(r#""x":4.0"#), "{}", json);
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn gurobi_solution_round_trip() {
+    let results: HashMap<String, f64> = vec![("a".to_string(), 5.0), ("b".to_string(), -0.25), ("c".to_string(), 0.0)]
+        .into_iter()
+        .collect();
+    let solution = Solution::new(Status::Optimal, results.clone());
+    solution.write_gurobi_format("gurobi_round_trip.sol").unwrap();
+
+    let solver = GurobiSolver::new();
+    let Solution { status, results: parsed, .. } = solver.read_solution(&"gurobi_round_trip.sol".to_string(), None).unwrap();
+    assert_eq!(status, Status::Optimal);
+    assert_eq!(parsed, results);
+
+    // the objective value written in the header includes the objective constant
+    let a = &LpContinuous::new("a");
+    let mut problem = LpProblem::new("Constant", LpObjective::Maximize);
+    problem += 2 * a;
+    problem.set_objective_constant(1.0);
+    let solution = Solution::with_problem(Status::Optimal, results, &problem);
+    assert_eq!(solution.eval(), Some(11.0));
+    solution.write_gurobi_format("gurobi_objective_constant.sol").unwrap();
+    let content = fs::read_to_string("gurobi_objective_constant.sol").unwrap();
+    let _ = fs::remove_file("gurobi_objective_constant.sol");
+    assert!(content.contains("# Objective value = 11\n"), "{}", content);
+}
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_solution_file_per_run() {
+    let solver = GlpkSolver::new();
+    assert_eq!(solver.arguments("problem_1.lp"), vec!["--lp", "problem_1.lp", "-o", "problem_1.sol"]);
+    assert_eq!(solver.clone().arguments("problem_2.lp")[3], "problem_2.sol");
+}
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_lp_relaxation() {
+    let solver = GlpkSolver::new().with_temp_solution_file("relaxation.sol".to_string());
+    let solution_file = std::env::temp_dir().join("relaxation.sol").to_string_lossy().into_owned();
+    assert_eq!(solver.arguments("model.lp"), vec!["--lp", "model.lp", "-o", &solution_file]);
+    let solver = solver.with_lp_relaxation();
+    assert_eq!(solver.arguments("model.lp"), vec!["--lp", "model.lp", "--nomip", "-o", &solution_file]);
+
+    if !available("glpsol") {
+        return;
+    }
+    let a = &LpInteger::new("a").lower_bound(0.0);
+    let mut problem = LpProblem::new("Relaxation", LpObjective::Maximize);
+    problem += a;
+    problem += (2 * a).le(5);
+    let solution = solver.run(&problem).unwrap();
+    assert_eq!(solution.results["a"], 2.5);
+}
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_initial_basis() {
+    let solver = GlpkSolver::new().with_temp_solution_file("basis.sol".to_string());
+    let solution_file = std::env::temp_dir().join("basis.sol").to_string_lossy().into_owned();
+    assert_eq!(
+        solver.with_saved_basis("glpk.bas".to_string()).arguments("model.lp"),
+        vec!["--lp", "model.lp", "-w", "glpk.bas", "-o", &solution_file]
+    );
+    assert_eq!(
+        solver.with_initial_basis("glpk.bas".to_string()).arguments("model.lp"),
+        vec!["--lp", "model.lp", "--ini", "glpk.bas", "-o", &solution_file]
+    );
+
+    if !available("glpsol") {
+        return;
+    }
+    let a = &LpContinuous::new("a");
+    let b = &LpContinuous::new("b");
+    let mut problem = LpProblem::new("Basis", LpObjective::Maximize);
+    problem += 3 * a + 2 * b;
+    problem += (a + b).le(4);
+    problem += (a + 3 * b).le(6);
+    problem += a.ge(0);
+    problem += b.ge(0);
+
+    let first = GlpkSolver::new().with_saved_basis("glpk_basis.bas".to_string()).run(&problem).unwrap();
+    let second = GlpkSolver::new().with_initial_basis("glpk_basis.bas".to_string()).run(&problem).unwrap();
+    let _ = fs::remove_file("glpk_basis.bas");
+    assert_eq!(first.status, Status::Optimal);
+    assert_eq!(first.results, second.results);
+}
+
+#[test]
+fn solution_norms() {
+    let results: HashMap<String, f64> = vec![("a".to_string(), 3.0), ("b".to_string(), -4.0), ("c".to_string(), 0.0)]
+        .into_iter()
+        .collect();
+    let solution = Solution::new(Status::Optimal, results);
+    assert_eq!(solution.l1_norm(), 7.0);
+    assert_eq!(solution.l2_norm(), 5.0);
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn gurobi_mip_gap() {
+    let output = "\
+Explored 1534 nodes (10520 simplex iterations) in 10.02 seconds
+Thread count was 8 (of 8 available processors)
+
+Solution count 3: 12 11 9
+
+Time limit reached
+Best objective 1.200000000000e+01, best bound 1.500000000000e+01, gap 25.0000%
+";
+    let (gap, bound) = parse_mip_gap(output);
+    assert_eq!(gap, Some(0.25));
+    assert_eq!(bound, Some(15.0));
+
+    let output = "Solved in 2 iterations and 0.00 seconds\nOptimal objective  1.200000000e+01\n";
+    assert_eq!(parse_mip_gap(output), (None, None));
+}
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_time_limit() {
+    let _ = fs::copy("tests/solution_files/glpk_time_limit.sol", "glpk_time_limit.sol");
+    let solver = GlpkSolver::new().with_temp_solution_file("glpk_time_limit.sol".to_string());
+    let Solution { status, results: mut variables, .. } = solver.read_solution(&"glpk_time_limit.sol".to_string(), None).unwrap();
+    // the incumbent is kept, and the run reports the time limit from the glpsol output
+    assert_eq!(status, Status::SubOptimal);
+    assert_eq!(variables.remove("b"), Some(5f64));
+    assert!(glpk_time_limit_reached("+   412: mip =   9.000000000e+01 <=   1.000000000e+02  10.0% (12; 0)\nTIME LIMIT EXCEEDED; SEARCH TERMINATED\n"));
+    assert!(!glpk_time_limit_reached("INTEGER OPTIMAL SOLUTION FOUND\n"));
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn gurobi_time_limit() {
+    let output = "\
+Solution count 3: 12 11 9
+
+Time limit reached
+Best objective 1.200000000000e+01, best bound 1.500000000000e+01, gap 25.0000%
+";
+    assert_eq!(parse_gurobi_status(output), Status::TimeLimit);
+    assert_eq!(parse_gurobi_status("Solution count 0\n\nTime limit reached\n"), Status::NotSolved);
+    assert_eq!(parse_gurobi_status("Optimal solution found (tolerance 1.00e-04)\n"), Status::Optimal);
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn gurobi_statuses() {
+    let cases = [
+        ("Solved in 2 iterations and 0.00 seconds\nOptimal objective  1.200000000e+01\n", Status::Optimal),
+        ("Solution count 2: 12 9\n\nOptimal solution found (tolerance 1.00e-04)\n", Status::Optimal),
+        ("Solved in 0 iterations and 0.00 seconds\nInfeasible model\n", Status::Infeasible),
+        ("Solved in 0 iterations and 0.00 seconds\nUnbounded model\n", Status::Unbounded),
+        ("Solved in 0 iterations and 0.00 seconds\nInfeasible or unbounded model\n", Status::Infeasible),
+        ("Status: INF_OR_UNBD\n", Status::Infeasible),
+        ("Solution count 1: 9\n\nSub-optimal termination - objective 9.00000000e+00\n", Status::SubOptimal),
+        ("Status: SUBOPTIMAL\n", Status::SubOptimal),
+        ("Solution count 1: 9\n\nNumerical trouble encountered\n", Status::SubOptimal),
+        ("Solution count 0\n\nNumeric error\n", Status::NotSolved),
+        ("Solution count 1: 9\n\nSolve interrupted\n", Status::SubOptimal),
+        ("Solution count 0\n\nSolve interrupted\n", Status::NotSolved),
+        ("Solution count 10: 12 11 10 9 8 7 6 5 4 3\n\nTime limit reached\n", Status::TimeLimit),
+    ];
+    for (output, status) in cases.iter() {
+        assert_eq!(&parse_gurobi_status(output), status, "{}", output);
+    }
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn gurobi_objective() {
+    let output = "Solved in 2 iterations and 0.00 seconds\nOptimal objective  1.200000000e+01\n";
+    assert_eq!(parse_gurobi_objective(output), Some(12.0));
+    let output = "Solution count 2: 12 9\n\nOptimal solution found (tolerance 1.00e-04)\n\
+                  Best objective 1.200000000000e+01, best bound 1.200000000000e+01, gap 0.0000%\n";
+    assert_eq!(parse_gurobi_objective(output), Some(12.0));
+    let output = "Solution count 0\n\nTime limit reached\nBest objective -, best bound 1.500000000000e+01, gap -\n";
+    assert_eq!(parse_gurobi_objective(output), None);
+    assert_eq!(parse_gurobi_objective("Infeasible model\n"), None);
+}
+
+#[cfg(feature = "scip")]
+#[test]
+fn scip_optimal() {
+    let _ = fs::copy("tests/solution_files/scip_optimal.sol", "scip_optimal.sol");
+    let x = &LpBinary::new("x");
+    let y = &LpBinary::new("y");
+    let z = &LpBinary::new("z");
+    let mut problem = LpProblem::new("Knapsack", LpObjective::Maximize);
+    problem += 5 * x + 4 * y + 3 * z;
+    problem += (2 * x + 3 * y + z).le(5);
+
+    let solver = ScipSolver::new().with_temp_solution_file("scip_optimal.sol".to_string());
+    let Solution { status, results: mut variables, .. } = solver.read_solution(&"scip_optimal.sol".to_string(), Some(&problem)).unwrap();
+    assert_eq!(status, Status::Optimal);
+    assert_eq!(variables.remove("x"), Some(1f64));
+    assert_eq!(variables.remove("y"), Some(1f64));
+    // variables at zero are left out of the file
+    assert_eq!(variables.remove("z"), Some(0f64));
+}
+
+#[cfg(feature = "scip")]
+#[test]
+fn scip_infeasible() {
+    let _ = fs::copy("tests/solution_files/scip_infeasible.sol", "scip_infeasible.sol");
+    let solver = ScipSolver::new().with_temp_solution_file("scip_infeasible.sol".to_string());
+    let Solution { status, results, .. } = solver.read_solution(&"scip_infeasible.sol".to_string(), None).unwrap();
+    assert_eq!(status, Status::Infeasible);
+    assert!(results.is_empty());
+}
+
+#[cfg(feature = "scip")]
+#[test]
+fn scip_time_limit() {
+    let _ = fs::copy("tests/solution_files/scip_time_limit.sol", "scip_time_limit.sol");
+    let solver = ScipSolver::new().with_temp_solution_file("scip_time_limit.sol".to_string());
+    let Solution { status, results: mut variables, .. } = solver.read_solution(&"scip_time_limit.sol".to_string(), None).unwrap();
+    assert_eq!(status, Status::TimeLimit);
+    assert_eq!(variables.remove("a"), Some(2.5));
+    assert_eq!(parse_scip_status("time limit reached", false), Status::NotSolved);
+    assert_eq!(parse_scip_status("unbounded", true), Status::Unbounded);
+    assert_eq!(parse_scip_status("gap limit reached", true), Status::SubOptimal);
+}
+
+#[cfg(feature = "scip")]
+#[test]
+fn scip_arguments() {
+    let solver = ScipSolver::new().with_temp_solution_file("model.sol".to_string()).with_work_dir("");
+    assert_eq!(
+        solver.with_max_seconds(10).arguments("model.lp"),
+        vec![
+            "-c".to_string(),
+            r#"read "model.lp" set limits time 10 optimize write solution "model.sol" quit"#.to_string(),
+        ]
+    );
+    assert_eq!(solver.arguments("model.lp")[1], r#"read "model.lp" optimize write solution "model.sol" quit"#);
+    // the solution is written in the temporary directory by default
+    let solver = ScipSolver::new().with_temp_solution_file("model.sol".to_string());
+    assert_eq!(
+        solver.arguments("model.lp")[1],
+        format!(r#"read "model.lp" optimize write solution "{}" quit"#, std::env::temp_dir().join("model.sol").display())
+    );
+    // paths with spaces stay single words of the shell
+    let solver = ScipSolver::new().with_work_dir("my models");
+    let file_model = std::path::Path::new("my models").join("problem.lp").to_string_lossy().into_owned();
+    let solution_file = std::path::Path::new("my models").join("problem.sol").to_string_lossy().into_owned();
+    assert_eq!(
+        solver.arguments(&file_model)[1],
+        format!(r#"read "{}" optimize write solution "{}" quit"#, file_model, solution_file)
+    );
+}
+
+#[cfg(feature = "lpsolve")]
+#[test]
+fn lp_solve_optimal() {
+    let _ = fs::copy("tests/solution_files/lp_solve_optimal.sol", "lp_solve_optimal.sol");
+    let solver = LpSolveSolver::new().with_temp_solution_file("lp_solve_optimal.sol".to_string());
+    let Solution { status, results: mut variables, .. } = solver.read_solution(&"lp_solve_optimal.sol".to_string(), None).unwrap();
+    assert_eq!(status, Status::Optimal);
+    assert_eq!(variables.remove("x"), Some(1f64));
+    assert_eq!(variables.remove("y"), Some(1f64));
+    assert_eq!(variables.remove("z"), Some(0f64));
+    // the constraint activities are not variables
+    assert!(variables.is_empty());
+}
+
+#[cfg(feature = "lpsolve")]
+#[test]
+fn lp_solve_infeasible() {
+    let _ = fs::copy("tests/solution_files/lp_solve_infeasible.sol", "lp_solve_infeasible.sol");
+    let solver = LpSolveSolver::new().with_temp_solution_file("lp_solve_infeasible.sol".to_string());
+    let Solution { status, .. } = solver.read_solution(&"lp_solve_infeasible.sol".to_string(), None).unwrap();
+    assert_eq!(status, Status::Infeasible);
+}
+
+#[cfg(feature = "lpsolve")]
+#[test]
+fn lp_solve_unbounded() {
+    let _ = fs::copy("tests/solution_files/lp_solve_unbounded.sol", "lp_solve_unbounded.sol");
+    let solver = LpSolveSolver::new().with_temp_solution_file("lp_solve_unbounded.sol".to_string());
+    let Solution { status, .. } = solver.read_solution(&"lp_solve_unbounded.sol".to_string(), None).unwrap();
+    assert_eq!(status, Status::Unbounded);
+}
+
+#[cfg(feature = "lpsolve")]
+#[test]
+fn lp_solve_arguments() {
+    let solver = LpSolveSolver::new().with_max_seconds(10);
+    assert_eq!(
+        solver.arguments("model.lp"),
+        vec!["-S3", "-timeout", "10", "-rxli", "xli_CPLEX", "model.lp"]
+    );
+    let solver = LpSolveSolver::new().xli_name("/opt/lp_solve/libxli_CPLEX.so".to_string());
+    assert_eq!(solver.arguments("model.lp")[2], "/opt/lp_solve/libxli_CPLEX.so");
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn gurobi_threads() {
+    let solver = GurobiSolver::new().with_nb_threads(4);
+    assert_eq!(solver.nb_threads(), Some(4));
+    let arguments = solver.arguments("model.lp", "model.mst");
+    assert!(arguments.contains(&"Threads=4".to_string()));
+    assert_eq!(arguments.last(), Some(&"model.lp".to_string()));
+    assert!(!GurobiSolver::new().arguments("model.lp", "model.mst").iter().any(|a| a.starts_with("Threads")));
+}
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_threads_ignored() {
+    let solver = GlpkSolver::new();
+    let threaded = solver.with_nb_threads(4);
+    assert_eq!(threaded.nb_threads(), Some(4));
+    assert_eq!(threaded.arguments("model.lp").len(), solver.arguments("model.lp").len());
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn round_near_integer_values() {
+    let x = &LpInteger::new("x");
+    let y = &LpInteger::new("y");
+    let z = &LpContinuous::new("z");
+    let mut problem = LpProblem::new("Rounding", LpObjective::Maximize);
+    problem += x + y + z;
+    problem += (x + y + z).le(10);
+
+    let content = "# Solution for model Rounding\nx 4.9999999\ny 4.4\nz 0.9999999\n";
+    fs::write("gurobi_rounding.sol", content).unwrap();
+    let solution = GurobiSolver::new().read_solution(&"gurobi_rounding.sol".to_string(), Some(&problem)).unwrap();
+    assert_eq!(solution.results["x"], 5.0);
+    assert_eq!(solution.results["y"], 4.4);
+    assert_eq!(solution.results["z"], 0.9999999);
+    assert_eq!(solution.fractional_integers(&problem), vec!["y"]);
+
+    fs::write("gurobi_rounding_strict.sol", content).unwrap();
+    let solution = GurobiSolver::new()
+        .with_integer_tolerance(1e-9)
+        .read_solution(&"gurobi_rounding_strict.sol".to_string(), Some(&problem))
+        .unwrap();
+    assert_eq!(solution.results["x"], 4.9999999);
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn gurobi_params() {
+    let solver = GurobiSolver::new()
+        .with_param("Cuts", "1")
+        .with_param("Presolve", "1")
+        .with_param("Cuts", "2");
+    let arguments = solver.arguments("model.lp", "model.mst");
+    assert!(arguments.contains(&"Presolve=1".to_string()));
+    assert_eq!(solver.time_limit(), None);
+    // gurobi_cl is killed once the time limit and a grace period are exceeded
+    assert_eq!(solver.with_param("TimeLimit", "2.5").time_limit(), Some(Duration::from_millis(2500)));
+    assert!(arguments.contains(&"Cuts=2".to_string()));
+    assert!(!arguments.contains(&"Cuts=1".to_string()));
+    assert_eq!(arguments.last(), Some(&"model.lp".to_string()));
+}
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_options() {
+    let solver = GlpkSolver::new()
+        .with_temp_solution_file("out.sol".to_string())
+        .with_work_dir("")
+        .with_option("--exact".to_string())
+        .with_options(vec!["--mipgap".to_string(), "0.01".to_string()]);
+    assert_eq!(
+        solver.arguments("model.lp"),
+        vec!["--lp", "model.lp", "--exact", "--mipgap", "0.01", "-o", "out.sol"]
+    );
+    assert_eq!(solver.time_limit(), None);
+    // glpsol is killed once the time limit and a grace period are exceeded
+    let solver = solver.with_options(vec!["--tmlim".to_string(), "10".to_string()]);
+    assert_eq!(solver.time_limit(), Some(Duration::from_secs(10)));
+
+    // the sensitivity report is written in the working directory, as the solution
+    let solver = GlpkSolver::new().with_temp_solution_file("out.sol".to_string()).with_ranges("ranges.txt".to_string());
+    assert_eq!(
+        solver.arguments("model.lp")[2..4],
+        ["--ranges".to_string(), std::env::temp_dir().join("ranges.txt").display().to_string()]
+    );
+}
+
+#[cfg(feature = "gurobi")]
+#[test]
+fn sanitized_variable_names() {
+    let a = &LpContinuous::new("my var+1");
+    let b = &LpContinuous::new("my_var_1");
+    let c = &LpInteger::new("2nd");
+    let mut problem = LpProblem::new("Names", LpObjective::Maximize);
+    problem += a + b + c;
+    problem += (a + b + c).le(10);
+
+    let written = problem.to_lp_file_format();
+    assert!(!written.contains("my var+1"));
+    assert!(written.contains("c1: my_var_1_1 + my_var_1 + _2nd <= 10"));
+    assert!(written.contains("obj: my_var_1_1 + my_var_1 + _2nd\n"));
+    assert!(written.contains("Generals\n  _2nd "));
+
+    let content = "# Solution for model Names\nmy_var_1_1 3\nmy_var_1 4\n_2nd 2\n";
+    fs::write("gurobi_sanitized.sol", content).unwrap();
+    let solution = GurobiSolver::new().read_solution(&"gurobi_sanitized.sol".to_string(), Some(&problem)).unwrap();
+    assert_eq!(solution.results["my var+1"], 3.0);
+    assert_eq!(solution.results["my_var_1"], 4.0);
+    assert_eq!(solution.results["2nd"], 2.0);
+    assert_eq!(solution.results.len(), 3);
+}
+
+#[cfg(all(feature = "cbc", feature = "glpk", feature = "gurobi", feature = "scip", feature = "lpsolve"))]
+#[test]
+fn missing_solver_executable() {
+    let bogus = "no_such_solver_executable".to_string();
+    assert!(!CbcSolver::new().command_name(bogus.clone()).is_available());
+    assert!(!GurobiSolver::new().command_name(bogus.clone()).is_available());
+    assert!(!ScipSolver::new().command_name(bogus.clone()).is_available());
+    assert!(!LpSolveSolver::new().command_name(bogus.clone()).is_available());
+    let solver = GlpkSolver::new().command_name(bogus);
+    assert!(!solver.is_available());
+
+    let x = &LpContinuous::new("x");
+    let mut problem = LpProblem::new("Missing", LpObjective::Maximize);
+    problem += x;
+    problem += x.le(1);
+    match solver.run(&problem) {
+        Err(e @ lp_modeler::error::LpError::SolverNotFound(_)) => {
+            assert_eq!(e.to_string(), "Error running the Glpk solver")
+        }
+        result => panic!("expected a missing solver, got {:?}", result.map(|s| s.status)),
+    }
+    // nothing has been written
+    assert!(run_files(&std::env::temp_dir(), &problem).is_empty());
+}
+
+#[cfg(feature = "cbc")]
+#[cfg(unix)]
+#[test]
+fn solver_killed_after_time_limit() {
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, Instant};
+
+    // a solver never answering, whatever its arguments
+    let script = "never_answering_solver.sh";
+    fs::write(script, "#!/bin/sh\nsleep 60\n").unwrap();
+    fs::set_permissions(script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let x = &LpContinuous::new("x");
+    let mut problem = LpProblem::new("Killed", LpObjective::Maximize);
+    problem += x;
+    problem += x.le(1);
+
+    let solver = CbcSolver::new()
+        .command_name(format!("./{}", script))
+        .with_temp_solution_file("killed_cbc.sol".to_string())
+        .with_max_seconds(1);
+    let start = Instant::now();
+    let result = solver.run(&problem);
+    let elapsed = start.elapsed();
+    let _ = fs::remove_file(script);
+
+    match result {
+        Err(e @ lp_modeler::error::LpError::Solver(_)) => {
+            assert_eq!(e.to_string(), "The Cbc solver has been killed after exceeding its time limit")
+        }
+        result => panic!("expected a killed solver, got {:?}", result.map(|s| s.status)),
+    }
+    // killed shortly after the time limit, not after the script
+    assert!(elapsed < Duration::from_secs(10), "killed after {:?}", elapsed);
+    // the temporary files are removed
+    assert!(run_files(&std::env::temp_dir(), &problem).is_empty());
+    assert!(fs::metadata(std::env::temp_dir().join("killed_cbc.sol")).is_err());
+}
+
+#[cfg(feature = "cbc")]
+#[cfg(unix)]
+#[test]
+fn solver_work_dir() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // a solver listing the directory of the model, then writing an optimal solution
+    let script = "listing_solver.sh";
+    fs::write(
+        script,
+        "#!/bin/sh\nls \"$(dirname \"$1\")\" > work_dir_listing.txt\n\
+         printf 'Optimal - objective value 1.00000000\\n      0 x     1     0\\n' > \"$4\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(script, fs::Permissions::from_mode(0o755)).unwrap();
+    let work_dir = "solver_work_dir";
+    let _ = fs::remove_dir_all(work_dir);
+    fs::create_dir(work_dir).unwrap();
+
+    let x = &LpContinuous::new("x");
+    let mut problem = LpProblem::new("Work dir", LpObjective::Maximize);
+    problem += x;
+    problem += x.le(1);
+
+    let solver = CbcSolver::new()
+        .command_name(format!("./{}", script))
+        .with_temp_solution_file("work_dir_cbc.sol".to_string())
+        .with_work_dir(work_dir);
+    let result = solver.run(&problem);
+    let listing = fs::read_to_string("work_dir_listing.txt");
+    let _ = solver.run(&problem);
+    let second_listing = fs::read_to_string("work_dir_listing.txt");
+    let remaining = fs::read_dir(work_dir).unwrap().count();
+    let _ = fs::remove_file(script);
+    let _ = fs::remove_file("work_dir_listing.txt");
+    let _ = fs::remove_dir_all(work_dir);
+
+    assert_eq!(result.unwrap().results["x"], 1.0);
+    // the model has been written in the working directory
+    let model = listing.unwrap().trim().to_string();
+    assert!(model.starts_with(&problem.unique_name) && model.ends_with(".lp"), "{}", model);
+    // every run writes its own model
+    assert_ne!(second_listing.unwrap().trim(), model);
+    // the model and the solution are removed from it
+    assert_eq!(remaining, 0);
+}
+
+#[cfg(all(feature = "cbc", feature = "glpk"))]
+#[cfg(unix)]
+#[test]
+fn available_solver_executable() {
+    assert!(GlpkSolver::new().command_name("sh".to_string()).is_available());
+    assert!(CbcSolver::new().command_name("/bin/sh".to_string()).is_available());
+}
+
+#[cfg(feature = "glpk")]
+#[test]
+fn glpk_iis() {
+    if !available("glpsol") {
+        return;
+    }
+    let x = &LpContinuous::new("x").lower_bound(0.0);
+    let mut problem = LpProblem::new("Infeasible", LpObjective::Maximize);
+    problem += x;
+    problem += x.le(10);
+    problem += x.ge(3);
+    problem += x.le(2);
+    assert_eq!(GlpkSolver::new().compute_iis(&problem).unwrap(), vec!["c2", "c3"]);
+}